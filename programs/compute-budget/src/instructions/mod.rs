@@ -0,0 +1,107 @@
+//! Instructions of the Compute Budget program.
+//!
+//! Compute budget instructions are processed by the runtime when they are part
+//! of a transaction rather than invoked through CPIs, and they take no
+//! accounts. The builders of this module return a [`ComputeBudgetInstruction`]
+//! holding the encoded data, which provides the [`Instruction`] targeting the
+//! Compute Budget program to include in an inner transaction or in tooling.
+
+use pinocchio::instruction::Instruction;
+
+mod request_heap_frame;
+mod set_compute_unit_limit;
+mod set_compute_unit_price;
+mod set_loaded_accounts_data_size_limit;
+
+pub use request_heap_frame::*;
+pub use set_compute_unit_limit::*;
+pub use set_compute_unit_price::*;
+pub use set_loaded_accounts_data_size_limit::*;
+
+/// A Compute Budget program instruction with `N` bytes of data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeBudgetInstruction<const N: usize> {
+    data: [u8; N],
+}
+
+impl<const N: usize> ComputeBudgetInstruction<N> {
+    /// Return the instruction data.
+    #[inline(always)]
+    pub fn data(&self) -> &[u8; N] {
+        &self.data
+    }
+
+    /// Return the instruction, which targets the Compute Budget program and
+    /// borrows the data of `self`.
+    #[inline(always)]
+    pub fn instruction(&self) -> Instruction<'_, '_, '_, '_> {
+        Instruction {
+            program_id: &crate::ID,
+            accounts: &[],
+            data: &self.data,
+        }
+    }
+}
+
+/// Build a [`SetComputeUnitLimit`] instruction.
+#[inline(always)]
+pub fn set_compute_unit_limit(units: u32) -> ComputeBudgetInstruction<5> {
+    ComputeBudgetInstruction {
+        data: SetComputeUnitLimit { units }.data(),
+    }
+}
+
+/// Build a [`SetComputeUnitPrice`] instruction.
+#[inline(always)]
+pub fn set_compute_unit_price(micro_lamports: u64) -> ComputeBudgetInstruction<9> {
+    ComputeBudgetInstruction {
+        data: SetComputeUnitPrice { micro_lamports }.data(),
+    }
+}
+
+/// Build a [`RequestHeapFrame`] instruction.
+#[inline(always)]
+pub fn request_heap_frame(bytes: u32) -> ComputeBudgetInstruction<5> {
+    ComputeBudgetInstruction {
+        data: RequestHeapFrame { bytes }.data(),
+    }
+}
+
+/// Build a [`SetLoadedAccountsDataSizeLimit`] instruction.
+#[inline(always)]
+pub fn set_loaded_accounts_data_size_limit(bytes: u32) -> ComputeBudgetInstruction<5> {
+    ComputeBudgetInstruction {
+        data: SetLoadedAccountsDataSizeLimit { bytes }.data(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_instruction(instruction: &Instruction, data: &[u8]) {
+        assert_eq!(instruction.program_id, &crate::ID);
+        assert!(instruction.accounts.is_empty());
+        assert_eq!(instruction.data, data);
+    }
+
+    #[test]
+    fn test_builders() {
+        assert_instruction(
+            &set_compute_unit_limit(200_000).instruction(),
+            &[2, 64, 13, 3, 0],
+        );
+        assert_instruction(
+            &set_compute_unit_price(1_000_000).instruction(),
+            &[3, 64, 66, 15, 0, 0, 0, 0, 0],
+        );
+        assert_instruction(
+            &request_heap_frame(32_768).instruction(),
+            &[1, 0, 128, 0, 0],
+        );
+        assert_instruction(
+            &set_loaded_accounts_data_size_limit(65_536).instruction(),
+            &[4, 0, 0, 1, 0],
+        );
+    }
+}
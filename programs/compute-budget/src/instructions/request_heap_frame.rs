@@ -0,0 +1,31 @@
+/// Request a specific transaction-wide program heap region size in bytes.
+pub struct RequestHeapFrame {
+    /// Heap size in bytes; must be a multiple of 1024 up to 256KiB.
+    pub bytes: u32,
+}
+
+impl RequestHeapFrame {
+    /// Return the instruction data.
+    ///
+    /// Instruction data layout:
+    /// -  `[0]`: instruction discriminator (1 byte, u8)
+    /// -  `[1..5]`: bytes (4 bytes, u32)
+    #[inline(always)]
+    pub fn data(&self) -> [u8; 5] {
+        let mut data = [0; 5];
+        data[0] = 1;
+        data[1..].copy_from_slice(&self.bytes.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data() {
+        let instruction = RequestHeapFrame { bytes: 32_768 };
+        assert_eq!(instruction.data(), [1, 0, 128, 0, 0]);
+    }
+}
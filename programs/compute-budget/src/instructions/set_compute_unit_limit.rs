@@ -0,0 +1,32 @@
+/// Set a specific compute unit limit that the transaction is allowed to
+/// consume.
+pub struct SetComputeUnitLimit {
+    /// Compute unit limit.
+    pub units: u32,
+}
+
+impl SetComputeUnitLimit {
+    /// Return the instruction data.
+    ///
+    /// Instruction data layout:
+    /// -  `[0]`: instruction discriminator (1 byte, u8)
+    /// -  `[1..5]`: units (4 bytes, u32)
+    #[inline(always)]
+    pub fn data(&self) -> [u8; 5] {
+        let mut data = [0; 5];
+        data[0] = 2;
+        data[1..].copy_from_slice(&self.units.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data() {
+        let instruction = SetComputeUnitLimit { units: 200_000 };
+        assert_eq!(instruction.data(), [2, 64, 13, 3, 0]);
+    }
+}
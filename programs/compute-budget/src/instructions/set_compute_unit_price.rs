@@ -0,0 +1,34 @@
+/// Set a compute unit price in "micro-lamports" to pay a higher transaction
+/// fee for higher transaction prioritization.
+pub struct SetComputeUnitPrice {
+    /// Price in micro-lamports per compute unit.
+    pub micro_lamports: u64,
+}
+
+impl SetComputeUnitPrice {
+    /// Return the instruction data.
+    ///
+    /// Instruction data layout:
+    /// -  `[0]`: instruction discriminator (1 byte, u8)
+    /// -  `[1..9]`: micro_lamports (8 bytes, u64)
+    #[inline(always)]
+    pub fn data(&self) -> [u8; 9] {
+        let mut data = [0; 9];
+        data[0] = 3;
+        data[1..].copy_from_slice(&self.micro_lamports.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data() {
+        let instruction = SetComputeUnitPrice {
+            micro_lamports: 1_000_000,
+        };
+        assert_eq!(instruction.data(), [3, 64, 66, 15, 0, 0, 0, 0, 0]);
+    }
+}
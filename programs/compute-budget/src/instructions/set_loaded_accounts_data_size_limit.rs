@@ -0,0 +1,32 @@
+/// Set a specific transaction-wide account data size limit, in bytes, allowed
+/// to be loaded.
+pub struct SetLoadedAccountsDataSizeLimit {
+    /// Account data size limit in bytes.
+    pub bytes: u32,
+}
+
+impl SetLoadedAccountsDataSizeLimit {
+    /// Return the instruction data.
+    ///
+    /// Instruction data layout:
+    /// -  `[0]`: instruction discriminator (1 byte, u8)
+    /// -  `[1..5]`: bytes (4 bytes, u32)
+    #[inline(always)]
+    pub fn data(&self) -> [u8; 5] {
+        let mut data = [0; 5];
+        data[0] = 4;
+        data[1..].copy_from_slice(&self.bytes.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data() {
+        let instruction = SetLoadedAccountsDataSizeLimit { bytes: 65_536 };
+        assert_eq!(instruction.data(), [4, 0, 0, 1, 0]);
+    }
+}
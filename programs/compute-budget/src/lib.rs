@@ -0,0 +1,5 @@
+#![no_std]
+
+pub mod instructions;
+
+pinocchio_pubkey::declare_id!("ComputeBudget111111111111111111111111111111");
@@ -1,5 +1,6 @@
 #![no_std]
 
 pub mod instructions;
+pub mod pda;
 
 pinocchio_pubkey::declare_id!("11111111111111111111111111111111");
@@ -0,0 +1,228 @@
+//! Helpers to create program derived accounts.
+
+use core::mem::size_of;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    pod::Pod,
+    program_error::ProgramError,
+    pubkey::{checked_create_program_address, Pubkey, MAX_SEEDS},
+    sysvars::rent::Rent,
+    ProgramResult,
+};
+
+use crate::instructions::CreateAccount;
+
+/// Create the [program derived account][pda] `pda` with a header `init`.
+///
+/// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
+///
+/// The address is derived from `seeds` followed by the `bump` seed and the
+/// `owner` program, and must match the key of `pda`. The account is then
+/// created rent exempt with the size of the header, signed with the same
+/// seeds, and `init` is written at the start of its data. Programs usually
+/// store the `bump` in the header, so later instructions can sign for the
+/// account without searching for it again.
+///
+/// The account must not exist yet, i.e. it must not hold any lamports.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidSeeds`] if the derived address does not
+/// match `pda`, and [`ProgramError::MaxSeedLengthExceeded`] if there are too
+/// many seeds or a seed is too long.
+pub fn init_pda_account<H: Pod>(
+    payer: &AccountInfo,
+    pda: &AccountInfo,
+    seeds: &[&[u8]],
+    bump: u8,
+    owner: &Pubkey,
+    rent: &Rent,
+    init: H,
+) -> ProgramResult {
+    if seeds.len() >= MAX_SEEDS {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    let bump = [bump];
+    let mut signer_seeds: [&[u8]; MAX_SEEDS] = [&[]; MAX_SEEDS];
+    signer_seeds[..seeds.len()].copy_from_slice(seeds);
+    signer_seeds[seeds.len()] = &bump;
+    let signer_seeds = &signer_seeds[..seeds.len() + 1];
+
+    // Seeds that derive a point on the curve do not match any account either.
+    match checked_create_program_address(signer_seeds, owner) {
+        Ok(address) if pda.key_eq(&address) => (),
+        Err(ProgramError::MaxSeedLengthExceeded) => {
+            return Err(ProgramError::MaxSeedLengthExceeded)
+        }
+        _ => return Err(ProgramError::InvalidSeeds),
+    }
+
+    let space = size_of::<H>();
+    let seeds: [Seed; MAX_SEEDS] =
+        core::array::from_fn(|i| Seed::from(signer_seeds.get(i).copied().unwrap_or(&[])));
+
+    CreateAccount {
+        from: payer,
+        to: pda,
+        lamports: rent.minimum_balance(space),
+        space: space as u64,
+        owner,
+    }
+    .invoke_signed(&[Signer::from(&seeds[..signer_seeds.len()])])?;
+
+    let mut data = pda.try_borrow_mut_data()?;
+    if data.len() < space {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    // SAFETY: The data holds at least `size_of::<H>()` bytes, and `H` has no
+    // padding bytes since it is `Pod`.
+    unsafe { (data.as_mut_ptr() as *mut H).write_unaligned(init) };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use pinocchio::{
+        mock::{self, serialized_input, SerializedAccount},
+        pod::Zeroable,
+        pubkey::find_program_address,
+    };
+
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Header {
+        bump: u8,
+        kind: u8,
+        counter: [u8; 8],
+    }
+
+    unsafe impl Zeroable for Header {}
+    unsafe impl Pod for Header {}
+
+    /// Returns the input buffer holding signer and writable accounts with the
+    /// given `(key, data_len)` and their `AccountInfo`s; the buffer must
+    /// outlive the infos.
+    fn accounts<const N: usize>(accounts: [(Pubkey, usize); N]) -> (Vec<u64>, [AccountInfo; N]) {
+        let data = [0; size_of::<Header>()];
+        serialized_input(accounts.map(|(key, data_len)| SerializedAccount {
+            is_signer: true,
+            is_writable: true,
+            key,
+            data: &data[..data_len],
+            ..SerializedAccount::default()
+        }))
+    }
+
+    #[test]
+    fn test_init_pda_account() {
+        mock::reset();
+
+        let owner = [7; 32];
+        let (address, bump) = find_program_address(&[b"vault", &[1; 32]], &owner);
+        // the mock does not execute the create CPI, so the account already
+        // has room for the header
+        let (_buffer, [payer, pda]) = accounts([([1; 32], 0), (address, size_of::<Header>())]);
+
+        let rent = Rent {
+            lamports_per_byte_year: 10,
+            exemption_threshold: 2.0,
+            burn_percent: 50,
+        };
+        let header = Header {
+            bump,
+            kind: 3,
+            counter: 42u64.to_le_bytes(),
+        };
+
+        init_pda_account(
+            &payer,
+            &pda,
+            &[b"vault", &[1; 32]],
+            bump,
+            &owner,
+            &rent,
+            header,
+        )
+        .unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, crate::ID);
+        assert_eq!(instruction.accounts[0].0, [1; 32]);
+        assert_eq!(instruction.accounts[1].0, address);
+
+        let mut data = std::vec![0; 4];
+        data.extend_from_slice(&rent.minimum_balance(size_of::<Header>()).to_le_bytes());
+        data.extend_from_slice(&(size_of::<Header>() as u64).to_le_bytes());
+        data.extend_from_slice(&owner);
+        assert_eq!(instruction.data, data);
+
+        // the account signs with the seeds and the bump
+        assert_eq!(
+            invocations[0].signers_seeds,
+            [[b"vault".to_vec(), [1; 32].to_vec(), [bump].to_vec()]]
+        );
+
+        let data = pda.try_borrow_data().unwrap();
+        assert_eq!(&data[..2], [bump, 3]);
+        assert_eq!(data[2..10], 42u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_init_pda_account_invalid_seeds() {
+        mock::reset();
+
+        let owner = [7; 32];
+        let (address, bump) = find_program_address(&[b"vault"], &owner);
+        let (_buffer, [payer, pda, other]) =
+            accounts([([1; 32], 0), (address, size_of::<Header>()), ([2; 32], 0)]);
+        let header = Header::zeroed();
+
+        // the account is not the address derived from the seeds
+        assert_eq!(
+            init_pda_account(
+                &payer,
+                &other,
+                &[b"vault"],
+                bump,
+                &owner,
+                &Rent::default(),
+                header
+            ),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert_eq!(
+            init_pda_account(
+                &payer,
+                &pda,
+                &[b"other"],
+                bump,
+                &owner,
+                &Rent::default(),
+                header
+            ),
+            Err(ProgramError::InvalidSeeds)
+        );
+
+        let seeds: [&[u8]; MAX_SEEDS] = [b"vault"; MAX_SEEDS];
+        assert_eq!(
+            init_pda_account(&payer, &pda, &seeds, bump, &owner, &Rent::default(), header),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+
+        assert!(mock::invocations().is_empty());
+    }
+}
@@ -0,0 +1,46 @@
+use pinocchio::program_error::{ProgramError, ToStr};
+
+/// Errors returned by the helpers of this crate.
+///
+/// The errors are converted to [`ProgramError::Custom`] using their
+/// discriminant as the error code.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenHelperError {
+    /// The mint has no freeze authority or the provided authority does not
+    /// match it.
+    InvalidFreezeAuthority,
+
+    /// The mint holds an extension that is not supported.
+    UnsupportedMintExtension,
+}
+
+impl From<TokenHelperError> for ProgramError {
+    fn from(error: TokenHelperError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+impl TryFrom<u32> for TokenHelperError {
+    type Error = ProgramError;
+
+    fn try_from(error: u32) -> Result<Self, Self::Error> {
+        match error {
+            0 => Ok(TokenHelperError::InvalidFreezeAuthority),
+            1 => Ok(TokenHelperError::UnsupportedMintExtension),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+impl ToStr for TokenHelperError {
+    fn to_str<E>(&self) -> &'static str
+    where
+        E: 'static + ToStr + TryFrom<u32>,
+    {
+        match self {
+            TokenHelperError::InvalidFreezeAuthority => "Error: InvalidFreezeAuthority",
+            TokenHelperError::UnsupportedMintExtension => "Error: UnsupportedMintExtension",
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use pinocchio::pod::{Pod, Zeroable};
+
+use super::{get_extension_from_bytes, BaseState, Extension, ExtensionType};
+use crate::state::TokenAccount;
+
+/// Length of an ElGamal public key.
+pub const ELGAMAL_PUBKEY_LEN: usize = 32;
+
+/// Length of an ElGamal ciphertext.
+pub const ELGAMAL_CIPHERTEXT_LEN: usize = 64;
+
+/// Length of an authenticated encryption ciphertext.
+pub const AE_CIPHERTEXT_LEN: usize = 36;
+
+/// Confidential transfer extension data for token accounts.
+///
+/// Only the approval state, the credit flags and the pending balance credit
+/// counters are exposed; the encrypted balances are kept opaque.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ConfidentialTransferAccount {
+    /// Indicates whether the account has been approved by the mint authority.
+    approved: u8,
+
+    /// The public key associated with ElGamal encryption.
+    elgamal_pubkey: [u8; ELGAMAL_PUBKEY_LEN],
+
+    /// The low 16 bits of the pending balance (encrypted by
+    /// `elgamal_pubkey`).
+    pending_balance_lo: [u8; ELGAMAL_CIPHERTEXT_LEN],
+
+    /// The high 48 bits of the pending balance (encrypted by
+    /// `elgamal_pubkey`).
+    pending_balance_hi: [u8; ELGAMAL_CIPHERTEXT_LEN],
+
+    /// The available balance (encrypted by `elgamal_pubkey`).
+    available_balance: [u8; ELGAMAL_CIPHERTEXT_LEN],
+
+    /// The decryptable available balance.
+    decryptable_available_balance: [u8; AE_CIPHERTEXT_LEN],
+
+    /// Indicates whether the account accepts incoming confidential transfers.
+    allow_confidential_credits: u8,
+
+    /// Indicates whether the account accepts incoming non-confidential
+    /// transfers.
+    allow_non_confidential_credits: u8,
+
+    /// The number of incoming transfers credited to the pending balance since
+    /// the last `ApplyPendingBalance`.
+    pending_balance_credit_counter: [u8; 8],
+
+    /// The maximum number of credits to the pending balance before an
+    /// `ApplyPendingBalance` is required.
+    maximum_pending_balance_credit_counter: [u8; 8],
+
+    /// The `expected_pending_balance_credit_counter` of the last
+    /// `ApplyPendingBalance`.
+    expected_pending_balance_credit_counter: [u8; 8],
+
+    /// The pending balance credit counter when the last `ApplyPendingBalance`
+    /// was processed.
+    actual_pending_balance_credit_counter: [u8; 8],
+}
+
+unsafe impl Zeroable for ConfidentialTransferAccount {}
+unsafe impl Pod for ConfidentialTransferAccount {}
+
+impl Extension for ConfidentialTransferAccount {
+    const TYPE: ExtensionType = ExtensionType::ConfidentialTransferAccount;
+    const BASE_STATE: BaseState = BaseState::TokenAccount;
+}
+
+impl ConfidentialTransferAccount {
+    /// Return the `ConfidentialTransferAccount` extension from the given token
+    /// account bytes.
+    #[inline(always)]
+    pub fn from_bytes(account_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(account_bytes)
+    }
+
+    /// Indicates whether the account has been approved by the mint authority.
+    #[inline(always)]
+    pub fn approved(&self) -> bool {
+        self.approved != 0
+    }
+
+    /// Return the ElGamal public key of the account.
+    #[inline(always)]
+    pub fn elgamal_pubkey(&self) -> &[u8; ELGAMAL_PUBKEY_LEN] {
+        &self.elgamal_pubkey
+    }
+
+    /// Indicates whether the account accepts incoming confidential transfers.
+    #[inline(always)]
+    pub fn allow_confidential_credits(&self) -> bool {
+        self.allow_confidential_credits != 0
+    }
+
+    /// Indicates whether the account accepts incoming non-confidential
+    /// transfers.
+    #[inline(always)]
+    pub fn allow_non_confidential_credits(&self) -> bool {
+        self.allow_non_confidential_credits != 0
+    }
+
+    /// Return the number of incoming transfers credited to the pending
+    /// balance since the last `ApplyPendingBalance`.
+    #[inline(always)]
+    pub fn pending_balance_credit_counter(&self) -> u64 {
+        u64::from_le_bytes(self.pending_balance_credit_counter)
+    }
+
+    /// Return the maximum number of credits to the pending balance before an
+    /// `ApplyPendingBalance` is required.
+    #[inline(always)]
+    pub fn maximum_pending_balance_credit_counter(&self) -> u64 {
+        u64::from_le_bytes(self.maximum_pending_balance_credit_counter)
+    }
+
+    /// Return the `expected_pending_balance_credit_counter` of the last
+    /// `ApplyPendingBalance`.
+    #[inline(always)]
+    pub fn expected_pending_balance_credit_counter(&self) -> u64 {
+        u64::from_le_bytes(self.expected_pending_balance_credit_counter)
+    }
+
+    /// Return the pending balance credit counter when the last
+    /// `ApplyPendingBalance` was processed.
+    #[inline(always)]
+    pub fn actual_pending_balance_credit_counter(&self) -> u64 {
+        u64::from_le_bytes(self.actual_pending_balance_credit_counter)
+    }
+}
+
+/// Public balance of a token account, with an indication of whether it has
+/// confidential transfers pending.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EffectiveAmount {
+    /// The public `amount` of the account.
+    pub public_amount: u64,
+
+    /// Indicates whether confidential transfers were credited to the pending
+    /// balance since the last `ApplyPendingBalance`, in which case the public
+    /// amount does not reflect the full balance of the account.
+    pub has_pending_confidential: bool,
+}
+
+/// Return the effective amount of the token account `account_bytes`.
+///
+/// The pending balance is encrypted, so only its presence is detected, from
+/// the pending balance credit counter of the `ConfidentialTransferAccount`
+/// extension. Accounts without the extension have no pending balance.
+///
+/// Returns `None` if `account_bytes` is shorter than a token account.
+#[inline]
+pub fn effective_amount(account_bytes: &[u8]) -> Option<EffectiveAmount> {
+    if account_bytes.len() < TokenAccount::LEN {
+        return None;
+    }
+
+    // SAFETY: `account_bytes` holds at least `TokenAccount::LEN` bytes and
+    // `TokenAccount` has an alignment of 1.
+    let public_amount = unsafe { TokenAccount::from_bytes(account_bytes) }.amount();
+
+    let has_pending_confidential = ConfidentialTransferAccount::from_bytes(account_bytes)
+        .is_some_and(|extension| extension.pending_balance_credit_counter() != 0);
+
+    Some(EffectiveAmount {
+        public_amount,
+        has_pending_confidential,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::extensions::tests::account_with_extensions;
+
+    /// Returns the bytes of a `ConfidentialTransferAccount` with the given
+    /// pending balance credit counter.
+    fn confidential_transfer_account(pending_balance_credit_counter: u64) -> Vec<u8> {
+        let mut extension = std::vec![0; ConfidentialTransferAccount::LEN];
+        let offset = ConfidentialTransferAccount::LEN - 32;
+        extension[offset..offset + 8]
+            .copy_from_slice(&pending_balance_credit_counter.to_le_bytes());
+        extension
+    }
+
+    #[test]
+    fn test_effective_amount() {
+        let amount_offset = 64;
+
+        for (pending_balance_credit_counter, expected) in [(0, false), (2, true)] {
+            let extension = confidential_transfer_account(pending_balance_credit_counter);
+            let mut data = account_with_extensions(
+                BaseState::TokenAccount,
+                &[(ExtensionType::ConfidentialTransferAccount, &extension)],
+            );
+            data[amount_offset..amount_offset + 8].copy_from_slice(&1_000u64.to_le_bytes());
+
+            assert_eq!(
+                effective_amount(&data),
+                Some(EffectiveAmount {
+                    public_amount: 1_000,
+                    has_pending_confidential: expected,
+                })
+            );
+        }
+
+        // an account without the extension
+        let mut data = std::vec![0; TokenAccount::LEN];
+        data[amount_offset..amount_offset + 8].copy_from_slice(&5u64.to_le_bytes());
+        assert_eq!(
+            effective_amount(&data),
+            Some(EffectiveAmount {
+                public_amount: 5,
+                has_pending_confidential: false,
+            })
+        );
+
+        assert_eq!(effective_amount(&data[..TokenAccount::LEN - 1]), None);
+    }
+
+    #[test]
+    fn test_confidential_transfer_account() {
+        let mut extension = Vec::new();
+        // approved
+        extension.push(1);
+        extension.extend_from_slice(&[7; ELGAMAL_PUBKEY_LEN]);
+        // pending balance lo/hi, available and decryptable available balances
+        extension.extend_from_slice(&[0; 3 * ELGAMAL_CIPHERTEXT_LEN + AE_CIPHERTEXT_LEN]);
+        // allow confidential and non-confidential credits
+        extension.extend_from_slice(&[1, 0]);
+        for counter in [3u64, 65_536, 2, 1] {
+            extension.extend_from_slice(&counter.to_le_bytes());
+        }
+        assert_eq!(extension.len(), ConfidentialTransferAccount::LEN);
+
+        let data = account_with_extensions(
+            BaseState::TokenAccount,
+            &[(ExtensionType::ConfidentialTransferAccount, &extension)],
+        );
+        let account = ConfidentialTransferAccount::from_bytes(&data).unwrap();
+
+        assert!(account.approved());
+        assert_eq!(account.elgamal_pubkey(), &[7; ELGAMAL_PUBKEY_LEN]);
+        assert!(account.allow_confidential_credits());
+        assert!(!account.allow_non_confidential_credits());
+        assert_eq!(account.pending_balance_credit_counter(), 3);
+        assert_eq!(account.maximum_pending_balance_credit_counter(), 65_536);
+        assert_eq!(account.expected_pending_balance_credit_counter(), 2);
+        assert_eq!(account.actual_pending_balance_credit_counter(), 1);
+
+        // the extension is only read from token accounts
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::ConfidentialTransferAccount, &extension)],
+        );
+        assert!(ConfidentialTransferAccount::from_bytes(&data).is_none());
+    }
+}
@@ -0,0 +1,275 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    pod::{Pod, Zeroable},
+    ProgramResult,
+};
+
+use super::{get_extension_from_bytes, BaseState, Extension, ExtensionType};
+use crate::{state::AccountState, TOKEN_2022_ID};
+
+/// Instruction discriminator of the `DefaultAccountState` extension.
+const DEFAULT_ACCOUNT_STATE_EXTENSION: u8 = 28;
+
+/// Default account state extension data for mints.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DefaultAccountState {
+    /// Default state of new token accounts.
+    state: u8,
+}
+
+unsafe impl Zeroable for DefaultAccountState {}
+unsafe impl Pod for DefaultAccountState {}
+
+impl Extension for DefaultAccountState {
+    const TYPE: ExtensionType = ExtensionType::DefaultAccountState;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl DefaultAccountState {
+    /// Return the `DefaultAccountState` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the default state of new token accounts.
+    ///
+    /// Returns `None` if the stored value is not a valid account state.
+    #[inline]
+    pub fn state(&self) -> Option<AccountState> {
+        match self.state {
+            0 => Some(AccountState::Uninitialized),
+            1 => Some(AccountState::Initialized),
+            2 => Some(AccountState::Frozen),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the state of a new token account of the mint once initialized.
+///
+/// This is the state configured by the `DefaultAccountState` extension, or
+/// `Initialized` if the mint does not have the extension. A stored value that
+/// is not a valid account state is treated as if the extension was absent.
+#[inline]
+pub fn default_new_account_state(mint_bytes: &[u8]) -> AccountState {
+    DefaultAccountState::from_bytes(mint_bytes)
+        .and_then(DefaultAccountState::state)
+        .unwrap_or(AccountState::Initialized)
+}
+
+/// Initialize the default state of new token accounts of a mint.
+///
+/// This instruction must be executed before the mint is initialized.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint to initialize.
+pub struct InitializeDefaultAccountState<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Default state of new token accounts.
+    pub state: AccountState,
+}
+
+impl InitializeDefaultAccountState<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 1] = [AccountMeta::writable(self.mint.key())];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &instruction_data(0, self.state),
+        };
+
+        invoke_signed(&instruction, &[self.mint], signers)
+    }
+}
+
+/// Update the default state of new token accounts of a mint.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint.
+///   1. `[SIGNER]` The mint freeze authority.
+pub struct UpdateDefaultAccountState<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Mint Freeze Authority Account.
+    pub freeze_authority: &'a AccountInfo,
+    /// Default state of new token accounts.
+    pub state: AccountState,
+}
+
+impl UpdateDefaultAccountState<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 2] = [
+            AccountMeta::writable(self.mint.key()),
+            AccountMeta::readonly_signer(self.freeze_authority.key()),
+        ];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &instruction_data(1, self.state),
+        };
+
+        invoke_signed(&instruction, &[self.mint, self.freeze_authority], signers)
+    }
+}
+
+/// Instruction data layout:
+/// -  [0]: extension instruction discriminator (1 byte, u8)
+/// -  [1]: `DefaultAccountState` instruction discriminator (1 byte, u8)
+/// -  [2]: account state (1 byte, u8)
+#[inline(always)]
+fn instruction_data(discriminator: u8, state: AccountState) -> [u8; 3] {
+    [DEFAULT_ACCOUNT_STATE_EXTENSION, discriminator, state.into()]
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use pinocchio::mock;
+
+    use super::*;
+    use crate::{extensions::tests::account_with_extensions, state::Mint, test_utils::account};
+
+    #[test]
+    fn test_default_new_account_state() {
+        // a mint with the extension set to frozen
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::DefaultAccountState, &[2])],
+        );
+        assert_eq!(
+            DefaultAccountState::from_bytes(&mint).and_then(DefaultAccountState::state),
+            Some(AccountState::Frozen)
+        );
+        assert_eq!(default_new_account_state(&mint), AccountState::Frozen);
+
+        // a mint with the extension set to initialized
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::DefaultAccountState, &[1])],
+        );
+        assert_eq!(default_new_account_state(&mint), AccountState::Initialized);
+
+        // mints without the extension
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::MintCloseAuthority, &[3; 32])],
+        );
+        assert!(DefaultAccountState::from_bytes(&mint).is_none());
+        assert_eq!(default_new_account_state(&mint), AccountState::Initialized);
+        assert_eq!(
+            default_new_account_state(&[0; Mint::LEN]),
+            AccountState::Initialized
+        );
+
+        // an invalid stored state
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::DefaultAccountState, &[3])],
+        );
+        assert!(DefaultAccountState::from_bytes(&mint)
+            .unwrap()
+            .state()
+            .is_none());
+        assert_eq!(default_new_account_state(&mint), AccountState::Initialized);
+    }
+
+    #[test]
+    fn test_initialize_instruction_data() {
+        assert_eq!(instruction_data(0, AccountState::Frozen), [28, 0, 2]);
+        assert_eq!(instruction_data(0, AccountState::Initialized), [28, 0, 1]);
+    }
+
+    #[test]
+    fn test_update_instruction_data() {
+        assert_eq!(instruction_data(1, AccountState::Frozen), [28, 1, 2]);
+        assert_eq!(instruction_data(1, AccountState::Initialized), [28, 1, 1]);
+    }
+
+    #[test]
+    fn test_initialize_default_account_state() {
+        mock::reset();
+
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+
+        InitializeDefaultAccountState {
+            mint: &mint.info,
+            state: AccountState::Frozen,
+        }
+        .invoke()
+        .unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(instruction.data, [28, 0, 2]);
+        assert_eq!(instruction.accounts.len(), 1);
+
+        let (key, flags) = instruction.accounts[0];
+        assert_eq!(key, [1; 32]);
+        assert!(flags.writable() && !flags.signer());
+        assert_eq!(invocations[0].accounts.len(), 1);
+    }
+
+    #[test]
+    fn test_update_default_account_state() {
+        mock::reset();
+
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let freeze_authority = account([2; 32], [0; 32], &[]);
+
+        UpdateDefaultAccountState {
+            mint: &mint.info,
+            freeze_authority: &freeze_authority.info,
+            state: AccountState::Initialized,
+        }
+        .invoke()
+        .unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(instruction.data, [28, 1, 1]);
+        assert_eq!(instruction.accounts.len(), 2);
+
+        let (key, flags) = instruction.accounts[0];
+        assert_eq!(key, [1; 32]);
+        assert!(flags.writable() && !flags.signer());
+
+        let (key, flags) = instruction.accounts[1];
+        assert_eq!(key, [2; 32]);
+        assert!(!flags.writable() && flags.signer());
+
+        let keys: Vec<_> = invocations[0]
+            .accounts
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        assert_eq!(keys, [[1; 32], [2; 32]]);
+    }
+}
@@ -0,0 +1,289 @@
+use pinocchio::{
+    pod::{Pod, Zeroable},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+};
+
+use super::{get_extension_from_bytes, BaseState, Extension, ExtensionType};
+
+/// Number of seconds in a year, as used by the Token-2022 program.
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+
+/// Number of basis points in one.
+const ONE_IN_BASIS_POINTS: f64 = 10_000.0;
+
+/// Interest-bearing extension data for mints.
+///
+/// Tokens accrue interest continuously, compounded at the average rate before
+/// the last rate update and at the current rate since then.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct InterestBearingConfig {
+    /// Authority that can set the interest rate.
+    ///
+    /// A zeroed pubkey represents `None`.
+    rate_authority: Pubkey,
+
+    /// Timestamp of the initialization of the interest-bearing mint.
+    initialization_timestamp: [u8; 8],
+
+    /// Average rate from the initialization until the last rate update, in
+    /// basis points.
+    pre_update_average_rate: [u8; 2],
+
+    /// Timestamp of the last rate update.
+    last_update_timestamp: [u8; 8],
+
+    /// Current rate since the last update, in basis points.
+    current_rate: [u8; 2],
+}
+
+unsafe impl Zeroable for InterestBearingConfig {}
+unsafe impl Pod for InterestBearingConfig {}
+
+impl Extension for InterestBearingConfig {
+    const TYPE: ExtensionType = ExtensionType::InterestBearingConfig;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl InterestBearingConfig {
+    /// Return the `InterestBearingConfig` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the rate authority, if any.
+    #[inline]
+    pub fn rate_authority(&self) -> Option<&Pubkey> {
+        if self.rate_authority == Pubkey::default() {
+            None
+        } else {
+            Some(&self.rate_authority)
+        }
+    }
+
+    /// Return the timestamp of the initialization of the mint.
+    #[inline(always)]
+    pub fn initialization_timestamp(&self) -> i64 {
+        i64::from_le_bytes(self.initialization_timestamp)
+    }
+
+    /// Return the average rate before the last rate update, in basis points.
+    #[inline(always)]
+    pub fn pre_update_average_rate(&self) -> i16 {
+        i16::from_le_bytes(self.pre_update_average_rate)
+    }
+
+    /// Return the timestamp of the last rate update.
+    #[inline(always)]
+    pub fn last_update_timestamp(&self) -> i64 {
+        i64::from_le_bytes(self.last_update_timestamp)
+    }
+
+    /// Return the current rate, in basis points.
+    #[inline(always)]
+    pub fn current_rate(&self) -> i16 {
+        i16::from_le_bytes(self.current_rate)
+    }
+
+    /// Convert `amount` of a mint with `decimals` decimals to a UI amount
+    /// including the interest accrued until `unix_timestamp`.
+    ///
+    /// Returns `None` if the timestamps overflow.
+    pub fn amount_to_ui_amount(
+        &self,
+        amount: u64,
+        decimals: u8,
+        unix_timestamp: i64,
+    ) -> Option<f64> {
+        let pre_update_timespan = self
+            .last_update_timestamp()
+            .checked_sub(self.initialization_timestamp())?;
+        let post_update_timespan = unix_timestamp.checked_sub(self.last_update_timestamp())?;
+
+        let pre_update_exp = exp(self.pre_update_average_rate() as f64
+            * pre_update_timespan as f64
+            / SECONDS_PER_YEAR
+            / ONE_IN_BASIS_POINTS);
+        let post_update_exp = exp(self.current_rate() as f64 * post_update_timespan as f64
+            / SECONDS_PER_YEAR
+            / ONE_IN_BASIS_POINTS);
+
+        let mut scale = 1.0;
+        for _ in 0..decimals {
+            scale *= 10.0;
+        }
+
+        Some(amount as f64 * pre_update_exp * post_update_exp / scale)
+    }
+
+    /// Convert `amount` of a mint with `decimals` decimals to a UI amount
+    /// including the interest accrued until the current `Clock` timestamp.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of reading the `Clock` sysvar, or
+    /// [`ProgramError::ArithmeticOverflow`] if the timestamps overflow.
+    #[inline]
+    pub fn current_ui_amount(&self, amount: u64, decimals: u8) -> Result<f64, ProgramError> {
+        let unix_timestamp = Clock::get()?.unix_timestamp;
+
+        self.amount_to_ui_amount(amount, decimals, unix_timestamp)
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+/// Compute `e^x` without the float functions of `std`.
+///
+/// `x` is reduced to `k * ln(2) + r`, with `|r| <= ln(2) / 2`, so that
+/// `e^x = 2^k * e^r` where `e^r` is computed with its Taylor series.
+fn exp(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    // outside of these bounds, e^x overflows or underflows an f64
+    if x > 709.8 {
+        return f64::INFINITY;
+    }
+    if x < -745.2 {
+        return 0.0;
+    }
+
+    // ln(2) split in a high part with trailing zero bits, so `k * LN2_HI` is
+    // exact, and the remaining low part
+    const LN2_HI: f64 = f64::from_bits(0x3fe6_2e42_fee0_0000);
+    const LN2_LO: f64 = f64::from_bits(0x3dea_39ef_3579_3c76);
+
+    let half = if x < 0.0 { -0.5 } else { 0.5 };
+    let k = (x / core::f64::consts::LN_2 + half) as i32;
+    let r = (x - k as f64 * LN2_HI) - k as f64 * LN2_LO;
+
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..=20 {
+        term *= r / n as f64;
+        sum += term;
+    }
+
+    // 2^k is applied in two steps since it may not be a normal f64
+    let pow2 = |k: i32| f64::from_bits(((k + 1023) as u64) << 52);
+    sum * pow2(k / 2) * pow2(k - k / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use pinocchio::mock;
+
+    use super::*;
+    use crate::extensions::tests::account_with_extensions;
+
+    /// Returns the bytes of an `InterestBearingConfig` extension.
+    fn interest_bearing_config(
+        initialization_timestamp: i64,
+        pre_update_average_rate: i16,
+        last_update_timestamp: i64,
+        current_rate: i16,
+    ) -> Vec<u8> {
+        let mut extension = Vec::new();
+        extension.extend_from_slice(&[5; 32]);
+        extension.extend_from_slice(&initialization_timestamp.to_le_bytes());
+        extension.extend_from_slice(&pre_update_average_rate.to_le_bytes());
+        extension.extend_from_slice(&last_update_timestamp.to_le_bytes());
+        extension.extend_from_slice(&current_rate.to_le_bytes());
+        extension
+    }
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!(
+            (value - expected).abs() <= expected.abs() * 1e-14,
+            "{value} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_exp() {
+        for (x, expected) in [
+            (0.0, 1.0),
+            (1.0, core::f64::consts::E),
+            (0.05, 1.0512710963760241),
+            (-0.05, 0.951229424500714),
+            (10.0, 22026.465794806718),
+            (-10.0, 4.5399929762484854e-5),
+            (700.0, 1.0142320547350045e304),
+            (-700.0, 9.85967654375977e-305),
+        ] {
+            assert_close(exp(x), expected);
+        }
+
+        assert_eq!(exp(710.0), f64::INFINITY);
+        assert_eq!(exp(-750.0), 0.0);
+        assert!(exp(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_interest_bearing_config() {
+        let year = SECONDS_PER_YEAR as i64;
+        let extension = interest_bearing_config(0, 500, year, -500);
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::InterestBearingConfig, &extension)],
+        );
+        let config = InterestBearingConfig::from_bytes(&data).unwrap();
+
+        assert_eq!(config.rate_authority(), Some(&[5; 32]));
+        assert_eq!(config.initialization_timestamp(), 0);
+        assert_eq!(config.pre_update_average_rate(), 500);
+        assert_eq!(config.last_update_timestamp(), year);
+        assert_eq!(config.current_rate(), -500);
+
+        // 5% for a year
+        assert_close(
+            config.amount_to_ui_amount(1_000_000, 6, year).unwrap(),
+            1.0512710963760241,
+        );
+        // followed by -5% for a year
+        assert_close(
+            config.amount_to_ui_amount(1_000, 0, 2 * year).unwrap(),
+            1_000.0,
+        );
+        assert!(config.amount_to_ui_amount(1, 0, i64::MIN).is_none());
+    }
+
+    #[test]
+    fn test_current_ui_amount() {
+        let year = SECONDS_PER_YEAR as i64;
+        let extension = interest_bearing_config(0, 0, 0, 500);
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::InterestBearingConfig, &extension)],
+        );
+        let config = InterestBearingConfig::from_bytes(&data).unwrap();
+
+        mock::reset();
+        assert_eq!(
+            config.current_ui_amount(1_000_000, 6),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+
+        mock::set_clock(Clock {
+            unix_timestamp: year,
+            ..Clock::default()
+        });
+        assert_close(
+            config.current_ui_amount(1_000_000, 6).unwrap(),
+            config.amount_to_ui_amount(1_000_000, 6, year).unwrap(),
+        );
+        assert_close(
+            config.current_ui_amount(1_000_000, 6).unwrap(),
+            1.0512710963760241,
+        );
+
+        mock::reset();
+    }
+}
@@ -0,0 +1,170 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    ProgramResult,
+};
+
+use crate::TOKEN_2022_ID;
+
+/// Instruction discriminator of the `MemoTransfer` extension.
+const MEMO_TRANSFER_EXTENSION: u8 = 30;
+
+/// Require memos for transfers into this token account.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The account to update.
+///   1. `[SIGNER]` The account's owner.
+pub struct EnableRequiredMemoTransfers<'a> {
+    /// Token Account.
+    pub account: &'a AccountInfo,
+    /// Owner Account.
+    pub owner: &'a AccountInfo,
+}
+
+impl EnableRequiredMemoTransfers<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_memo_transfer(self.account, self.owner, 0, signers)
+    }
+}
+
+/// Stop requiring memos for transfers into this token account.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The account to update.
+///   1. `[SIGNER]` The account's owner.
+pub struct DisableRequiredMemoTransfers<'a> {
+    /// Token Account.
+    pub account: &'a AccountInfo,
+    /// Owner Account.
+    pub owner: &'a AccountInfo,
+}
+
+impl DisableRequiredMemoTransfers<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_memo_transfer(self.account, self.owner, 1, signers)
+    }
+}
+
+#[inline(always)]
+fn invoke_memo_transfer(
+    account: &AccountInfo,
+    owner: &AccountInfo,
+    discriminator: u8,
+    signers: &[Signer],
+) -> ProgramResult {
+    // account metadata
+    let account_metas: [AccountMeta; 2] = [
+        AccountMeta::writable(account.key()),
+        AccountMeta::readonly_signer(owner.key()),
+    ];
+
+    let instruction = Instruction {
+        program_id: &TOKEN_2022_ID,
+        accounts: &account_metas,
+        data: &instruction_data(discriminator),
+    };
+
+    invoke_signed(&instruction, &[account, owner], signers)
+}
+
+/// Instruction data layout:
+/// -  [0]: extension instruction discriminator (1 byte, u8)
+/// -  [1]: `MemoTransfer` instruction discriminator (1 byte, u8)
+#[inline(always)]
+fn instruction_data(discriminator: u8) -> [u8; 2] {
+    [MEMO_TRANSFER_EXTENSION, discriminator]
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use pinocchio::mock;
+
+    use super::*;
+    use crate::test_utils::account;
+
+    /// Checks the single invocation recorded by the mock against the
+    /// `MemoTransfer` instruction with the given discriminator.
+    fn assert_memo_transfer_invocation(discriminator: u8) {
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(instruction.data, [30, discriminator]);
+        assert_eq!(instruction.accounts.len(), 2);
+
+        let (key, flags) = instruction.accounts[0];
+        assert_eq!(key, [1; 32]);
+        assert!(flags.writable() && !flags.signer());
+
+        let (key, flags) = instruction.accounts[1];
+        assert_eq!(key, [2; 32]);
+        assert!(!flags.writable() && flags.signer());
+
+        let keys: Vec<_> = invocations[0]
+            .accounts
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        assert_eq!(keys, [[1; 32], [2; 32]]);
+    }
+
+    #[test]
+    fn test_enable_instruction_data() {
+        assert_eq!(instruction_data(0), [30, 0]);
+    }
+
+    #[test]
+    fn test_disable_instruction_data() {
+        assert_eq!(instruction_data(1), [30, 1]);
+    }
+
+    #[test]
+    fn test_enable_required_memo_transfers() {
+        mock::reset();
+
+        let token_account = account([1; 32], TOKEN_2022_ID, &[]);
+        let owner = account([2; 32], [0; 32], &[]);
+
+        EnableRequiredMemoTransfers {
+            account: &token_account.info,
+            owner: &owner.info,
+        }
+        .invoke()
+        .unwrap();
+
+        assert_memo_transfer_invocation(0);
+    }
+
+    #[test]
+    fn test_disable_required_memo_transfers() {
+        mock::reset();
+
+        let token_account = account([1; 32], TOKEN_2022_ID, &[]);
+        let owner = account([2; 32], [0; 32], &[]);
+
+        DisableRequiredMemoTransfers {
+            account: &token_account.info,
+            owner: &owner.info,
+        }
+        .invoke()
+        .unwrap();
+
+        assert_memo_transfer_invocation(1);
+    }
+}
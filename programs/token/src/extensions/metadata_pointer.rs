@@ -0,0 +1,381 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    pod::{Pod, Zeroable},
+    program_error::ProgramError,
+    pubkey::{pubkey_eq, Pubkey},
+    ProgramResult,
+};
+
+use super::{
+    get_extension_from_bytes, token_metadata::TokenMetadata, BaseState, Extension, ExtensionType,
+};
+use crate::TOKEN_2022_ID;
+
+/// Instruction discriminator of the `MetadataPointer` extension.
+const METADATA_POINTER_EXTENSION: u8 = 39;
+
+/// Metadata pointer extension data for mints.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MetadataPointer {
+    /// Authority that can set the metadata address.
+    ///
+    /// A zeroed pubkey represents `None`.
+    authority: Pubkey,
+
+    /// Account address that holds the metadata.
+    ///
+    /// A zeroed pubkey represents `None`.
+    metadata_address: Pubkey,
+}
+
+unsafe impl Zeroable for MetadataPointer {}
+unsafe impl Pod for MetadataPointer {}
+
+impl Extension for MetadataPointer {
+    const TYPE: ExtensionType = ExtensionType::MetadataPointer;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl MetadataPointer {
+    /// Return the `MetadataPointer` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the authority that can set the metadata address, if any.
+    #[inline]
+    pub fn authority(&self) -> Option<&Pubkey> {
+        if self.authority == Pubkey::default() {
+            None
+        } else {
+            Some(&self.authority)
+        }
+    }
+
+    /// Return the account address that holds the metadata, if any.
+    #[inline]
+    pub fn metadata_address(&self) -> Option<&Pubkey> {
+        if self.metadata_address == Pubkey::default() {
+            None
+        } else {
+            Some(&self.metadata_address)
+        }
+    }
+}
+
+/// Validates that the metadata pointer of the mint `mint_key` points to its
+/// metadata.
+///
+/// The mint must have a `MetadataPointer` extension with a metadata address.
+/// When the address is the mint itself, the mint must also hold a
+/// `TokenMetadata` extension for the same mint. An address of another account
+/// is accepted, since its metadata is not part of the mint data; the caller
+/// should then validate that account separately.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidAccountData`] if the metadata pointer is
+/// missing or not set, or if a self-referential mint has no valid
+/// `TokenMetadata` for itself.
+pub fn validate_metadata_consistency(
+    mint_key: &Pubkey,
+    mint_bytes: &[u8],
+) -> Result<(), ProgramError> {
+    let metadata_address = MetadataPointer::from_bytes(mint_bytes)
+        .and_then(MetadataPointer::metadata_address)
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    if !pubkey_eq(metadata_address, mint_key) {
+        return Ok(());
+    }
+
+    match TokenMetadata::from_bytes(mint_bytes) {
+        Some(metadata) if pubkey_eq(metadata.mint(), mint_key) => Ok(()),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Initialize the metadata pointer on a new mint.
+///
+/// This instruction must be executed before the mint is initialized.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint to initialize.
+pub struct InitializeMetadataPointer<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Authority that can set the metadata address.
+    pub authority: Option<&'a Pubkey>,
+    /// Account address that holds the metadata.
+    pub metadata_address: Option<&'a Pubkey>,
+}
+
+impl InitializeMetadataPointer<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 1] = [AccountMeta::writable(self.mint.key())];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &self.instruction_data(),
+        };
+
+        invoke_signed(&instruction, &[self.mint], signers)
+    }
+
+    // Instruction data layout:
+    // -  [0]: extension instruction discriminator (1 byte, u8)
+    // -  [1]: `MetadataPointer` instruction discriminator (1 byte, u8)
+    // -  [2..34]: authority, zeroed if `None` (32 bytes, Pubkey)
+    // -  [34..66]: metadata_address, zeroed if `None` (32 bytes, Pubkey)
+    #[inline(always)]
+    fn instruction_data(&self) -> [u8; 66] {
+        let mut data = [0; 66];
+        data[..2].copy_from_slice(&[METADATA_POINTER_EXTENSION, 0]);
+        if let Some(authority) = self.authority {
+            data[2..34].copy_from_slice(authority);
+        }
+        if let Some(metadata_address) = self.metadata_address {
+            data[34..].copy_from_slice(metadata_address);
+        }
+        data
+    }
+}
+
+/// Update the metadata address. Only supported for mints that include the
+/// `MetadataPointer` extension.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint.
+///   1. `[SIGNER]` The metadata pointer authority.
+pub struct UpdateMetadataPointer<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Metadata Pointer Authority.
+    pub authority: &'a AccountInfo,
+    /// New account address that holds the metadata.
+    pub metadata_address: Option<&'a Pubkey>,
+}
+
+impl UpdateMetadataPointer<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 2] = [
+            AccountMeta::writable(self.mint.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &self.instruction_data(),
+        };
+
+        invoke_signed(&instruction, &[self.mint, self.authority], signers)
+    }
+
+    // Instruction data layout:
+    // -  [0]: extension instruction discriminator (1 byte, u8)
+    // -  [1]: `MetadataPointer` instruction discriminator (1 byte, u8)
+    // -  [2..34]: metadata_address, zeroed if `None` (32 bytes, Pubkey)
+    #[inline(always)]
+    fn instruction_data(&self) -> [u8; 34] {
+        let mut data = [0; 34];
+        data[..2].copy_from_slice(&[METADATA_POINTER_EXTENSION, 1]);
+        if let Some(metadata_address) = self.metadata_address {
+            data[2..].copy_from_slice(metadata_address);
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::{extensions::tests::account_with_extensions, test_utils::account};
+
+    /// Returns the value of a `TokenMetadata` extension for `mint` with empty
+    /// strings.
+    fn token_metadata(mint: &Pubkey) -> Vec<u8> {
+        let mut value = [9; 32].to_vec();
+        value.extend_from_slice(mint);
+        // name, symbol, uri and the number of additional fields
+        value.extend_from_slice(&[0; 16]);
+        value
+    }
+
+    /// Returns the value of a `MetadataPointer` extension.
+    fn metadata_pointer(authority: &Pubkey, metadata_address: &Pubkey) -> Vec<u8> {
+        [authority.as_slice(), metadata_address].concat()
+    }
+
+    #[test]
+    fn test_metadata_pointer() {
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(
+                ExtensionType::MetadataPointer,
+                &metadata_pointer(&[2; 32], &[3; 32]),
+            )],
+        );
+        let pointer = MetadataPointer::from_bytes(&mint).unwrap();
+        assert_eq!(pointer.authority(), Some(&[2; 32]));
+        assert_eq!(pointer.metadata_address(), Some(&[3; 32]));
+
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::MetadataPointer, &[0; 64])],
+        );
+        let pointer = MetadataPointer::from_bytes(&mint).unwrap();
+        assert!(pointer.authority().is_none());
+        assert!(pointer.metadata_address().is_none());
+    }
+
+    #[test]
+    fn test_validate_metadata_consistency() {
+        let mint_key = [1; 32];
+
+        // a consistent self-referential mint
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (
+                    ExtensionType::MetadataPointer,
+                    &metadata_pointer(&[2; 32], &mint_key),
+                ),
+                (ExtensionType::TokenMetadata, &token_metadata(&mint_key)),
+            ],
+        );
+        assert_eq!(validate_metadata_consistency(&mint_key, &mint), Ok(()));
+
+        // the metadata is stored in another account
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(
+                ExtensionType::MetadataPointer,
+                &metadata_pointer(&[2; 32], &[5; 32]),
+            )],
+        );
+        assert_eq!(validate_metadata_consistency(&mint_key, &mint), Ok(()));
+
+        // a self-referential mint without metadata
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(
+                ExtensionType::MetadataPointer,
+                &metadata_pointer(&[2; 32], &mint_key),
+            )],
+        );
+        assert_eq!(
+            validate_metadata_consistency(&mint_key, &mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // a self-referential mint with the metadata of another mint
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (
+                    ExtensionType::MetadataPointer,
+                    &metadata_pointer(&[2; 32], &mint_key),
+                ),
+                (ExtensionType::TokenMetadata, &token_metadata(&[4; 32])),
+            ],
+        );
+        assert_eq!(
+            validate_metadata_consistency(&mint_key, &mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // a pointer without a metadata address
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (
+                    ExtensionType::MetadataPointer,
+                    &metadata_pointer(&[2; 32], &[0; 32]),
+                ),
+                (ExtensionType::TokenMetadata, &token_metadata(&mint_key)),
+            ],
+        );
+        assert_eq!(
+            validate_metadata_consistency(&mint_key, &mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // a mint without a pointer
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TokenMetadata, &token_metadata(&mint_key))],
+        );
+        assert_eq!(
+            validate_metadata_consistency(&mint_key, &mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_initialize_metadata_pointer() {
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = [2; 32];
+        let metadata_address = [3; 32];
+
+        for (authority, metadata_address) in [
+            (Some(&authority), Some(&metadata_address)),
+            (Some(&authority), None),
+            (None, Some(&metadata_address)),
+            (None, None),
+        ] {
+            let instruction = InitializeMetadataPointer {
+                mint: &mint.info,
+                authority,
+                metadata_address,
+            };
+
+            let mut expected = std::vec![39, 0];
+            expected.extend_from_slice(authority.unwrap_or(&[0; 32]));
+            expected.extend_from_slice(metadata_address.unwrap_or(&[0; 32]));
+
+            assert_eq!(instruction.instruction_data().as_slice(), expected);
+        }
+    }
+
+    #[test]
+    fn test_update_metadata_pointer() {
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = account([2; 32], Pubkey::default(), &[]);
+        let metadata_address = [3; 32];
+
+        for metadata_address in [Some(&metadata_address), None] {
+            let instruction = UpdateMetadataPointer {
+                mint: &mint.info,
+                authority: &authority.info,
+                metadata_address,
+            };
+
+            let mut expected = std::vec![39, 1];
+            expected.extend_from_slice(metadata_address.unwrap_or(&[0; 32]));
+
+            assert_eq!(instruction.instruction_data().as_slice(), expected);
+        }
+    }
+}
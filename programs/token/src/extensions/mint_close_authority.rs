@@ -0,0 +1,103 @@
+use pinocchio::{
+    pod::{Pod, Zeroable},
+    pubkey::Pubkey,
+};
+
+use super::{get_extension_from_bytes, BaseState, Extension, ExtensionType};
+
+/// Close authority extension data for mints.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct MintCloseAuthority {
+    /// Optional authority to close the mint.
+    ///
+    /// A zeroed pubkey represents `None`.
+    close_authority: Pubkey,
+}
+
+unsafe impl Zeroable for MintCloseAuthority {}
+unsafe impl Pod for MintCloseAuthority {}
+
+impl Extension for MintCloseAuthority {
+    const TYPE: ExtensionType = ExtensionType::MintCloseAuthority;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl MintCloseAuthority {
+    /// Return the `MintCloseAuthority` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the close authority, if any.
+    #[inline]
+    pub fn close_authority(&self) -> Option<&Pubkey> {
+        if self.close_authority == Pubkey::default() {
+            None
+        } else {
+            Some(&self.close_authority)
+        }
+    }
+}
+
+/// Checks whether `key` is the close authority of the mint.
+///
+/// Returns `false` if the mint does not have the `MintCloseAuthority` extension
+/// or the close authority is not set, since the mint cannot be closed in this
+/// case.
+#[inline]
+pub fn is_close_authority(mint_bytes: &[u8], key: &Pubkey) -> bool {
+    MintCloseAuthority::from_bytes(mint_bytes)
+        .and_then(MintCloseAuthority::close_authority)
+        .is_some_and(|close_authority| close_authority == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extensions::tests::account_with_extensions;
+
+    #[test]
+    fn test_close_authority() {
+        let close_authority = [3u8; 32];
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (ExtensionType::MintCloseAuthority, &close_authority),
+                (ExtensionType::PermanentDelegate, &[7; 32]),
+            ],
+        );
+
+        let extension = MintCloseAuthority::from_bytes(&mint).unwrap();
+
+        assert_eq!(extension.close_authority(), Some(&close_authority));
+        assert!(is_close_authority(&mint, &close_authority));
+        assert!(!is_close_authority(&mint, &[7; 32]));
+    }
+
+    #[test]
+    fn test_close_authority_not_set() {
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::MintCloseAuthority, &[0; 32])],
+        );
+
+        assert!(MintCloseAuthority::from_bytes(&mint)
+            .unwrap()
+            .close_authority()
+            .is_none());
+        assert!(!is_close_authority(&mint, &Pubkey::default()));
+    }
+
+    #[test]
+    fn test_without_close_authority() {
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[7; 32])],
+        );
+
+        assert!(MintCloseAuthority::from_bytes(&mint).is_none());
+        assert!(!is_close_authority(&mint, &[7; 32]));
+    }
+}
@@ -0,0 +1,1118 @@
+//! Token-2022 extensions.
+//!
+//! Token-2022 accounts store extensions in a TLV (type-length-value) region
+//! following the base state. Mints are padded to the size of a token account,
+//! so the account type byte and the TLV region start at the same offset for
+//! both account types.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    pod::{cast_ref, Pod},
+    program_error::ProgramError,
+};
+
+use crate::{
+    error::TokenHelperError,
+    state::{Mint, TokenAccount},
+    TOKEN_2022_ID,
+};
+
+pub mod confidential_transfer;
+pub mod default_account_state;
+pub mod interest_bearing;
+pub mod memo_transfer;
+pub mod metadata_pointer;
+pub mod mint_close_authority;
+pub mod permanent_delegate;
+pub mod token_metadata;
+pub mod transfer_fee;
+pub mod transfer_hook;
+
+/// Length of the extension type field of a TLV entry.
+pub const EXTENSION_TYPE_LEN: usize = 2;
+
+/// Length of the extension length field of a TLV entry.
+pub const EXTENSION_LENGTH_LEN: usize = 2;
+
+/// Length of the account type byte that precedes the TLV region.
+pub const EXTENSION_START_OFFSET: usize = 1;
+
+/// Number of padding bytes between the `Mint` base state and the account
+/// type byte.
+pub const MINT_PADDING_LEN: usize = TokenAccount::LEN - Mint::LEN;
+
+/// Offset of the account type byte, shared by mints and token accounts.
+pub const ACCOUNT_TYPE_OFFSET: usize = TokenAccount::LEN;
+
+/// Maximum number of account extensions required by the extensions of a mint.
+///
+/// See [`ExtensionType::get_required_init_account_extensions`].
+pub const MAX_REQUIRED_ACCOUNT_EXTENSIONS: usize = 5;
+
+/// Length of a multisig account, which accounts with extensions must not have.
+const MULTISIG_LEN: usize = 355;
+
+/// Extension types supported by the Token-2022 program.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtensionType {
+    /// Used as padding if the account size would otherwise be 355, same as a
+    /// multisig.
+    Uninitialized,
+    /// Includes transfer fee rate info and accompanying authorities to withdraw
+    /// and set the fee.
+    TransferFeeConfig,
+    /// Includes withheld transfer fees.
+    TransferFeeAmount,
+    /// Includes an optional mint close authority.
+    MintCloseAuthority,
+    /// Auditor configuration for confidential transfers.
+    ConfidentialTransferMint,
+    /// State for confidential transfers.
+    ConfidentialTransferAccount,
+    /// Specifies the default account state for new accounts.
+    DefaultAccountState,
+    /// Indicates that the account owner authority cannot be changed.
+    ImmutableOwner,
+    /// Require inbound transfers to have memo.
+    MemoTransfer,
+    /// Indicates that the tokens from this mint can't be transferred.
+    NonTransferable,
+    /// Tokens accrue interest over time.
+    InterestBearingConfig,
+    /// Locks privileged token operations from happening via CPI.
+    CpiGuard,
+    /// Includes an optional permanent delegate.
+    PermanentDelegate,
+    /// Indicates that the tokens in this account belong to a non-transferable
+    /// mint.
+    NonTransferableAccount,
+    /// Mint requires a CPI to a program implementing the "transfer hook"
+    /// interface.
+    TransferHook,
+    /// Indicates that the tokens in this account belong to a mint with a
+    /// transfer hook.
+    TransferHookAccount,
+    /// Includes encrypted withheld fees and the encryption public key that
+    /// they are encrypted under.
+    ConfidentialTransferFeeConfig,
+    /// Includes confidential withheld transfer fees.
+    ConfidentialTransferFeeAmount,
+    /// Mint contains a pointer to another account (or the same account) that
+    /// holds metadata.
+    MetadataPointer,
+    /// Mint contains token-metadata.
+    TokenMetadata,
+    /// Mint contains a pointer to another account (or the same account) that
+    /// holds group configurations.
+    GroupPointer,
+    /// Mint contains token group configurations.
+    TokenGroup,
+    /// Mint contains a pointer to another account (or the same account) that
+    /// holds group member configurations.
+    GroupMemberPointer,
+    /// Mint contains token group member configurations.
+    TokenGroupMember,
+    /// Mint allowing the minting and burning of confidential tokens.
+    ConfidentialMintBurn,
+    /// Tokens whose UI amount is scaled by a given amount.
+    ScaledUiAmount,
+    /// Tokens where minting / burning / transferring can be paused.
+    Pausable,
+    /// Indicates that the account belongs to a pausable mint.
+    PausableAccount,
+}
+
+impl ExtensionType {
+    /// Returns the `ExtensionType` represented by the given TLV type bytes.
+    ///
+    /// Returns `None` if the value does not represent a known extension type.
+    #[inline]
+    pub fn from_bytes(bytes: [u8; EXTENSION_TYPE_LEN]) -> Option<Self> {
+        Some(match u16::from_le_bytes(bytes) {
+            0 => ExtensionType::Uninitialized,
+            1 => ExtensionType::TransferFeeConfig,
+            2 => ExtensionType::TransferFeeAmount,
+            3 => ExtensionType::MintCloseAuthority,
+            4 => ExtensionType::ConfidentialTransferMint,
+            5 => ExtensionType::ConfidentialTransferAccount,
+            6 => ExtensionType::DefaultAccountState,
+            7 => ExtensionType::ImmutableOwner,
+            8 => ExtensionType::MemoTransfer,
+            9 => ExtensionType::NonTransferable,
+            10 => ExtensionType::InterestBearingConfig,
+            11 => ExtensionType::CpiGuard,
+            12 => ExtensionType::PermanentDelegate,
+            13 => ExtensionType::NonTransferableAccount,
+            14 => ExtensionType::TransferHook,
+            15 => ExtensionType::TransferHookAccount,
+            16 => ExtensionType::ConfidentialTransferFeeConfig,
+            17 => ExtensionType::ConfidentialTransferFeeAmount,
+            18 => ExtensionType::MetadataPointer,
+            19 => ExtensionType::TokenMetadata,
+            20 => ExtensionType::GroupPointer,
+            21 => ExtensionType::TokenGroup,
+            22 => ExtensionType::GroupMemberPointer,
+            23 => ExtensionType::TokenGroupMember,
+            24 => ExtensionType::ConfidentialMintBurn,
+            25 => ExtensionType::ScaledUiAmount,
+            26 => ExtensionType::Pausable,
+            27 => ExtensionType::PausableAccount,
+            _ => return None,
+        })
+    }
+
+    /// Returns the value of the extension type.
+    #[inline(always)]
+    pub const fn to_u16(self) -> u16 {
+        self as u16
+    }
+
+    /// Returns the TLV type bytes of the extension type.
+    ///
+    /// This is the inverse of [`ExtensionType::from_bytes`].
+    #[inline(always)]
+    pub const fn to_bytes(self) -> [u8; EXTENSION_TYPE_LEN] {
+        self.to_u16().to_le_bytes()
+    }
+
+    /// Returns the length of the extension data.
+    ///
+    /// Returns `None` for `TokenMetadata`, whose length depends on its
+    /// content.
+    #[inline]
+    pub const fn try_get_type_len(self) -> Option<usize> {
+        Some(match self {
+            ExtensionType::Uninitialized => 0,
+            ExtensionType::TransferFeeConfig => 108,
+            ExtensionType::TransferFeeAmount => 8,
+            ExtensionType::MintCloseAuthority => 32,
+            ExtensionType::ConfidentialTransferMint => 65,
+            ExtensionType::ConfidentialTransferAccount => 295,
+            ExtensionType::DefaultAccountState => 1,
+            ExtensionType::ImmutableOwner => 0,
+            ExtensionType::MemoTransfer => 1,
+            ExtensionType::NonTransferable => 0,
+            ExtensionType::InterestBearingConfig => 52,
+            ExtensionType::CpiGuard => 1,
+            ExtensionType::PermanentDelegate => 32,
+            ExtensionType::NonTransferableAccount => 0,
+            ExtensionType::TransferHook => 64,
+            ExtensionType::TransferHookAccount => 1,
+            ExtensionType::ConfidentialTransferFeeConfig => 129,
+            ExtensionType::ConfidentialTransferFeeAmount => 64,
+            ExtensionType::MetadataPointer => 64,
+            ExtensionType::TokenMetadata => return None,
+            ExtensionType::GroupPointer => 64,
+            ExtensionType::TokenGroup => 80,
+            ExtensionType::GroupMemberPointer => 64,
+            ExtensionType::TokenGroupMember => 72,
+            ExtensionType::ConfidentialMintBurn => 196,
+            ExtensionType::ScaledUiAmount => 56,
+            ExtensionType::Pausable => 33,
+            ExtensionType::PausableAccount => 0,
+        })
+    }
+
+    /// Returns the account extensions that a token account must hold when it
+    /// is initialized for a mint with the extensions `mint_extensions`.
+    ///
+    /// Some mint extensions imply an account extension, e.g. the accounts of a
+    /// `TransferFeeConfig` mint hold a `TransferFeeAmount`, so new accounts
+    /// must be sized for them, as done by spl-token-2022:
+    ///   * `TransferFeeConfig` requires `TransferFeeAmount`;
+    ///   * `NonTransferable` requires `NonTransferableAccount` and
+    ///     `ImmutableOwner`;
+    ///   * `TransferHook` requires `TransferHookAccount`;
+    ///   * `Pausable` requires `PausableAccount`.
+    ///
+    /// The extensions are written to `account_extensions` without duplicates,
+    /// and the written ones are returned.
+    #[inline]
+    pub fn get_required_init_account_extensions<'b>(
+        mint_extensions: &[ExtensionType],
+        account_extensions: &'b mut [ExtensionType; MAX_REQUIRED_ACCOUNT_EXTENSIONS],
+    ) -> &'b [ExtensionType] {
+        let mut len = 0;
+
+        for extension_type in mint_extensions {
+            let required: &[ExtensionType] = match extension_type {
+                ExtensionType::TransferFeeConfig => &[ExtensionType::TransferFeeAmount],
+                ExtensionType::NonTransferable => &[
+                    ExtensionType::NonTransferableAccount,
+                    ExtensionType::ImmutableOwner,
+                ],
+                ExtensionType::TransferHook => &[ExtensionType::TransferHookAccount],
+                ExtensionType::Pausable => &[ExtensionType::PausableAccount],
+                _ => &[],
+            };
+
+            for account_extension in required {
+                // There are `MAX_REQUIRED_ACCOUNT_EXTENSIONS` distinct required
+                // extensions, so the buffer cannot overflow.
+                if !account_extensions[..len].contains(account_extension) {
+                    account_extensions[len] = *account_extension;
+                    len += 1;
+                }
+            }
+        }
+
+        &account_extensions[..len]
+    }
+}
+
+/// Returns the length of an account with base state `base` holding the
+/// extensions `extension_types`.
+///
+/// Repeated extension types are only counted once. Accounts without extensions
+/// only hold the base state; otherwise the length includes the account type
+/// byte and, when the account would have the length of a multisig, an empty
+/// `Uninitialized` entry as padding.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if one of the extensions has a
+/// variable length.
+pub fn try_calculate_account_len(
+    base: BaseState,
+    extension_types: &[ExtensionType],
+) -> Result<usize, ProgramError> {
+    if extension_types.is_empty() {
+        return Ok(match base {
+            BaseState::Mint => Mint::LEN,
+            BaseState::TokenAccount => TokenAccount::LEN,
+        });
+    }
+
+    let mut len = base.extensions_offset();
+
+    for (index, extension_type) in extension_types.iter().enumerate() {
+        if extension_types[..index].contains(extension_type) {
+            continue;
+        }
+
+        let extension_len = extension_type
+            .try_get_type_len()
+            .ok_or(ProgramError::InvalidArgument)?;
+        len += EXTENSION_TYPE_LEN + EXTENSION_LENGTH_LEN + extension_len;
+    }
+
+    if len == MULTISIG_LEN {
+        len += EXTENSION_TYPE_LEN + EXTENSION_LENGTH_LEN;
+    }
+
+    Ok(len)
+}
+
+/// Base state of an account holding extensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BaseState {
+    /// The account is a `Mint`.
+    Mint,
+    /// The account is a `TokenAccount`.
+    TokenAccount,
+}
+
+impl BaseState {
+    /// Returns the offset of the TLV region for the base state.
+    #[inline(always)]
+    pub const fn extensions_offset(self) -> usize {
+        match self {
+            BaseState::Mint => Mint::LEN + MINT_PADDING_LEN + EXTENSION_START_OFFSET,
+            BaseState::TokenAccount => TokenAccount::LEN + EXTENSION_START_OFFSET,
+        }
+    }
+}
+
+/// Account type stored in the byte preceding the TLV region.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountType {
+    /// The account type has not been set yet.
+    Uninitialized,
+    /// The account is a `Mint`.
+    Mint,
+    /// The account is a `TokenAccount`.
+    Account,
+}
+
+impl AccountType {
+    /// Returns the `AccountType` represented by the given byte.
+    ///
+    /// Returns `None` if the value does not represent a known account type.
+    #[inline]
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0 => AccountType::Uninitialized,
+            1 => AccountType::Mint,
+            2 => AccountType::Account,
+            _ => return None,
+        })
+    }
+
+    /// Indicates whether an account of this type can hold extensions of the
+    /// base state `base`.
+    ///
+    /// The account type is only set when the base state is initialized, so
+    /// `Uninitialized` accounts are accepted for both base states.
+    #[inline(always)]
+    pub fn matches(self, base: BaseState) -> bool {
+        matches!(
+            (self, base),
+            (AccountType::Uninitialized, _)
+                | (AccountType::Mint, BaseState::Mint)
+                | (AccountType::Account, BaseState::TokenAccount)
+        )
+    }
+}
+
+/// Returns the account type of the data of an account.
+///
+/// Returns `None` if the data does not extend past the base state or the
+/// account type byte is not a known account type.
+#[inline]
+pub fn get_account_type(acc_data_bytes: &[u8]) -> Option<AccountType> {
+    AccountType::from_byte(*acc_data_bytes.get(ACCOUNT_TYPE_OFFSET)?)
+}
+
+/// A fixed-length extension stored in the TLV region of an account.
+///
+/// Extensions are read in place with [`cast_ref`], so implementors should
+/// have an alignment of `1` since TLV entries are not aligned.
+pub trait Extension: Pod {
+    /// The extension type.
+    const TYPE: ExtensionType;
+
+    /// The length of the extension data.
+    const LEN: usize = core::mem::size_of::<Self>();
+
+    /// The base state of accounts holding the extension.
+    const BASE_STATE: BaseState;
+}
+
+/// Returns the extension `T` from the data of an account.
+///
+/// The account data is expected to start with the base state of the extension;
+/// the TLV entries are then scanned until an entry matching both the type and
+/// the length of `T` is found.
+///
+/// Returns `None` if the extension is not present, the account type does not
+/// match the base state of the extension or the TLV region is malformed.
+/// Legacy accounts that hold only the base state, such as a 165-byte token
+/// account, have no account type byte and therefore no extensions.
+pub fn get_extension_from_bytes<T: Extension>(acc_data_bytes: &[u8]) -> Option<&T> {
+    let value = find_extension_value(acc_data_bytes, T::BASE_STATE, T::TYPE)?;
+
+    if value.len() != T::LEN {
+        return None;
+    }
+
+    cast_ref(value)
+}
+
+/// Returns the extension `T` from the data of an account, matching the TLV
+/// entry on its type alone, together with the length stored in the entry.
+///
+/// Unlike [`get_extension_from_bytes`], an entry whose length differs from
+/// `T::LEN` is not skipped, so programs can detect a layout that differs from
+/// the compiled one (e.g., a newer version of the extension) and handle it
+/// explicitly. The extension is read from the start of the stored value if
+/// it holds at least `T::LEN` bytes; otherwise only the length is returned.
+///
+/// Returns `None` if no entry of the extension type is present, the account
+/// type does not match the base state of the extension or the TLV region is
+/// malformed.
+pub fn get_extension_by_type_unchecked_len<T: Extension>(
+    acc_data_bytes: &[u8],
+) -> Option<(Option<&T>, usize)> {
+    let value = find_extension_value(acc_data_bytes, T::BASE_STATE, T::TYPE)?;
+
+    Some((value.get(..T::LEN).and_then(cast_ref), value.len()))
+}
+
+/// Returns the value of the first TLV entry of type `extension_type` in the
+/// data of an account with base state `base`.
+pub(crate) fn find_extension_value(
+    acc_data_bytes: &[u8],
+    base: BaseState,
+    extension_type: ExtensionType,
+) -> Option<&[u8]> {
+    // Reading the account type fails for legacy accounts without extensions,
+    // so the TLV region is never sliced past the end of their data.
+    if !get_account_type(acc_data_bytes)?.matches(base) {
+        return None;
+    }
+
+    let extension_bytes = acc_data_bytes.get(base.extensions_offset()..)?;
+
+    let mut start = 0;
+
+    while start < extension_bytes.len() {
+        let ext_len_idx = start + EXTENSION_TYPE_LEN;
+        let ext_data_idx = ext_len_idx + EXTENSION_LENGTH_LEN;
+
+        let ext_type: [u8; EXTENSION_TYPE_LEN] =
+            extension_bytes.get(start..ext_len_idx)?.try_into().ok()?;
+        let ext_len: [u8; EXTENSION_LENGTH_LEN] = extension_bytes
+            .get(ext_len_idx..ext_data_idx)?
+            .try_into()
+            .ok()?;
+        let ext_len = u16::from_le_bytes(ext_len) as usize;
+
+        if ExtensionType::from_bytes(ext_type) == Some(extension_type) {
+            return extension_bytes.get(ext_data_idx..ext_data_idx + ext_len);
+        }
+
+        start = ext_data_idx + ext_len;
+    }
+
+    None
+}
+
+/// Borrows the data of a Token-2022 account and runs `f` with its extension `T`.
+///
+/// The borrow of the account data is held while `f` runs and released before
+/// returning, so the extension can be used without tying its lifetime to the
+/// borrow at the call site.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidAccountOwner`] if the account is not owned by
+/// the Token-2022 program, [`ProgramError::InvalidAccountData`] if the extension
+/// is not present, or [`ProgramError::AccountBorrowFailed`] if the account data
+/// is already mutably borrowed.
+#[inline]
+pub fn with_extension<T: Extension, R>(
+    account_info: &AccountInfo,
+    f: impl FnOnce(&T) -> R,
+) -> Result<R, ProgramError> {
+    if !account_info.is_owned_by(&TOKEN_2022_ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let data = account_info.try_borrow_data()?;
+    let extension = get_extension_from_bytes::<T>(&data).ok_or(ProgramError::InvalidAccountData)?;
+
+    Ok(f(extension))
+}
+
+/// Validates the TLV region of the data of an account.
+///
+/// Every TLV entry is checked to:
+///   * have a known extension type;
+///   * have a declared length that fits in the remaining data;
+///   * not repeat the type of a previous entry.
+///
+/// The TLV region must end exactly at the end of the data, except for trailing
+/// zero bytes, which are treated as uninitialized padding.
+///
+/// Accounts without extensions (i.e., the data holds only the base state) are
+/// valid.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidAccountData`] if the TLV region is malformed.
+pub fn validate_extensions(acc_data_bytes: &[u8], base: BaseState) -> Result<(), ProgramError> {
+    let base_len = match base {
+        BaseState::Mint => Mint::LEN,
+        BaseState::TokenAccount => TokenAccount::LEN,
+    };
+
+    if acc_data_bytes.len() == base_len {
+        return Ok(());
+    }
+
+    let extension_bytes = acc_data_bytes
+        .get(base.extensions_offset()..)
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    // bitmap of the extension types seen so far
+    let mut seen = 0u32;
+    let mut start = 0;
+
+    while start < extension_bytes.len() {
+        let remaining = &extension_bytes[start..];
+
+        if remaining.len() < EXTENSION_TYPE_LEN + EXTENSION_LENGTH_LEN
+            || remaining[..EXTENSION_TYPE_LEN] == [0; EXTENSION_TYPE_LEN]
+        {
+            // only zero padding is allowed after the last entry
+            return if remaining.iter().all(|byte| *byte == 0) {
+                Ok(())
+            } else {
+                Err(ProgramError::InvalidAccountData)
+            };
+        }
+
+        let extension_type = ExtensionType::from_bytes([remaining[0], remaining[1]])
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let extension_len = u16::from_le_bytes([remaining[2], remaining[3]]) as usize;
+
+        let entry_len = EXTENSION_TYPE_LEN + EXTENSION_LENGTH_LEN + extension_len;
+
+        if entry_len > remaining.len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mask = 1 << (extension_type as u16);
+
+        if seen & mask != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        seen |= mask;
+        start += entry_len;
+    }
+
+    Ok(())
+}
+
+/// Asserts that the extensions of a mint are all in `allowed`.
+///
+/// Programs that only handle some extensions, e.g. plain transfers without
+/// transfer fees or interest, can use this guard to reject mints holding
+/// extensions they would otherwise silently mishandle. A mint without
+/// extensions is always supported.
+///
+/// # Errors
+///
+/// Returns [`TokenHelperError::UnsupportedMintExtension`] if the mint holds an
+/// extension that is not in `allowed`, or [`ProgramError::InvalidAccountData`]
+/// if the data is not a mint or its TLV region is malformed.
+///
+/// [`TokenHelperError::UnsupportedMintExtension`]: crate::error::TokenHelperError::UnsupportedMintExtension
+pub fn assert_supported_mint(
+    mint_bytes: &[u8],
+    allowed: &[ExtensionType],
+) -> Result<(), ProgramError> {
+    validate_extensions(mint_bytes, BaseState::Mint)?;
+
+    if mint_bytes.len() == Mint::LEN {
+        return Ok(());
+    }
+
+    if !get_account_type(mint_bytes)
+        .is_some_and(|account_type| account_type.matches(BaseState::Mint))
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    // The TLV region was validated, so every entry is in bounds and has a
+    // known type.
+    let mut remaining = &mint_bytes[BaseState::Mint.extensions_offset()..];
+
+    while let [low, high, len_low, len_high, ..] = *remaining {
+        let Some(extension_type) = ExtensionType::from_bytes([low, high])
+            .filter(|extension_type| *extension_type != ExtensionType::Uninitialized)
+        else {
+            // zero padding after the last entry
+            break;
+        };
+
+        if !allowed.contains(&extension_type) {
+            return Err(TokenHelperError::UnsupportedMintExtension.into());
+        }
+
+        let entry_len = EXTENSION_TYPE_LEN
+            + EXTENSION_LENGTH_LEN
+            + u16::from_le_bytes([len_low, len_high]) as usize;
+        remaining = &remaining[entry_len..];
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    /// Returns the data of a Token-2022 account with the given extensions.
+    pub(crate) fn account_with_extensions(
+        base: BaseState,
+        extensions: &[(ExtensionType, &[u8])],
+    ) -> Vec<u8> {
+        let mut data = std::vec![0u8; base.extensions_offset()];
+        // account type byte
+        data[ACCOUNT_TYPE_OFFSET] = match base {
+            BaseState::Mint => AccountType::Mint,
+            BaseState::TokenAccount => AccountType::Account,
+        } as u8;
+
+        for (extension_type, value) in extensions {
+            data.extend_from_slice(&extension_type.to_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+
+        data
+    }
+
+    #[test]
+    fn test_extension_type_from_bytes() {
+        assert_eq!(
+            ExtensionType::from_bytes([1, 0]),
+            Some(ExtensionType::TransferFeeConfig)
+        );
+        assert_eq!(
+            ExtensionType::from_bytes([27, 0]),
+            Some(ExtensionType::PausableAccount)
+        );
+        assert_eq!(ExtensionType::from_bytes([28, 0]), None);
+    }
+
+    #[test]
+    fn test_extension_type_to_bytes() {
+        let mut count = 0;
+
+        for value in 0..=u16::MAX {
+            let Some(extension_type) = ExtensionType::from_bytes(value.to_le_bytes()) else {
+                continue;
+            };
+            assert_eq!(extension_type.to_u16(), value);
+            assert_eq!(extension_type.to_bytes(), value.to_le_bytes());
+            assert_eq!(
+                ExtensionType::from_bytes(extension_type.to_bytes()),
+                Some(extension_type)
+            );
+            count += 1;
+        }
+
+        // every variant round-trips
+        assert_eq!(count, ExtensionType::PausableAccount.to_u16() + 1);
+        assert_eq!(ExtensionType::TokenMetadata.to_bytes(), [19, 0]);
+    }
+
+    #[test]
+    fn test_extension_type_len() {
+        assert_eq!(ExtensionType::Uninitialized.try_get_type_len(), Some(0));
+        assert_eq!(
+            ExtensionType::TransferFeeConfig.try_get_type_len(),
+            Some(108)
+        );
+        assert_eq!(
+            ExtensionType::MintCloseAuthority.try_get_type_len(),
+            Some(32)
+        );
+        assert_eq!(ExtensionType::ImmutableOwner.try_get_type_len(), Some(0));
+        assert_eq!(ExtensionType::TransferHook.try_get_type_len(), Some(64));
+        assert_eq!(ExtensionType::TokenMetadata.try_get_type_len(), None);
+    }
+
+    #[test]
+    fn test_get_required_init_account_extensions() {
+        let mut buffer = [ExtensionType::Uninitialized; MAX_REQUIRED_ACCOUNT_EXTENSIONS];
+        let mut required = |mint_extensions: &[ExtensionType]| {
+            ExtensionType::get_required_init_account_extensions(mint_extensions, &mut buffer)
+                .to_vec()
+        };
+
+        // a plain mint
+        assert!(required(&[]).is_empty());
+        assert!(required(&[ExtensionType::MintCloseAuthority]).is_empty());
+
+        // a fee mint
+        assert_eq!(
+            required(&[ExtensionType::TransferFeeConfig]),
+            [ExtensionType::TransferFeeAmount]
+        );
+
+        // a non-transferable mint
+        assert_eq!(
+            required(&[ExtensionType::NonTransferable]),
+            [
+                ExtensionType::NonTransferableAccount,
+                ExtensionType::ImmutableOwner
+            ]
+        );
+
+        // a transfer-hook mint
+        assert_eq!(
+            required(&[ExtensionType::TransferHook]),
+            [ExtensionType::TransferHookAccount]
+        );
+
+        // all the mint extensions requiring account extensions, with duplicates
+        let account_extensions = required(&[
+            ExtensionType::Pausable,
+            ExtensionType::TransferHook,
+            ExtensionType::MetadataPointer,
+            ExtensionType::NonTransferable,
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::TransferHook,
+        ]);
+        assert_eq!(
+            account_extensions,
+            [
+                ExtensionType::PausableAccount,
+                ExtensionType::TransferHookAccount,
+                ExtensionType::NonTransferableAccount,
+                ExtensionType::ImmutableOwner,
+                ExtensionType::TransferFeeAmount,
+            ]
+        );
+        assert_eq!(account_extensions.len(), MAX_REQUIRED_ACCOUNT_EXTENSIONS);
+
+        // the required extensions size the account
+        assert_eq!(
+            try_calculate_account_len(
+                BaseState::TokenAccount,
+                &required(&[ExtensionType::TransferFeeConfig])
+            ),
+            Ok(TokenAccount::LEN + EXTENSION_START_OFFSET + 12)
+        );
+    }
+
+    #[test]
+    fn test_try_calculate_account_len() {
+        assert_eq!(
+            try_calculate_account_len(BaseState::Mint, &[]),
+            Ok(Mint::LEN)
+        );
+        assert_eq!(
+            try_calculate_account_len(BaseState::TokenAccount, &[]),
+            Ok(TokenAccount::LEN)
+        );
+
+        // the extension lengths match the extensions read in place
+        for (extension_type, len) in [
+            (
+                ExtensionType::TransferFeeConfig,
+                transfer_fee::TransferFeeConfig::LEN,
+            ),
+            (
+                ExtensionType::TransferFeeAmount,
+                transfer_fee::TransferFeeAmount::LEN,
+            ),
+            (
+                ExtensionType::MintCloseAuthority,
+                mint_close_authority::MintCloseAuthority::LEN,
+            ),
+            (
+                ExtensionType::ConfidentialTransferAccount,
+                confidential_transfer::ConfidentialTransferAccount::LEN,
+            ),
+            (
+                ExtensionType::PermanentDelegate,
+                permanent_delegate::PermanentDelegate::LEN,
+            ),
+            (
+                ExtensionType::TransferHook,
+                transfer_hook::TransferHook::LEN,
+            ),
+            (
+                ExtensionType::InterestBearingConfig,
+                interest_bearing::InterestBearingConfig::LEN,
+            ),
+        ] {
+            assert_eq!(extension_type.try_get_type_len(), Some(len));
+        }
+
+        assert_eq!(
+            try_calculate_account_len(BaseState::Mint, &[ExtensionType::TransferFeeConfig]),
+            Ok(278)
+        );
+        // repeated extensions are counted once
+        assert_eq!(
+            try_calculate_account_len(
+                BaseState::TokenAccount,
+                &[
+                    ExtensionType::TransferFeeAmount,
+                    ExtensionType::ImmutableOwner,
+                    ExtensionType::TransferFeeAmount,
+                ],
+            ),
+            Ok(182)
+        );
+        // padded to avoid the length of a multisig
+        assert_eq!(
+            try_calculate_account_len(
+                BaseState::Mint,
+                &[
+                    ExtensionType::TransferFeeConfig,
+                    ExtensionType::TransferHook,
+                    ExtensionType::PausableAccount,
+                ],
+            ),
+            Ok(350)
+        );
+        assert_eq!(
+            try_calculate_account_len(
+                BaseState::Mint,
+                &[
+                    ExtensionType::TransferFeeConfig,
+                    ExtensionType::TransferHook,
+                    ExtensionType::PausableAccount,
+                    ExtensionType::DefaultAccountState,
+                ],
+            ),
+            Ok(MULTISIG_LEN + 4)
+        );
+
+        assert_eq!(
+            try_calculate_account_len(BaseState::Mint, &[ExtensionType::TokenMetadata]),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_account_type() {
+        let mut data = account_with_extensions(BaseState::Mint, &[]);
+        assert_eq!(get_account_type(&data), Some(AccountType::Mint));
+
+        data[ACCOUNT_TYPE_OFFSET] = 2;
+        assert_eq!(get_account_type(&data), Some(AccountType::Account));
+
+        data[ACCOUNT_TYPE_OFFSET] = 3;
+        assert_eq!(get_account_type(&data), None);
+
+        // base state only
+        assert_eq!(get_account_type(&[0; Mint::LEN]), None);
+        assert_eq!(get_account_type(&[0; TokenAccount::LEN]), None);
+    }
+
+    #[test]
+    fn test_account_type_mismatch() {
+        let mut data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[1; 32])],
+        );
+        type Delegate = permanent_delegate::PermanentDelegate;
+        assert!(get_extension_from_bytes::<Delegate>(&data).is_some());
+
+        // a token account does not hold mint extensions
+        data[ACCOUNT_TYPE_OFFSET] = AccountType::Account as u8;
+        assert!(get_extension_from_bytes::<Delegate>(&data).is_none());
+
+        // the account type is set when the mint is initialized
+        data[ACCOUNT_TYPE_OFFSET] = AccountType::Uninitialized as u8;
+        assert!(get_extension_from_bytes::<Delegate>(&data).is_some());
+
+        // unknown account type
+        data[ACCOUNT_TYPE_OFFSET] = 3;
+        assert!(get_extension_from_bytes::<Delegate>(&data).is_none());
+    }
+
+    #[test]
+    fn test_get_extension_by_type_unchecked_len() {
+        type Delegate = permanent_delegate::PermanentDelegate;
+
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[1; 32])],
+        );
+        let (extension, len) = get_extension_by_type_unchecked_len::<Delegate>(&data).unwrap();
+        assert_eq!(extension.unwrap().delegate(), Some(&[1; 32]));
+        assert_eq!(len, 32);
+
+        // a longer value, e.g. a newer version of the extension
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[2; 40])],
+        );
+        assert!(get_extension_from_bytes::<Delegate>(&data).is_none());
+        let (extension, len) = get_extension_by_type_unchecked_len::<Delegate>(&data).unwrap();
+        assert_eq!(extension.unwrap().delegate(), Some(&[2; 32]));
+        assert_eq!(len, 40);
+
+        // a shorter value
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[3; 16])],
+        );
+        assert!(get_extension_from_bytes::<Delegate>(&data).is_none());
+        let (extension, len) = get_extension_by_type_unchecked_len::<Delegate>(&data).unwrap();
+        assert!(extension.is_none());
+        assert_eq!(len, 16);
+
+        // missing extension
+        let data = account_with_extensions(BaseState::Mint, &[]);
+        assert!(get_extension_by_type_unchecked_len::<Delegate>(&data).is_none());
+    }
+
+    #[test]
+    fn test_legacy_token_account() {
+        type FeeAmount = transfer_fee::TransferFeeAmount;
+
+        // a token account of the legacy token program
+        let data = [1; TokenAccount::LEN];
+        assert!(get_account_type(&data).is_none());
+        assert!(get_extension_from_bytes::<FeeAmount>(&data).is_none());
+        assert!(get_extension_by_type_unchecked_len::<FeeAmount>(&data).is_none());
+        assert!(get_extension_from_bytes::<FeeAmount>(&data[..TokenAccount::LEN - 1]).is_none());
+
+        // a Token-2022 account with extensions
+        let data = account_with_extensions(
+            BaseState::TokenAccount,
+            &[
+                (ExtensionType::ImmutableOwner, &[]),
+                (ExtensionType::TransferFeeAmount, &7u64.to_le_bytes()),
+            ],
+        );
+        assert_eq!(get_account_type(&data), Some(AccountType::Account));
+        assert_eq!(
+            get_extension_from_bytes::<FeeAmount>(&data).map(FeeAmount::withheld_amount),
+            Some(7)
+        );
+
+        // a Token-2022 account without extensions only holds the account type
+        let data = account_with_extensions(BaseState::TokenAccount, &[]);
+        assert_eq!(data.len(), TokenAccount::LEN + EXTENSION_START_OFFSET);
+        assert!(get_extension_from_bytes::<FeeAmount>(&data).is_none());
+    }
+
+    #[test]
+    fn test_extensions_offset() {
+        assert_eq!(BaseState::Mint.extensions_offset(), 166);
+        assert_eq!(BaseState::TokenAccount.extensions_offset(), 166);
+    }
+
+    #[test]
+    fn test_truncated_tlv_region() {
+        let mut data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[1; 32])],
+        );
+        data.truncate(data.len() - 1);
+
+        assert!(get_extension_from_bytes::<permanent_delegate::PermanentDelegate>(&data).is_none());
+    }
+
+    #[test]
+    fn test_validate_extensions() {
+        // base state only
+        assert!(validate_extensions(&[0; Mint::LEN], BaseState::Mint).is_ok());
+        assert!(validate_extensions(&[0; TokenAccount::LEN], BaseState::TokenAccount).is_ok());
+
+        let mut data = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (ExtensionType::MintCloseAuthority, &[3; 32]),
+                (ExtensionType::PermanentDelegate, &[7; 32]),
+            ],
+        );
+        assert!(validate_extensions(&data, BaseState::Mint).is_ok());
+
+        // trailing zero padding
+        data.extend_from_slice(&[0; 7]);
+        assert!(validate_extensions(&data, BaseState::Mint).is_ok());
+    }
+
+    #[test]
+    fn test_assert_supported_mint() {
+        let allowed = [
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::MetadataPointer,
+        ];
+
+        // a plain mint
+        assert!(assert_supported_mint(&[0; Mint::LEN], &allowed).is_ok());
+        assert!(assert_supported_mint(&[0; Mint::LEN], &[]).is_ok());
+        let data = account_with_extensions(BaseState::Mint, &[]);
+        assert!(assert_supported_mint(&data, &[]).is_ok());
+
+        // a mint with allowed extensions and zero padding
+        let mut data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::MintCloseAuthority, &[3; 32])],
+        );
+        data.extend_from_slice(&[0; 5]);
+        assert!(assert_supported_mint(&data, &allowed).is_ok());
+
+        // a fee-bearing mint
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (ExtensionType::MintCloseAuthority, &[3; 32]),
+                (ExtensionType::TransferFeeConfig, &[0; 108]),
+            ],
+        );
+        assert_eq!(
+            assert_supported_mint(&data, &allowed),
+            Err(TokenHelperError::UnsupportedMintExtension.into())
+        );
+
+        // an interest-bearing mint
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::InterestBearingConfig, &[0; 52])],
+        );
+        assert_eq!(
+            assert_supported_mint(&data, &allowed),
+            Err(TokenHelperError::UnsupportedMintExtension.into())
+        );
+
+        // not a mint
+        let data = account_with_extensions(
+            BaseState::TokenAccount,
+            &[(ExtensionType::ImmutableOwner, &[])],
+        );
+        assert_eq!(
+            assert_supported_mint(&data, &[ExtensionType::ImmutableOwner]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            assert_supported_mint(&[0; Mint::LEN + 1], &allowed),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_validate_extensions_duplicate_type() {
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (ExtensionType::PermanentDelegate, &[7; 32]),
+                (ExtensionType::MintCloseAuthority, &[3; 32]),
+                (ExtensionType::PermanentDelegate, &[8; 32]),
+            ],
+        );
+
+        assert_eq!(
+            validate_extensions(&data, BaseState::Mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_validate_extensions_overlong_length() {
+        let mut data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[7; 32])],
+        );
+        // declare one byte more than available
+        let offset = BaseState::Mint.extensions_offset() + EXTENSION_TYPE_LEN;
+        data[offset..offset + EXTENSION_LENGTH_LEN].copy_from_slice(&33u16.to_le_bytes());
+
+        assert_eq!(
+            validate_extensions(&data, BaseState::Mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_validate_extensions_trailing_garbage() {
+        let mut data = account_with_extensions(
+            BaseState::TokenAccount,
+            &[(ExtensionType::ImmutableOwner, &[])],
+        );
+        data.extend_from_slice(&[0, 0, 1]);
+
+        assert_eq!(
+            validate_extensions(&data, BaseState::TokenAccount),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // unknown extension type
+        let mut data = account_with_extensions(BaseState::TokenAccount, &[]);
+        data.extend_from_slice(&[255, 0, 0, 0]);
+
+        assert_eq!(
+            validate_extensions(&data, BaseState::TokenAccount),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
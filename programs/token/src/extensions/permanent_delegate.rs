@@ -0,0 +1,102 @@
+use pinocchio::{
+    pod::{Pod, Zeroable},
+    pubkey::Pubkey,
+};
+
+use super::{get_extension_from_bytes, BaseState, Extension, ExtensionType};
+
+/// Permanent delegate extension data for mints.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PermanentDelegate {
+    /// Optional permanent delegate for transferring or burning tokens.
+    ///
+    /// A zeroed pubkey represents `None`.
+    delegate: Pubkey,
+}
+
+unsafe impl Zeroable for PermanentDelegate {}
+unsafe impl Pod for PermanentDelegate {}
+
+impl Extension for PermanentDelegate {
+    const TYPE: ExtensionType = ExtensionType::PermanentDelegate;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl PermanentDelegate {
+    /// Return the `PermanentDelegate` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the permanent delegate, if any.
+    #[inline]
+    pub fn delegate(&self) -> Option<&Pubkey> {
+        if self.delegate == Pubkey::default() {
+            None
+        } else {
+            Some(&self.delegate)
+        }
+    }
+}
+
+/// Checks whether `key` is the permanent delegate of the mint.
+///
+/// Returns `false` if the mint does not have the `PermanentDelegate` extension
+/// or the permanent delegate is not set.
+#[inline]
+pub fn is_permanent_delegate(mint_bytes: &[u8], key: &Pubkey) -> bool {
+    PermanentDelegate::from_bytes(mint_bytes)
+        .and_then(PermanentDelegate::delegate)
+        .is_some_and(|delegate| delegate == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extensions::tests::account_with_extensions;
+
+    #[test]
+    fn test_permanent_delegate() {
+        let delegate = [7u8; 32];
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[
+                (ExtensionType::MintCloseAuthority, &[3; 32]),
+                (ExtensionType::PermanentDelegate, &delegate),
+            ],
+        );
+
+        let extension = PermanentDelegate::from_bytes(&mint).unwrap();
+
+        assert_eq!(extension.delegate(), Some(&delegate));
+        assert!(is_permanent_delegate(&mint, &delegate));
+        assert!(!is_permanent_delegate(&mint, &[3; 32]));
+    }
+
+    #[test]
+    fn test_permanent_delegate_not_set() {
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::PermanentDelegate, &[0; 32])],
+        );
+
+        assert!(PermanentDelegate::from_bytes(&mint)
+            .unwrap()
+            .delegate()
+            .is_none());
+        assert!(!is_permanent_delegate(&mint, &Pubkey::default()));
+    }
+
+    #[test]
+    fn test_without_permanent_delegate() {
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::MintCloseAuthority, &[3; 32])],
+        );
+
+        assert!(PermanentDelegate::from_bytes(&mint).is_none());
+        assert!(!is_permanent_delegate(&mint, &[3; 32]));
+    }
+}
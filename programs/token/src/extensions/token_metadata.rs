@@ -0,0 +1,748 @@
+use core::mem::MaybeUninit;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use super::{find_extension_value, BaseState, ExtensionType};
+use crate::{write_bytes, TOKEN_2022_ID, UNINIT_BYTE};
+
+/// Discriminator of the `Initialize` instruction of the token metadata
+/// interface.
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [210, 225, 30, 162, 88, 184, 77, 141];
+
+/// Discriminator of the `UpdateField` instruction of the token metadata
+/// interface.
+pub const UPDATE_FIELD_DISCRIMINATOR: [u8; 8] = [221, 233, 49, 45, 181, 202, 220, 200];
+
+/// Discriminator of the `RemoveKey` instruction of the token metadata
+/// interface.
+pub const REMOVE_KEY_DISCRIMINATOR: [u8; 8] = [234, 18, 32, 56, 89, 141, 37, 181];
+
+/// Discriminator of the `UpdateAuthority` instruction of the token metadata
+/// interface.
+pub const UPDATE_AUTHORITY_DISCRIMINATOR: [u8; 8] = [215, 228, 166, 228, 84, 100, 86, 123];
+
+/// Maximum length of the instruction data of the token metadata
+/// instructions with string arguments.
+///
+/// Instructions whose encoded strings do not fit fail with
+/// [`ProgramError::InvalidArgument`].
+pub const MAX_TOKEN_METADATA_DATA_LEN: usize = 512;
+
+/// A field of the token metadata.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field<'a> {
+    /// The name of the token.
+    Name,
+    /// The symbol of the token.
+    Symbol,
+    /// The URI of the token metadata.
+    Uri,
+    /// A user-defined field.
+    Key(&'a str),
+}
+
+/// Token metadata extension data, read in place from the data of a mint.
+///
+/// Unlike fixed-length extensions, the metadata holds length-prefixed
+/// strings, so it is parsed rather than cast from the account data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenMetadata<'a> {
+    /// Authority that can update the metadata.
+    ///
+    /// A zeroed pubkey represents `None`.
+    update_authority: &'a Pubkey,
+
+    /// The mint of the metadata.
+    mint: &'a Pubkey,
+
+    /// The name of the token.
+    name: &'a str,
+
+    /// The symbol of the token.
+    symbol: &'a str,
+
+    /// The URI of the token metadata.
+    uri: &'a str,
+
+    /// The encoded user-defined fields.
+    additional_metadata: &'a [u8],
+}
+
+impl<'a> TokenMetadata<'a> {
+    /// Return the `TokenMetadata` extension from the given mint bytes.
+    ///
+    /// Returns `None` if the extension is not present or its fixed fields
+    /// are malformed.
+    pub fn from_bytes(mint_bytes: &'a [u8]) -> Option<Self> {
+        let mut data =
+            find_extension_value(mint_bytes, BaseState::Mint, ExtensionType::TokenMetadata)?;
+
+        let (update_authority, rest) = data.split_first_chunk::<32>()?;
+        let (mint, rest) = rest.split_first_chunk::<32>()?;
+        data = rest;
+
+        Some(Self {
+            update_authority,
+            mint,
+            name: read_str(&mut data)?,
+            symbol: read_str(&mut data)?,
+            uri: read_str(&mut data)?,
+            additional_metadata: data,
+        })
+    }
+
+    /// Return the authority that can update the metadata, if any.
+    #[inline]
+    pub fn update_authority(&self) -> Option<&'a Pubkey> {
+        if self.update_authority == &Pubkey::default() {
+            None
+        } else {
+            Some(self.update_authority)
+        }
+    }
+
+    /// Return the mint of the metadata.
+    #[inline(always)]
+    pub fn mint(&self) -> &'a Pubkey {
+        self.mint
+    }
+
+    /// Return the name of the token.
+    #[inline(always)]
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Return the symbol of the token.
+    #[inline(always)]
+    pub fn symbol(&self) -> &'a str {
+        self.symbol
+    }
+
+    /// Return the URI of the token metadata.
+    #[inline(always)]
+    pub fn uri(&self) -> &'a str {
+        self.uri
+    }
+
+    /// Return an iterator over the user-defined `(key, value)` fields.
+    #[inline]
+    pub fn additional_metadata(&self) -> AdditionalMetadata<'a> {
+        let (remaining, data) = match self.additional_metadata.split_first_chunk::<4>() {
+            Some((count, data)) => (u32::from_le_bytes(*count), data),
+            None => (0, &[][..]),
+        };
+
+        AdditionalMetadata { data, remaining }
+    }
+}
+
+/// Iterator over the user-defined `(key, value)` fields of a
+/// [`TokenMetadata`].
+///
+/// Each key and value is a `u32` length followed by its UTF-8 bytes. The
+/// iteration stops after the number of fields stored in the metadata, or at
+/// the first field that is truncated or not valid UTF-8.
+#[derive(Clone, Debug)]
+pub struct AdditionalMetadata<'a> {
+    /// The encoded fields not yet read.
+    data: &'a [u8],
+
+    /// Number of fields not yet read.
+    remaining: u32,
+}
+
+impl<'a> Iterator for AdditionalMetadata<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let mut data = self.data;
+        let field = read_str(&mut data).zip(read_str(&mut data));
+
+        match field {
+            Some(_) => {
+                self.data = data;
+                self.remaining -= 1;
+            }
+            // A malformed field ends the iteration.
+            None => self.remaining = 0,
+        }
+
+        field
+    }
+}
+
+/// Read a `u32` length-prefixed UTF-8 string from the start of `data`,
+/// advancing it past the string.
+///
+/// Returns `None` if the string is truncated or not valid UTF-8.
+#[inline(always)]
+fn read_str<'a>(data: &mut &'a [u8]) -> Option<&'a str> {
+    let (len, rest) = data.split_first_chunk::<4>()?;
+    let (value, rest) = rest.split_at_checked(u32::from_le_bytes(*len) as usize)?;
+    *data = rest;
+    core::str::from_utf8(value).ok()
+}
+
+/// Initialize the token metadata of a mint.
+///
+/// The metadata account must have enough lamports to be rent exempt after
+/// the metadata is written.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The metadata account.
+///   1. `[]` The update authority.
+///   2. `[]` The mint.
+///   3. `[SIGNER]` The mint authority.
+pub struct InitializeTokenMetadata<'a, 'b> {
+    /// Metadata Account.
+    pub metadata: &'a AccountInfo,
+    /// Update Authority.
+    pub update_authority: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Mint Authority.
+    pub mint_authority: &'a AccountInfo,
+    /// The name of the token.
+    pub name: &'b str,
+    /// The symbol of the token.
+    pub symbol: &'b str,
+    /// The URI of the token metadata.
+    pub uri: &'b str,
+}
+
+impl InitializeTokenMetadata<'_, '_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 4] = [
+            AccountMeta::writable(self.metadata.key()),
+            AccountMeta::readonly(self.update_authority.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::readonly_signer(self.mint_authority.key()),
+        ];
+
+        let mut instruction_data = [UNINIT_BYTE; MAX_TOKEN_METADATA_DATA_LEN];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: self.instruction_data(&mut instruction_data)?,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.metadata,
+                self.update_authority,
+                self.mint,
+                self.mint_authority,
+            ],
+            signers,
+        )
+    }
+
+    // Instruction data layout:
+    // -  [0..8]: instruction discriminator (8 bytes, [u8; 8])
+    // -  [8..]: name (4 bytes, u32 length followed by the UTF-8 bytes)
+    // -  [..]: symbol (4 bytes, u32 length followed by the UTF-8 bytes)
+    // -  [..]: uri (4 bytes, u32 length followed by the UTF-8 bytes)
+    #[inline(always)]
+    fn instruction_data<'d>(
+        &self,
+        data: &'d mut [MaybeUninit<u8>; MAX_TOKEN_METADATA_DATA_LEN],
+    ) -> Result<&'d [u8], ProgramError> {
+        write_bytes(data, &INITIALIZE_DISCRIMINATOR);
+        let mut offset = INITIALIZE_DISCRIMINATOR.len();
+
+        offset = write_str(data, offset, self.name)?;
+        offset = write_str(data, offset, self.symbol)?;
+        offset = write_str(data, offset, self.uri)?;
+
+        // SAFETY: The first `offset` bytes have been initialized.
+        Ok(unsafe { core::slice::from_raw_parts(data.as_ptr() as _, offset) })
+    }
+}
+
+/// Update a field of the token metadata, adding it if it is a new
+/// user-defined field.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The metadata account.
+///   1. `[SIGNER]` The update authority.
+pub struct UpdateTokenMetadataField<'a, 'b> {
+    /// Metadata Account.
+    pub metadata: &'a AccountInfo,
+    /// Update Authority.
+    pub update_authority: &'a AccountInfo,
+    /// The field to update.
+    pub field: Field<'b>,
+    /// The new value of the field.
+    pub value: &'b str,
+}
+
+impl UpdateTokenMetadataField<'_, '_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut instruction_data = [UNINIT_BYTE; MAX_TOKEN_METADATA_DATA_LEN];
+
+        invoke_token_metadata(
+            self.metadata,
+            self.update_authority,
+            self.instruction_data(&mut instruction_data)?,
+            signers,
+        )
+    }
+
+    // Instruction data layout:
+    // -  [0..8]: instruction discriminator (8 bytes, [u8; 8])
+    // -  [8]: field discriminator (1 byte, u8), followed by the key if the
+    //    field is user-defined (4 bytes, u32 length followed by the UTF-8
+    //    bytes)
+    // -  [..]: value (4 bytes, u32 length followed by the UTF-8 bytes)
+    #[inline(always)]
+    fn instruction_data<'d>(
+        &self,
+        data: &'d mut [MaybeUninit<u8>; MAX_TOKEN_METADATA_DATA_LEN],
+    ) -> Result<&'d [u8], ProgramError> {
+        write_bytes(data, &UPDATE_FIELD_DISCRIMINATOR);
+        let mut offset = UPDATE_FIELD_DISCRIMINATOR.len();
+
+        let field = match self.field {
+            Field::Name => 0,
+            Field::Symbol => 1,
+            Field::Uri => 2,
+            Field::Key(_) => 3,
+        };
+        data[offset].write(field);
+        offset += 1;
+
+        if let Field::Key(key) = self.field {
+            offset = write_str(data, offset, key)?;
+        }
+        offset = write_str(data, offset, self.value)?;
+
+        // SAFETY: The first `offset` bytes have been initialized.
+        Ok(unsafe { core::slice::from_raw_parts(data.as_ptr() as _, offset) })
+    }
+}
+
+/// Remove a user-defined field of the token metadata.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The metadata account.
+///   1. `[SIGNER]` The update authority.
+pub struct RemoveTokenMetadataKey<'a, 'b> {
+    /// Metadata Account.
+    pub metadata: &'a AccountInfo,
+    /// Update Authority.
+    pub update_authority: &'a AccountInfo,
+    /// Indicates whether removing a missing key succeeds.
+    pub idempotent: bool,
+    /// The key of the field to remove.
+    pub key: &'b str,
+}
+
+impl RemoveTokenMetadataKey<'_, '_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let mut instruction_data = [UNINIT_BYTE; MAX_TOKEN_METADATA_DATA_LEN];
+
+        invoke_token_metadata(
+            self.metadata,
+            self.update_authority,
+            self.instruction_data(&mut instruction_data)?,
+            signers,
+        )
+    }
+
+    // Instruction data layout:
+    // -  [0..8]: instruction discriminator (8 bytes, [u8; 8])
+    // -  [8]: idempotent (1 byte, bool)
+    // -  [9..]: key (4 bytes, u32 length followed by the UTF-8 bytes)
+    #[inline(always)]
+    fn instruction_data<'d>(
+        &self,
+        data: &'d mut [MaybeUninit<u8>; MAX_TOKEN_METADATA_DATA_LEN],
+    ) -> Result<&'d [u8], ProgramError> {
+        write_bytes(data, &REMOVE_KEY_DISCRIMINATOR);
+        let mut offset = REMOVE_KEY_DISCRIMINATOR.len();
+
+        data[offset].write(self.idempotent as u8);
+        offset += 1;
+        offset = write_str(data, offset, self.key)?;
+
+        // SAFETY: The first `offset` bytes have been initialized.
+        Ok(unsafe { core::slice::from_raw_parts(data.as_ptr() as _, offset) })
+    }
+}
+
+/// Update the update authority of the token metadata.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The metadata account.
+///   1. `[SIGNER]` The current update authority.
+pub struct UpdateTokenMetadataAuthority<'a> {
+    /// Metadata Account.
+    pub metadata: &'a AccountInfo,
+    /// Current Update Authority.
+    pub update_authority: &'a AccountInfo,
+    /// The new update authority, or `None` to make the metadata immutable.
+    pub new_authority: Option<&'a Pubkey>,
+}
+
+impl UpdateTokenMetadataAuthority<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        invoke_token_metadata(
+            self.metadata,
+            self.update_authority,
+            &self.instruction_data(),
+            signers,
+        )
+    }
+
+    // Instruction data layout:
+    // -  [0..8]: instruction discriminator (8 bytes, [u8; 8])
+    // -  [8..40]: new_authority, zeroed if `None` (32 bytes, Pubkey)
+    #[inline(always)]
+    fn instruction_data(&self) -> [u8; 40] {
+        let mut data = [0; 40];
+        data[..8].copy_from_slice(&UPDATE_AUTHORITY_DISCRIMINATOR);
+        if let Some(new_authority) = self.new_authority {
+            data[8..].copy_from_slice(new_authority);
+        }
+        data
+    }
+}
+
+#[inline(always)]
+fn invoke_token_metadata(
+    metadata: &AccountInfo,
+    update_authority: &AccountInfo,
+    data: &[u8],
+    signers: &[Signer],
+) -> ProgramResult {
+    // account metadata
+    let account_metas: [AccountMeta; 2] = [
+        AccountMeta::writable(metadata.key()),
+        AccountMeta::readonly_signer(update_authority.key()),
+    ];
+
+    let instruction = Instruction {
+        program_id: &TOKEN_2022_ID,
+        accounts: &account_metas,
+        data,
+    };
+
+    invoke_signed(&instruction, &[metadata, update_authority], signers)
+}
+
+/// Write `value` at `offset` as a `u32` length followed by its bytes.
+///
+/// Returns the offset following the string, or
+/// [`ProgramError::InvalidArgument`] if it does not fit in `data`.
+#[inline(always)]
+fn write_str(
+    data: &mut [MaybeUninit<u8>],
+    offset: usize,
+    value: &str,
+) -> Result<usize, ProgramError> {
+    let end = offset + 4 + value.len();
+    if end > data.len() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    write_bytes(&mut data[offset..], &(value.len() as u32).to_le_bytes());
+    write_bytes(&mut data[offset + 4..], value.as_bytes());
+
+    Ok(end)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::{extensions::tests::account_with_extensions, test_utils::account};
+
+    /// Returns `value` encoded as a length-prefixed string.
+    fn encoded(value: &str) -> Vec<u8> {
+        let mut bytes = (value.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    /// Returns the mint data with a `TokenMetadata` extension holding the
+    /// fixed fields followed by `additional_metadata`.
+    fn mint_with_metadata(additional_metadata: &[u8]) -> Vec<u8> {
+        let mut value = [3; 32].to_vec();
+        value.extend_from_slice(&[2; 32]);
+        value.extend_from_slice(&encoded("Token"));
+        value.extend_from_slice(&encoded("TKN"));
+        value.extend_from_slice(&encoded("https://example.com"));
+        value.extend_from_slice(additional_metadata);
+
+        account_with_extensions(BaseState::Mint, &[(ExtensionType::TokenMetadata, &value)])
+    }
+
+    /// Returns the encoded user-defined fields.
+    fn fields(fields: &[(&str, &str)]) -> Vec<u8> {
+        let mut bytes = (fields.len() as u32).to_le_bytes().to_vec();
+        for (key, value) in fields {
+            bytes.extend_from_slice(&encoded(key));
+            bytes.extend_from_slice(&encoded(value));
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_token_metadata() {
+        let data = mint_with_metadata(&fields(&[]));
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+
+        assert_eq!(metadata.update_authority(), Some(&[3; 32]));
+        assert_eq!(metadata.mint(), &[2; 32]);
+        assert_eq!(metadata.name(), "Token");
+        assert_eq!(metadata.symbol(), "TKN");
+        assert_eq!(metadata.uri(), "https://example.com");
+        assert_eq!(metadata.additional_metadata().next(), None);
+
+        // the fixed fields are truncated
+        let mut value = [3; 32].to_vec();
+        value.extend_from_slice(&[2; 32]);
+        value.extend_from_slice(&100u32.to_le_bytes());
+        value.extend_from_slice(b"Token");
+        let data =
+            account_with_extensions(BaseState::Mint, &[(ExtensionType::TokenMetadata, &value)]);
+        assert_eq!(TokenMetadata::from_bytes(&data), None);
+
+        let data = account_with_extensions(BaseState::Mint, &[]);
+        assert_eq!(TokenMetadata::from_bytes(&data), None);
+    }
+
+    #[test]
+    fn test_additional_metadata() {
+        let data = mint_with_metadata(&fields(&[("color", "blue")]));
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        assert_eq!(
+            metadata.additional_metadata().collect::<Vec<_>>(),
+            [("color", "blue")]
+        );
+
+        let expected = [("color", "blue"), ("", "empty key"), ("size", "")];
+        let data = mint_with_metadata(&fields(&expected));
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        assert_eq!(metadata.additional_metadata().collect::<Vec<_>>(), expected);
+
+        // no additional metadata at all
+        let data = mint_with_metadata(&[]);
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        assert_eq!(metadata.additional_metadata().next(), None);
+    }
+
+    #[test]
+    fn test_additional_metadata_truncated() {
+        let encoded_fields = fields(&[("color", "blue"), ("size", "large")]);
+
+        // the second value is cut short
+        let data = mint_with_metadata(&encoded_fields[..encoded_fields.len() - 2]);
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        let mut fields_iter = metadata.additional_metadata();
+        assert_eq!(fields_iter.next(), Some(("color", "blue")));
+        assert_eq!(fields_iter.next(), None);
+        assert_eq!(fields_iter.next(), None);
+
+        // the count exceeds the stored fields
+        let mut bytes = encoded_fields.clone();
+        bytes[..4].copy_from_slice(&3u32.to_le_bytes());
+        let data = mint_with_metadata(&bytes);
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        assert_eq!(metadata.additional_metadata().count(), 2);
+
+        // a length prefix past the end of the extension
+        let mut bytes = fields(&[]);
+        bytes[..4].copy_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(b"key");
+        let data = mint_with_metadata(&bytes);
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        assert_eq!(metadata.additional_metadata().next(), None);
+
+        // a key that is not valid UTF-8
+        let mut bytes = fields(&[("ab", "value")]);
+        bytes[8] = 0xff;
+        let data = mint_with_metadata(&bytes);
+        let metadata = TokenMetadata::from_bytes(&data).unwrap();
+        assert_eq!(metadata.additional_metadata().next(), None);
+    }
+
+    #[test]
+    fn test_discriminators() {
+        // first 8 bytes of the sha256 of the instruction names
+        assert_eq!(
+            INITIALIZE_DISCRIMINATOR,
+            [0xd2, 0xe1, 0x1e, 0xa2, 0x58, 0xb8, 0x4d, 0x8d]
+        );
+        assert_eq!(
+            UPDATE_FIELD_DISCRIMINATOR,
+            [0xdd, 0xe9, 0x31, 0x2d, 0xb5, 0xca, 0xdc, 0xc8]
+        );
+        assert_eq!(
+            REMOVE_KEY_DISCRIMINATOR,
+            [0xea, 0x12, 0x20, 0x38, 0x59, 0x8d, 0x25, 0xb5]
+        );
+        assert_eq!(
+            UPDATE_AUTHORITY_DISCRIMINATOR,
+            [0xd7, 0xe4, 0xa6, 0xe4, 0x54, 0x64, 0x56, 0x7b]
+        );
+    }
+
+    #[test]
+    fn test_initialize() {
+        let metadata = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = account([2; 32], Pubkey::default(), &[]);
+
+        let instruction = InitializeTokenMetadata {
+            metadata: &metadata.info,
+            update_authority: &authority.info,
+            mint: &metadata.info,
+            mint_authority: &authority.info,
+            name: "Token",
+            symbol: "TKN",
+            uri: "https://example.com",
+        };
+
+        let mut expected = INITIALIZE_DISCRIMINATOR.to_vec();
+        expected.extend_from_slice(&encoded("Token"));
+        expected.extend_from_slice(&encoded("TKN"));
+        expected.extend_from_slice(&encoded("https://example.com"));
+
+        let mut data = [UNINIT_BYTE; MAX_TOKEN_METADATA_DATA_LEN];
+        assert_eq!(
+            instruction.instruction_data(&mut data),
+            Ok(expected.as_slice())
+        );
+
+        // the strings do not fit in the instruction data
+        let uri = "a".repeat(MAX_TOKEN_METADATA_DATA_LEN);
+        let instruction = InitializeTokenMetadata {
+            uri: &uri,
+            ..instruction
+        };
+        assert_eq!(
+            instruction.instruction_data(&mut data),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_update_field() {
+        let metadata = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = account([2; 32], Pubkey::default(), &[]);
+
+        // multi-byte UTF-8 value: the length is the number of bytes
+        let value = "Café ☕";
+        assert_eq!(value.len(), 9);
+
+        for (field, field_bytes) in [
+            (Field::Name, std::vec![0]),
+            (Field::Symbol, std::vec![1]),
+            (Field::Uri, std::vec![2]),
+            (
+                Field::Key("drink"),
+                [&[3], encoded("drink").as_slice()].concat(),
+            ),
+        ] {
+            let instruction = UpdateTokenMetadataField {
+                metadata: &metadata.info,
+                update_authority: &authority.info,
+                field,
+                value,
+            };
+
+            let mut expected = UPDATE_FIELD_DISCRIMINATOR.to_vec();
+            expected.extend_from_slice(&field_bytes);
+            expected.extend_from_slice(&[9, 0, 0, 0]);
+            expected.extend_from_slice(&[0x43, 0x61, 0x66, 0xc3, 0xa9, 0x20, 0xe2, 0x98, 0x95]);
+
+            let mut data = [UNINIT_BYTE; MAX_TOKEN_METADATA_DATA_LEN];
+            assert_eq!(
+                instruction.instruction_data(&mut data),
+                Ok(expected.as_slice())
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_key() {
+        let metadata = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = account([2; 32], Pubkey::default(), &[]);
+
+        let instruction = RemoveTokenMetadataKey {
+            metadata: &metadata.info,
+            update_authority: &authority.info,
+            idempotent: true,
+            key: "drink",
+        };
+
+        let mut expected = REMOVE_KEY_DISCRIMINATOR.to_vec();
+        expected.push(1);
+        expected.extend_from_slice(&encoded("drink"));
+
+        let mut data = [UNINIT_BYTE; MAX_TOKEN_METADATA_DATA_LEN];
+        assert_eq!(
+            instruction.instruction_data(&mut data),
+            Ok(expected.as_slice())
+        );
+    }
+
+    #[test]
+    fn test_update_authority() {
+        let metadata = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = account([2; 32], Pubkey::default(), &[]);
+        let new_authority = [3; 32];
+
+        for new_authority in [Some(&new_authority), None] {
+            let instruction = UpdateTokenMetadataAuthority {
+                metadata: &metadata.info,
+                update_authority: &authority.info,
+                new_authority,
+            };
+
+            let mut expected = UPDATE_AUTHORITY_DISCRIMINATOR.to_vec();
+            expected.extend_from_slice(new_authority.unwrap_or(&[0; 32]));
+
+            assert_eq!(instruction.instruction_data().as_slice(), expected);
+        }
+    }
+}
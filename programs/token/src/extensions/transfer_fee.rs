@@ -0,0 +1,1101 @@
+use core::mem::MaybeUninit;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::{invoke_signed, slice_invoke_signed, MAX_CPI_ACCOUNTS},
+    instruction::{AccountMeta, Instruction, Signer},
+    pod::{Pod, Zeroable},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use super::{
+    get_extension_from_bytes, try_calculate_account_len, BaseState, Extension, ExtensionType,
+};
+use crate::{
+    instructions::InitializeMint2, write_bytes, write_optional_pubkey, TOKEN_2022_ID, UNINIT_BYTE,
+};
+
+/// Instruction discriminator of the `TransferFee` extension.
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+
+/// Basis points of a transfer fee of 100%.
+const MAX_FEE_BASIS_POINTS: u128 = 10_000;
+
+/// Maximum length of the `InitializeTransferFeeConfig` instruction data.
+const INITIALIZE_TRANSFER_FEE_CONFIG_MAX_LEN: usize = 2 + 33 + 33 + 2 + 8;
+
+/// Number of fixed accounts of `WithdrawWithheldTokensFromAccounts`.
+const WITHDRAW_FROM_ACCOUNTS_FIXED_ACCOUNTS: usize = 3;
+
+/// Transfer fee information.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TransferFee {
+    /// First epoch where the transfer fee takes effect.
+    epoch: [u8; 8],
+
+    /// Maximum fee assessed on transfers, expressed as an amount of tokens.
+    maximum_fee: [u8; 8],
+
+    /// Amount of transfer collected as fees, expressed as basis points of the
+    /// transfer amount, ie. increments of 0.01%.
+    transfer_fee_basis_points: [u8; 2],
+}
+
+unsafe impl Zeroable for TransferFee {}
+unsafe impl Pod for TransferFee {}
+
+impl TransferFee {
+    /// Return the first epoch where the transfer fee takes effect.
+    #[inline(always)]
+    pub fn epoch(&self) -> u64 {
+        u64::from_le_bytes(self.epoch)
+    }
+
+    /// Return the maximum fee assessed on transfers.
+    #[inline(always)]
+    pub fn maximum_fee(&self) -> u64 {
+        u64::from_le_bytes(self.maximum_fee)
+    }
+
+    /// Return the transfer fee in basis points of the transfer amount.
+    #[inline(always)]
+    pub fn transfer_fee_basis_points(&self) -> u16 {
+        u16::from_le_bytes(self.transfer_fee_basis_points)
+    }
+
+    /// Calculate the fee assessed on a transfer of `amount` tokens.
+    ///
+    /// The fee is rounded up and capped at the maximum fee.
+    #[inline]
+    pub fn calculate_fee(&self, amount: u64) -> u64 {
+        // The product of a `u64` and a `u16` always fits in a `u128`.
+        let fee = (amount as u128 * self.transfer_fee_basis_points() as u128)
+            .div_ceil(MAX_FEE_BASIS_POINTS);
+
+        core::cmp::min(fee, self.maximum_fee() as u128) as u64
+    }
+}
+
+/// Transfer fee extension data for mints.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TransferFeeConfig {
+    /// Optional authority to set the fee.
+    ///
+    /// A zeroed pubkey represents `None`.
+    transfer_fee_config_authority: Pubkey,
+
+    /// Withdraw from mint instructions must be signed by this key.
+    ///
+    /// A zeroed pubkey represents `None`.
+    withdraw_withheld_authority: Pubkey,
+
+    /// Withheld transfer fee tokens that have been moved to the mint for
+    /// withdrawal.
+    withheld_amount: [u8; 8],
+
+    /// Older transfer fee, used if the current epoch < `new_transfer_fee.epoch`.
+    older_transfer_fee: TransferFee,
+
+    /// Newer transfer fee, used if the current epoch >= `new_transfer_fee.epoch`.
+    newer_transfer_fee: TransferFee,
+}
+
+unsafe impl Zeroable for TransferFeeConfig {}
+unsafe impl Pod for TransferFeeConfig {}
+
+impl Extension for TransferFeeConfig {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeConfig;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl TransferFeeConfig {
+    /// Return the `TransferFeeConfig` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the authority to set the fee, if any.
+    #[inline]
+    pub fn transfer_fee_config_authority(&self) -> Option<&Pubkey> {
+        if self.transfer_fee_config_authority == Pubkey::default() {
+            None
+        } else {
+            Some(&self.transfer_fee_config_authority)
+        }
+    }
+
+    /// Return the authority to withdraw withheld fees, if any.
+    #[inline]
+    pub fn withdraw_withheld_authority(&self) -> Option<&Pubkey> {
+        if self.withdraw_withheld_authority == Pubkey::default() {
+            None
+        } else {
+            Some(&self.withdraw_withheld_authority)
+        }
+    }
+
+    /// Return the withheld transfer fee tokens that have been moved to the
+    /// mint for withdrawal.
+    ///
+    /// Fees withheld in token accounts are not included until they are
+    /// harvested to the mint.
+    #[inline(always)]
+    pub fn withheld_amount(&self) -> u64 {
+        u64::from_le_bytes(self.withheld_amount)
+    }
+
+    /// Return the older transfer fee.
+    #[inline(always)]
+    pub fn older_transfer_fee(&self) -> &TransferFee {
+        &self.older_transfer_fee
+    }
+
+    /// Return the newer transfer fee.
+    #[inline(always)]
+    pub fn newer_transfer_fee(&self) -> &TransferFee {
+        &self.newer_transfer_fee
+    }
+
+    /// Return the transfer fee in effect at `epoch`.
+    ///
+    /// The newer transfer fee takes effect at its epoch, so it is returned
+    /// from that epoch onwards; the older transfer fee is returned before it.
+    #[inline(always)]
+    pub fn current_fee(&self, epoch: u64) -> &TransferFee {
+        if self.newer_transfer_fee.epoch() <= epoch {
+            &self.newer_transfer_fee
+        } else {
+            &self.older_transfer_fee
+        }
+    }
+
+    /// Indicate whether transfers are charged a fee at `epoch`.
+    #[inline(always)]
+    pub fn charges_fee(&self, epoch: u64) -> bool {
+        self.current_fee(epoch).transfer_fee_basis_points() != 0
+    }
+}
+
+/// Transfer fee extension data for token accounts.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TransferFeeAmount {
+    /// Amount withheld during transfers, to be harvested to the mint.
+    withheld_amount: [u8; 8],
+}
+
+unsafe impl Zeroable for TransferFeeAmount {}
+unsafe impl Pod for TransferFeeAmount {}
+
+impl Extension for TransferFeeAmount {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeAmount;
+    const BASE_STATE: BaseState = BaseState::TokenAccount;
+}
+
+impl TransferFeeAmount {
+    /// Return the `TransferFeeAmount` extension from the given token account
+    /// bytes.
+    #[inline(always)]
+    pub fn from_bytes(account_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(account_bytes)
+    }
+
+    /// Return the amount withheld during transfers.
+    #[inline(always)]
+    pub fn withheld_amount(&self) -> u64 {
+        u64::from_le_bytes(self.withheld_amount)
+    }
+}
+
+/// Initialize the transfer fee on a new mint.
+///
+/// This instruction must be executed before the mint is initialized.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint to initialize.
+pub struct InitializeTransferFeeConfig<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Authority that can update the fees.
+    pub transfer_fee_config_authority: Option<&'a Pubkey>,
+    /// Authority that can withdraw withheld fees.
+    pub withdraw_withheld_authority: Option<&'a Pubkey>,
+    /// Amount of transfer collected as fees, expressed as basis points of the
+    /// transfer amount.
+    pub transfer_fee_basis_points: u16,
+    /// Maximum fee assessed on transfers.
+    pub maximum_fee: u64,
+}
+
+impl InitializeTransferFeeConfig<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 1] = [AccountMeta::writable(self.mint.key())];
+
+        let mut instruction_data = [UNINIT_BYTE; INITIALIZE_TRANSFER_FEE_CONFIG_MAX_LEN];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: self.instruction_data(&mut instruction_data),
+        };
+
+        invoke_signed(&instruction, &[self.mint], signers)
+    }
+
+    // Instruction data layout:
+    // -  [0]: extension instruction discriminator (1 byte, u8)
+    // -  [1]: `TransferFee` instruction discriminator (1 byte, u8)
+    // -  [2..]: transfer_fee_config_authority presence flag (1 byte, u8)
+    //    followed by the authority if present (32 bytes, Pubkey)
+    // -  [..]: withdraw_withheld_authority presence flag (1 byte, u8)
+    //    followed by the authority if present (32 bytes, Pubkey)
+    // -  [..]: transfer_fee_basis_points (2 bytes, u16)
+    // -  [..]: maximum_fee (8 bytes, u64)
+    #[inline(always)]
+    fn instruction_data<'d>(
+        &self,
+        data: &'d mut [MaybeUninit<u8>; INITIALIZE_TRANSFER_FEE_CONFIG_MAX_LEN],
+    ) -> &'d [u8] {
+        write_bytes(data, &[TRANSFER_FEE_EXTENSION, 0]);
+        let mut offset = 2;
+
+        offset += write_optional_pubkey(&mut data[offset..], self.transfer_fee_config_authority);
+        offset += write_optional_pubkey(&mut data[offset..], self.withdraw_withheld_authority);
+
+        write_bytes(
+            &mut data[offset..offset + 2],
+            &self.transfer_fee_basis_points.to_le_bytes(),
+        );
+        offset += 2;
+        write_bytes(
+            &mut data[offset..offset + 8],
+            &self.maximum_fee.to_le_bytes(),
+        );
+        offset += 8;
+
+        // SAFETY: The first `offset` bytes have been initialized.
+        unsafe { core::slice::from_raw_parts(data.as_ptr() as _, offset) }
+    }
+}
+
+/// Create and initialize a mint with the `TransferFeeConfig` extension.
+///
+/// The mint account is created with the length of a mint holding the
+/// extension and funded to be rent exempt, then the extension is initialized
+/// before the mint itself, as required by the Token-2022 program.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` The funding account.
+///   1. `[WRITE, SIGNER]` The mint to create.
+pub struct CreateTransferFeeMint<'a> {
+    /// Funding account.
+    pub payer: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Decimals.
+    pub decimals: u8,
+    /// Mint Authority.
+    pub mint_authority: &'a Pubkey,
+    /// Freeze Authority.
+    pub freeze_authority: Option<&'a Pubkey>,
+    /// Authority that can update the fees.
+    pub transfer_fee_config_authority: Option<&'a Pubkey>,
+    /// Authority that can withdraw withheld fees.
+    pub withdraw_withheld_authority: Option<&'a Pubkey>,
+    /// Amount of transfer collected as fees, expressed as basis points of the
+    /// transfer amount.
+    pub transfer_fee_basis_points: u16,
+    /// Maximum fee assessed on transfers.
+    pub maximum_fee: u64,
+}
+
+impl CreateTransferFeeMint<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    /// Invoke the instructions creating the mint, using `signers` for the
+    /// creation of the account.
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let space =
+            try_calculate_account_len(BaseState::Mint, &[ExtensionType::TransferFeeConfig])?;
+
+        CreateAccount {
+            from: self.payer,
+            to: self.mint,
+            lamports: Rent::get()?.minimum_balance(space),
+            space: space as u64,
+            owner: &TOKEN_2022_ID,
+        }
+        .invoke_signed(signers)?;
+
+        // extensions must be initialized before the mint
+        InitializeTransferFeeConfig {
+            mint: self.mint,
+            transfer_fee_config_authority: self.transfer_fee_config_authority,
+            withdraw_withheld_authority: self.withdraw_withheld_authority,
+            transfer_fee_basis_points: self.transfer_fee_basis_points,
+            maximum_fee: self.maximum_fee,
+        }
+        .invoke()?;
+
+        InitializeMint2 {
+            mint: self.mint,
+            decimals: self.decimals,
+            mint_authority: self.mint_authority,
+            freeze_authority: self.freeze_authority,
+        }
+        .invoke_signed_with_program_id(&TOKEN_2022_ID, &[])
+    }
+}
+
+/// Set the transfer fee. Only supported for mints that include the
+/// `TransferFeeConfig` extension.
+///
+/// The new fee takes effect two epochs after it is set.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint.
+///   1. `[SIGNER]` The mint's fee account owner.
+pub struct SetTransferFee<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Transfer Fee Config Authority.
+    pub authority: &'a AccountInfo,
+    /// Amount of transfer collected as fees, expressed as basis points of the
+    /// transfer amount.
+    pub transfer_fee_basis_points: u16,
+    /// Maximum fee assessed on transfers.
+    pub maximum_fee: u64,
+}
+
+impl SetTransferFee<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 2] = [
+            AccountMeta::writable(self.mint.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &self.instruction_data(),
+        };
+
+        invoke_signed(&instruction, &[self.mint, self.authority], signers)
+    }
+
+    // Instruction data layout:
+    // -  [0]: extension instruction discriminator (1 byte, u8)
+    // -  [1]: `TransferFee` instruction discriminator (1 byte, u8)
+    // -  [2..4]: transfer_fee_basis_points (2 bytes, u16)
+    // -  [4..12]: maximum_fee (8 bytes, u64)
+    #[inline(always)]
+    fn instruction_data(&self) -> [u8; 12] {
+        let mut data = [0; 12];
+        data[..2].copy_from_slice(&[TRANSFER_FEE_EXTENSION, 5]);
+        data[2..4].copy_from_slice(&self.transfer_fee_basis_points.to_le_bytes());
+        data[4..].copy_from_slice(&self.maximum_fee.to_le_bytes());
+        data
+    }
+}
+
+/// Transfer tokens from one account to another, asserting the transfer fee
+/// calculated by the client.
+///
+/// The token program rejects the transfer if `fee` does not match the fee
+/// calculated from the mint's `TransferFeeConfig`.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The source account.
+///   1. `[]` The token mint.
+///   2. `[WRITE]` The destination account.
+///   3. `[SIGNER]` The source account's owner/delegate.
+pub struct TransferCheckedWithFee<'a> {
+    /// Source Account.
+    pub source: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Destination Account.
+    pub destination: &'a AccountInfo,
+    /// Owner or Delegate of the Source Account.
+    pub authority: &'a AccountInfo,
+    /// Amount of tokens to transfer.
+    pub amount: u64,
+    /// Decimals of the mint.
+    pub decimals: u8,
+    /// Expected fee assessed on the transfer.
+    pub fee: u64,
+}
+
+impl TransferCheckedWithFee<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 4] = [
+            AccountMeta::writable(self.source.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::writable(self.destination.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &self.instruction_data(),
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.source, self.mint, self.destination, self.authority],
+            signers,
+        )
+    }
+
+    // Instruction data layout:
+    // -  [0]: extension instruction discriminator (1 byte, u8)
+    // -  [1]: `TransferFee` instruction discriminator (1 byte, u8)
+    // -  [2..10]: amount (8 bytes, u64)
+    // -  [10]: decimals (1 byte, u8)
+    // -  [11..19]: fee (8 bytes, u64)
+    #[inline(always)]
+    pub(crate) fn instruction_data(&self) -> [u8; 19] {
+        let mut data = [0; 19];
+        data[..2].copy_from_slice(&[TRANSFER_FEE_EXTENSION, 1]);
+        data[2..10].copy_from_slice(&self.amount.to_le_bytes());
+        data[10] = self.decimals;
+        data[11..].copy_from_slice(&self.fee.to_le_bytes());
+        data
+    }
+}
+
+/// Transfer all withheld tokens in the mint to an account.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The token mint.
+///   1. `[WRITE]` The fee receiver account.
+///   2. `[SIGNER]` The mint's `withdraw_withheld_authority`.
+pub struct WithdrawWithheldTokensFromMint<'a> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Destination Account.
+    pub destination: &'a AccountInfo,
+    /// Withdraw Withheld Authority.
+    pub authority: &'a AccountInfo,
+}
+
+impl WithdrawWithheldTokensFromMint<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // account metadata
+        let account_metas: [AccountMeta; 3] = [
+            AccountMeta::writable(self.mint.key()),
+            AccountMeta::writable(self.destination.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0]: extension instruction discriminator (1 byte, u8)
+        // -  [1]: `TransferFee` instruction discriminator (1 byte, u8)
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: &account_metas,
+            data: &[TRANSFER_FEE_EXTENSION, 2],
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.mint, self.destination, self.authority],
+            signers,
+        )
+    }
+}
+
+/// Transfer all withheld tokens to an account from token accounts of the
+/// mint.
+///
+/// ### Accounts:
+///   0. `[]` The token mint.
+///   1. `[WRITE]` The fee receiver account.
+///   2. `[SIGNER]` The mint's `withdraw_withheld_authority`.
+///   3. `..3+N` `[WRITE]` The source accounts to withdraw from.
+pub struct WithdrawWithheldTokensFromAccounts<'a, 'b> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Destination Account.
+    pub destination: &'a AccountInfo,
+    /// Withdraw Withheld Authority.
+    pub authority: &'a AccountInfo,
+    /// Source Accounts.
+    pub sources: &'b [&'a AccountInfo],
+}
+
+impl<'a> WithdrawWithheldTokensFromAccounts<'a, '_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        const UNINIT_META: MaybeUninit<AccountMeta> = MaybeUninit::<AccountMeta>::uninit();
+        const UNINIT_INFO: MaybeUninit<&AccountInfo> = MaybeUninit::<&AccountInfo>::uninit();
+
+        // We don't know num_accounts at compile time, so we use MAX_CPI_ACCOUNTS
+        let mut account_metas = [UNINIT_META; MAX_CPI_ACCOUNTS];
+        let mut account_infos = [UNINIT_INFO; MAX_CPI_ACCOUNTS];
+
+        let account_metas = self.account_metas(&mut account_metas)?;
+
+        account_infos[0].write(self.mint);
+        account_infos[1].write(self.destination);
+        account_infos[2].write(self.authority);
+
+        for (account_info, source) in account_infos[WITHDRAW_FROM_ACCOUNTS_FIXED_ACCOUNTS..]
+            .iter_mut()
+            .zip(self.sources.iter())
+        {
+            account_info.write(source);
+        }
+
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: account_metas,
+            data: &self.instruction_data()?,
+        };
+
+        // SAFETY: The first `account_metas.len()` infos have been initialized.
+        let account_infos = unsafe {
+            core::slice::from_raw_parts(account_infos.as_ptr() as _, account_metas.len())
+        };
+
+        slice_invoke_signed(&instruction, account_infos, signers)
+    }
+
+    /// Write the account metas of the instruction into `account_metas`,
+    /// returning the initialized metas.
+    #[inline(always)]
+    fn account_metas<'m>(
+        &self,
+        account_metas: &'m mut [MaybeUninit<AccountMeta<'a>>; MAX_CPI_ACCOUNTS],
+    ) -> Result<&'m [AccountMeta<'a>], ProgramError> {
+        let num_accounts = WITHDRAW_FROM_ACCOUNTS_FIXED_ACCOUNTS + self.sources.len();
+        if num_accounts > MAX_CPI_ACCOUNTS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        account_metas[0].write(AccountMeta::readonly(self.mint.key()));
+        account_metas[1].write(AccountMeta::writable(self.destination.key()));
+        account_metas[2].write(AccountMeta::readonly_signer(self.authority.key()));
+
+        for (account_meta, source) in account_metas[WITHDRAW_FROM_ACCOUNTS_FIXED_ACCOUNTS..]
+            .iter_mut()
+            .zip(self.sources.iter())
+        {
+            account_meta.write(AccountMeta::writable(source.key()));
+        }
+
+        // SAFETY: `num_accounts` metas have been initialized.
+        Ok(unsafe { core::slice::from_raw_parts(account_metas.as_ptr() as _, num_accounts) })
+    }
+
+    // Instruction data layout:
+    // -  [0]: extension instruction discriminator (1 byte, u8)
+    // -  [1]: `TransferFee` instruction discriminator (1 byte, u8)
+    // -  [2]: number of source accounts (1 byte, u8)
+    #[inline(always)]
+    fn instruction_data(&self) -> Result<[u8; 3], ProgramError> {
+        let num_token_accounts =
+            u8::try_from(self.sources.len()).map_err(|_| ProgramError::InvalidArgument)?;
+        Ok([TRANSFER_FEE_EXTENSION, 3, num_token_accounts])
+    }
+}
+
+/// Permissionless instruction to transfer all withheld tokens to the mint.
+///
+/// Succeeds for frozen accounts. Accounts that are not token accounts of the
+/// mint are skipped.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The mint.
+///   1. `..1+N` `[WRITE]` The source accounts to harvest from.
+pub struct HarvestWithheldTokensToMint<'a, 'b> {
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Source Accounts.
+    pub sources: &'b [&'a AccountInfo],
+}
+
+impl<'a> HarvestWithheldTokensToMint<'a, '_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        const UNINIT_META: MaybeUninit<AccountMeta> = MaybeUninit::<AccountMeta>::uninit();
+        const UNINIT_INFO: MaybeUninit<&AccountInfo> = MaybeUninit::<&AccountInfo>::uninit();
+
+        // We don't know num_accounts at compile time, so we use MAX_CPI_ACCOUNTS
+        let mut account_metas = [UNINIT_META; MAX_CPI_ACCOUNTS];
+        let mut account_infos = [UNINIT_INFO; MAX_CPI_ACCOUNTS];
+
+        let account_metas = self.account_metas(&mut account_metas)?;
+
+        account_infos[0].write(self.mint);
+
+        for (account_info, source) in account_infos[1..].iter_mut().zip(self.sources.iter()) {
+            account_info.write(source);
+        }
+
+        // Instruction data layout:
+        // -  [0]: extension instruction discriminator (1 byte, u8)
+        // -  [1]: `TransferFee` instruction discriminator (1 byte, u8)
+        let instruction = Instruction {
+            program_id: &TOKEN_2022_ID,
+            accounts: account_metas,
+            data: &[TRANSFER_FEE_EXTENSION, 4],
+        };
+
+        // SAFETY: The first `account_metas.len()` infos have been initialized.
+        let account_infos = unsafe {
+            core::slice::from_raw_parts(account_infos.as_ptr() as _, account_metas.len())
+        };
+
+        slice_invoke_signed(&instruction, account_infos, signers)
+    }
+
+    /// Write the account metas of the instruction into `account_metas`,
+    /// returning the initialized metas.
+    #[inline(always)]
+    fn account_metas<'m>(
+        &self,
+        account_metas: &'m mut [MaybeUninit<AccountMeta<'a>>; MAX_CPI_ACCOUNTS],
+    ) -> Result<&'m [AccountMeta<'a>], ProgramError> {
+        let num_accounts = 1 + self.sources.len();
+        if num_accounts > MAX_CPI_ACCOUNTS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        account_metas[0].write(AccountMeta::writable(self.mint.key()));
+
+        for (account_meta, source) in account_metas[1..].iter_mut().zip(self.sources.iter()) {
+            account_meta.write(AccountMeta::writable(source.key()));
+        }
+
+        // SAFETY: `num_accounts` metas have been initialized.
+        Ok(unsafe { core::slice::from_raw_parts(account_metas.as_ptr() as _, num_accounts) })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use pinocchio::pod::cast_ref;
+
+    use super::*;
+    use crate::{
+        extensions::{tests::account_with_extensions, with_extension},
+        test_utils::account,
+        TOKEN_2022_ID,
+    };
+
+    /// Returns the bytes of a `TransferFee`.
+    pub(crate) fn transfer_fee(epoch: u64, maximum_fee: u64, basis_points: u16) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&epoch.to_le_bytes());
+        bytes.extend_from_slice(&maximum_fee.to_le_bytes());
+        bytes.extend_from_slice(&basis_points.to_le_bytes());
+        bytes
+    }
+
+    /// Returns the bytes of a `TransferFeeConfig`.
+    pub(crate) fn transfer_fee_config(
+        config_authority: Pubkey,
+        withdraw_authority: Pubkey,
+        withheld_amount: u64,
+        older: (u64, u64, u16),
+        newer: (u64, u64, u16),
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&config_authority);
+        bytes.extend_from_slice(&withdraw_authority);
+        bytes.extend_from_slice(&withheld_amount.to_le_bytes());
+        bytes.extend_from_slice(&transfer_fee(older.0, older.1, older.2));
+        bytes.extend_from_slice(&transfer_fee(newer.0, newer.1, newer.2));
+        bytes
+    }
+
+    #[test]
+    fn test_transfer_fee_config() {
+        let config = transfer_fee_config([1; 32], [0; 32], 10, (1, 1_000, 50), (5, 2_000, 100));
+        let mint = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TransferFeeConfig, &config)],
+        );
+
+        let extension = TransferFeeConfig::from_bytes(&mint).unwrap();
+
+        assert_eq!(extension.transfer_fee_config_authority(), Some(&[1; 32]));
+        assert!(extension.withdraw_withheld_authority().is_none());
+        assert_eq!(extension.withheld_amount(), 10);
+        assert_eq!(extension.older_transfer_fee().epoch(), 1);
+        assert_eq!(extension.older_transfer_fee().maximum_fee(), 1_000);
+        assert_eq!(
+            extension.older_transfer_fee().transfer_fee_basis_points(),
+            50
+        );
+        assert_eq!(extension.newer_transfer_fee().epoch(), 5);
+        assert_eq!(extension.newer_transfer_fee().maximum_fee(), 2_000);
+        assert_eq!(
+            extension.newer_transfer_fee().transfer_fee_basis_points(),
+            100
+        );
+    }
+
+    #[test]
+    fn test_with_extension() {
+        let config = transfer_fee_config([1; 32], [2; 32], 0, (0, 1_000, 50), (0, 1_000, 50));
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TransferFeeConfig, &config)],
+        );
+        let mint = account([9; 32], TOKEN_2022_ID, &data);
+
+        let basis_points = with_extension(&mint.info, |config: &TransferFeeConfig| {
+            config.newer_transfer_fee().transfer_fee_basis_points()
+        });
+        assert_eq!(basis_points, Ok(50));
+
+        // the borrow is released after the closure runs
+        assert!(mint.info.try_borrow_mut_data().is_ok());
+
+        // missing extension
+        assert_eq!(
+            with_extension(
+                &mint.info,
+                |_: &crate::extensions::permanent_delegate::PermanentDelegate| ()
+            ),
+            Err(pinocchio::program_error::ProgramError::InvalidAccountData)
+        );
+
+        // wrong owner
+        let mint = account([9; 32], crate::ID, &data);
+        assert_eq!(
+            with_extension(&mint.info, |_: &TransferFeeConfig| ()),
+            Err(pinocchio::program_error::ProgramError::InvalidAccountOwner)
+        );
+    }
+
+    #[test]
+    fn test_withdraw_withheld_tokens_from_accounts() {
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let destination = account([2; 32], TOKEN_2022_ID, &[]);
+        let authority = account([3; 32], Pubkey::default(), &[]);
+        let sources = [
+            account([4; 32], TOKEN_2022_ID, &[]),
+            account([5; 32], TOKEN_2022_ID, &[]),
+            account([6; 32], TOKEN_2022_ID, &[]),
+        ];
+
+        let instruction = WithdrawWithheldTokensFromAccounts {
+            mint: &mint.info,
+            destination: &destination.info,
+            authority: &authority.info,
+            sources: &[&sources[0].info, &sources[1].info, &sources[2].info],
+        };
+
+        assert_eq!(instruction.instruction_data(), Ok([26, 3, 3]));
+
+        let mut account_metas = [const { MaybeUninit::uninit() }; MAX_CPI_ACCOUNTS];
+        let account_metas = instruction.account_metas(&mut account_metas).unwrap();
+        let expected = [
+            ([1; 32], false, false),
+            ([2; 32], true, false),
+            ([3; 32], false, true),
+            ([4; 32], true, false),
+            ([5; 32], true, false),
+            ([6; 32], true, false),
+        ];
+
+        assert_eq!(account_metas.len(), expected.len());
+        for (meta, (key, is_writable, is_signer)) in account_metas.iter().zip(expected) {
+            assert_eq!(meta.pubkey, &key);
+            assert_eq!(meta.is_writable, is_writable);
+            assert_eq!(meta.is_signer, is_signer);
+        }
+    }
+
+    #[test]
+    fn test_transfer_fee_amount() {
+        let data = account_with_extensions(
+            BaseState::TokenAccount,
+            &[(ExtensionType::TransferFeeAmount, &1_234u64.to_le_bytes())],
+        );
+
+        let amount = TransferFeeAmount::from_bytes(&data).unwrap();
+        assert_eq!(amount.withheld_amount(), 1_234);
+
+        // the extension is only read from token accounts
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TransferFeeAmount, &1_234u64.to_le_bytes())],
+        );
+        assert!(TransferFeeAmount::from_bytes(&data).is_none());
+    }
+
+    #[test]
+    fn test_create_transfer_fee_mint() {
+        use pinocchio::{mock, sysvars::rent::Rent};
+
+        mock::reset();
+        mock::set_rent(Rent::default());
+
+        let payer = account([1; 32], Pubkey::default(), &[]);
+        let mint = account([2; 32], Pubkey::default(), &[]);
+        let mint_authority = [3; 32];
+        let fee_authority = [4; 32];
+
+        CreateTransferFeeMint {
+            payer: &payer.info,
+            mint: &mint.info,
+            decimals: 6,
+            mint_authority: &mint_authority,
+            freeze_authority: None,
+            transfer_fee_config_authority: Some(&fee_authority),
+            withdraw_withheld_authority: None,
+            transfer_fee_basis_points: 50,
+            maximum_fee: 1_000,
+        }
+        .invoke()
+        .unwrap();
+
+        let invocations = mock::take_invocations();
+        let instructions: Vec<_> = invocations
+            .iter()
+            .map(|invocation| &invocation.instruction)
+            .collect();
+        assert_eq!(instructions.len(), 3);
+
+        // the account is created with room for the extension
+        let space = 278;
+        assert_eq!(instructions[0].program_id, pinocchio_system::ID);
+        let mut expected = std::vec![0; 4];
+        expected.extend_from_slice(&Rent::default().minimum_balance(space).to_le_bytes());
+        expected.extend_from_slice(&(space as u64).to_le_bytes());
+        expected.extend_from_slice(&TOKEN_2022_ID);
+        assert_eq!(instructions[0].data, expected);
+        assert_eq!(instructions[0].accounts[0].0, [1; 32]);
+        assert_eq!(instructions[0].accounts[1].0, [2; 32]);
+
+        // then the extension is initialized, followed by the mint
+        assert_eq!(instructions[1].program_id, TOKEN_2022_ID);
+        let mut expected = std::vec![26, 0, 1];
+        expected.extend_from_slice(&fee_authority);
+        expected.push(0);
+        expected.extend_from_slice(&50u16.to_le_bytes());
+        expected.extend_from_slice(&1_000u64.to_le_bytes());
+        assert_eq!(instructions[1].data, expected);
+
+        assert_eq!(instructions[2].program_id, TOKEN_2022_ID);
+        assert_eq!(instructions[2].data[..2], [20, 6]);
+        assert_eq!(instructions[2].data[2..34], mint_authority);
+        assert_eq!(instructions[2].data[34], 0);
+
+        for instruction in &instructions[1..] {
+            assert_eq!(instruction.accounts.len(), 1);
+            assert_eq!(instruction.accounts[0].0, [2; 32]);
+        }
+    }
+
+    #[test]
+    fn test_harvest_withheld_tokens_to_mint() {
+        pinocchio::mock::reset();
+
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let sources = [
+            account([2; 32], TOKEN_2022_ID, &[]),
+            account([3; 32], TOKEN_2022_ID, &[]),
+        ];
+
+        HarvestWithheldTokensToMint {
+            mint: &mint.info,
+            sources: &[&sources[0].info, &sources[1].info],
+        }
+        .invoke()
+        .unwrap();
+
+        let invocations = pinocchio::mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(instruction.data, [26, 4]);
+
+        let expected = [[1; 32], [2; 32], [3; 32]];
+        assert_eq!(instruction.accounts.len(), expected.len());
+        for ((key, flags), expected) in instruction.accounts.iter().zip(expected) {
+            assert_eq!(key, &expected);
+            assert!(flags.writable() && !flags.signer());
+        }
+    }
+
+    #[test]
+    fn test_withdraw_withheld_tokens_too_many_sources() {
+        let info = account([1; 32], TOKEN_2022_ID, &[]);
+        let sources = [&info.info; MAX_CPI_ACCOUNTS - WITHDRAW_FROM_ACCOUNTS_FIXED_ACCOUNTS + 1];
+
+        let instruction = WithdrawWithheldTokensFromAccounts {
+            mint: &info.info,
+            destination: &info.info,
+            authority: &info.info,
+            sources: &sources,
+        };
+
+        let mut account_metas = [const { MaybeUninit::uninit() }; MAX_CPI_ACCOUNTS];
+        assert_eq!(
+            instruction.account_metas(&mut account_metas).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_initialize_transfer_fee_config() {
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let config_authority = [2; 32];
+        let withdraw_authority = [3; 32];
+
+        for (transfer_fee_config_authority, withdraw_withheld_authority) in [
+            (Some(&config_authority), Some(&withdraw_authority)),
+            (Some(&config_authority), None),
+            (None, Some(&withdraw_authority)),
+            (None, None),
+        ] {
+            let instruction = InitializeTransferFeeConfig {
+                mint: &mint.info,
+                transfer_fee_config_authority,
+                withdraw_withheld_authority,
+                transfer_fee_basis_points: 0x0102,
+                maximum_fee: 0x0304,
+            };
+
+            let mut expected = std::vec![26, 0];
+            for authority in [transfer_fee_config_authority, withdraw_withheld_authority] {
+                match authority {
+                    Some(authority) => {
+                        expected.push(1);
+                        expected.extend_from_slice(authority);
+                    }
+                    None => expected.push(0),
+                }
+            }
+            expected.extend_from_slice(&[0x02, 0x01]);
+            expected.extend_from_slice(&[0x04, 0x03, 0, 0, 0, 0, 0, 0]);
+
+            let mut data = [UNINIT_BYTE; INITIALIZE_TRANSFER_FEE_CONFIG_MAX_LEN];
+            assert_eq!(instruction.instruction_data(&mut data), expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_set_transfer_fee() {
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let authority = account([2; 32], Pubkey::default(), &[]);
+
+        let instruction = SetTransferFee {
+            mint: &mint.info,
+            authority: &authority.info,
+            transfer_fee_basis_points: 250,
+            maximum_fee: u64::MAX,
+        };
+
+        assert_eq!(
+            instruction.instruction_data(),
+            [26, 5, 250, 0, 255, 255, 255, 255, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn test_current_fee() {
+        // no fee before epoch 10, 1% fee from epoch 10
+        let bytes = transfer_fee_config([0; 32], [0; 32], 0, (0, 0, 0), (10, 1_000, 100));
+        let config = cast_ref::<TransferFeeConfig>(&bytes).unwrap();
+
+        assert_eq!(config.current_fee(9).transfer_fee_basis_points(), 0);
+        assert!(!config.charges_fee(9));
+
+        assert_eq!(config.current_fee(10).transfer_fee_basis_points(), 100);
+        assert!(config.charges_fee(10));
+
+        assert_eq!(config.current_fee(11).transfer_fee_basis_points(), 100);
+        assert!(config.charges_fee(11));
+    }
+
+    #[test]
+    fn test_calculate_fee() {
+        let bytes = transfer_fee(0, 5_000, 100);
+        let fee = cast_ref::<TransferFee>(&bytes).unwrap();
+
+        assert_eq!(fee.calculate_fee(0), 0);
+        // 1% of 1 token rounds up
+        assert_eq!(fee.calculate_fee(1), 1);
+        assert_eq!(fee.calculate_fee(10_000), 100);
+        assert_eq!(fee.calculate_fee(10_001), 101);
+        // capped at the maximum fee
+        assert_eq!(fee.calculate_fee(u64::MAX), 5_000);
+
+        let bytes = transfer_fee(0, 5_000, 0);
+        let fee = cast_ref::<TransferFee>(&bytes).unwrap();
+        assert_eq!(fee.calculate_fee(10_000), 0);
+    }
+
+    #[test]
+    fn test_transfer_checked_with_fee() {
+        let info = account([1; 32], TOKEN_2022_ID, &[]);
+
+        let instruction = TransferCheckedWithFee {
+            source: &info.info,
+            mint: &info.info,
+            destination: &info.info,
+            authority: &info.info,
+            amount: 1_000_000,
+            decimals: 6,
+            fee: 5_000,
+        };
+
+        assert_eq!(
+            instruction.instruction_data(),
+            [26, 1, 64, 66, 15, 0, 0, 0, 0, 0, 6, 136, 19, 0, 0, 0, 0, 0, 0]
+        );
+    }
+}
@@ -0,0 +1,693 @@
+use core::mem::MaybeUninit;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::MAX_CPI_ACCOUNTS,
+    instruction::{AccountMeta, Instruction},
+    pod::{cast_slice, Pod, Zeroable},
+    program_error::ProgramError,
+    pubkey::{find_program_address, try_find_program_address, Pubkey, MAX_SEEDS, MAX_SEED_LEN},
+};
+
+use super::{get_extension_from_bytes, BaseState, Extension, ExtensionType};
+
+/// Seed of the validation state account holding the extra account metas.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+
+/// Discriminator of the `Execute` instruction of the transfer hook interface.
+///
+/// This is also the TLV type of the extra account metas in the validation
+/// state account.
+pub const EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+
+/// Number of accounts of the `Execute` instruction preceding the extra
+/// account metas: source, mint, destination, authority and validation state.
+const EXECUTE_ACCOUNTS: usize = 5;
+
+/// Length of the TLV type of the validation state data.
+const TLV_TYPE_LEN: usize = 8;
+
+/// Length of the TLV length of the validation state data.
+const TLV_LENGTH_LEN: usize = 4;
+
+/// Discriminator bit of metas derived from the seeds of another program.
+const EXTERNAL_PDA_FLAG: u8 = 1 << 7;
+
+/// Transfer hook extension data for mints.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct TransferHook {
+    /// Authority that can set the transfer hook program ID.
+    ///
+    /// A zeroed pubkey represents `None`.
+    authority: Pubkey,
+
+    /// Program called on every transfer.
+    ///
+    /// A zeroed pubkey represents `None`.
+    program_id: Pubkey,
+}
+
+unsafe impl Zeroable for TransferHook {}
+unsafe impl Pod for TransferHook {}
+
+impl Extension for TransferHook {
+    const TYPE: ExtensionType = ExtensionType::TransferHook;
+    const BASE_STATE: BaseState = BaseState::Mint;
+}
+
+impl TransferHook {
+    /// Return the `TransferHook` extension from the given mint bytes.
+    #[inline(always)]
+    pub fn from_bytes(mint_bytes: &[u8]) -> Option<&Self> {
+        get_extension_from_bytes(mint_bytes)
+    }
+
+    /// Return the authority that can set the transfer hook program ID, if any.
+    #[inline]
+    pub fn authority(&self) -> Option<&Pubkey> {
+        if self.authority == Pubkey::default() {
+            None
+        } else {
+            Some(&self.authority)
+        }
+    }
+
+    /// Return the transfer hook program ID, if any.
+    #[inline]
+    pub fn program_id(&self) -> Option<&Pubkey> {
+        if self.program_id == Pubkey::default() {
+            None
+        } else {
+            Some(&self.program_id)
+        }
+    }
+}
+
+/// Find the address of the validation state account of `mint` for the
+/// transfer hook program `program_id`, and its bump seed.
+#[inline]
+pub fn find_validation_address(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint], program_id)
+}
+
+/// Append the `extra` account metas to the accounts of `instruction`.
+///
+/// The account metas of the extended instruction are written to
+/// `account_metas`, with the `extra` metas following the accounts of
+/// `instruction` in order. This is used to add the accounts required by a
+/// transfer hook program to the accounts of a transfer.
+///
+/// # Errors
+///
+/// Returns `ProgramError::InvalidArgument` if the extended instruction would
+/// have more than `MAX_CPI_ACCOUNTS` accounts.
+pub fn with_extra_hook_accounts<'a, 'm, 'c, 'd>(
+    instruction: &Instruction<'a, '_, 'c, 'd>,
+    extra: &[AccountMeta<'a>],
+    account_metas: &'m mut [MaybeUninit<AccountMeta<'a>>; MAX_CPI_ACCOUNTS],
+) -> Result<Instruction<'a, 'm, 'c, 'd>, ProgramError> {
+    let num_accounts = instruction.accounts.len() + extra.len();
+    if num_accounts > MAX_CPI_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    for (account_meta, meta) in account_metas
+        .iter_mut()
+        .zip(instruction.accounts.iter().chain(extra))
+    {
+        account_meta.write(meta.clone());
+    }
+
+    Ok(Instruction {
+        program_id: instruction.program_id,
+        // SAFETY: `num_accounts` metas have been initialized.
+        accounts: unsafe { core::slice::from_raw_parts(account_metas.as_ptr() as _, num_accounts) },
+        data: instruction.data,
+    })
+}
+
+/// An extra account required by the transfer hook program, as stored in the
+/// validation state account.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ExtraAccountMeta {
+    /// How the address is configured:
+    ///   * `0`: a fixed address.
+    ///   * `1`: a PDA of the transfer hook program.
+    ///   * `128 + i`: a PDA of the program at account index `i`.
+    discriminator: u8,
+
+    /// The fixed address or the packed seeds of the PDA.
+    address_config: [u8; 32],
+
+    /// Indicates whether the account signs the instruction.
+    is_signer: u8,
+
+    /// Indicates whether the account is writable.
+    is_writable: u8,
+}
+
+unsafe impl Zeroable for ExtraAccountMeta {}
+unsafe impl Pod for ExtraAccountMeta {}
+
+impl ExtraAccountMeta {
+    /// Return the discriminator of the address configuration.
+    #[inline(always)]
+    pub fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    /// Return the fixed address or the packed seeds of the PDA.
+    #[inline(always)]
+    pub fn address_config(&self) -> &[u8; 32] {
+        &self.address_config
+    }
+
+    /// Indicates whether the account signs the instruction.
+    #[inline(always)]
+    pub fn is_signer(&self) -> bool {
+        self.is_signer != 0
+    }
+
+    /// Indicates whether the account is writable.
+    #[inline(always)]
+    pub fn is_writable(&self) -> bool {
+        self.is_writable != 0
+    }
+}
+
+/// Return the extra account metas of the `Execute` instruction from the
+/// validation state account data.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidAccountData`] if the data is malformed or
+/// does not contain the extra account metas of the `Execute` instruction.
+pub fn get_extra_account_metas(data: &[u8]) -> Result<&[ExtraAccountMeta], ProgramError> {
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let value_offset = offset + TLV_TYPE_LEN + TLV_LENGTH_LEN;
+        let header = data
+            .get(offset..value_offset)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let length = u32::from_le_bytes(header[TLV_TYPE_LEN..].try_into().unwrap()) as usize;
+
+        let value = value_offset
+            .checked_add(length)
+            .and_then(|end| data.get(value_offset..end))
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        if header[..TLV_TYPE_LEN] == EXECUTE_DISCRIMINATOR {
+            // The value is a slice of metas, prefixed by the number of metas.
+            let (count, metas) = value
+                .split_first_chunk::<4>()
+                .ok_or(ProgramError::InvalidAccountData)?;
+            let len = (u32::from_le_bytes(*count) as usize)
+                .checked_mul(core::mem::size_of::<ExtraAccountMeta>())
+                .ok_or(ProgramError::InvalidAccountData)?;
+
+            return metas
+                .get(..len)
+                .and_then(cast_slice)
+                .ok_or(ProgramError::InvalidAccountData);
+        }
+
+        offset = value_offset + length;
+    }
+
+    Err(ProgramError::InvalidAccountData)
+}
+
+/// An extra account meta with its address resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolvedAccountMeta {
+    /// Address of the account.
+    pub pubkey: Pubkey,
+
+    /// Indicates whether the account signs the instruction.
+    pub is_signer: bool,
+
+    /// Indicates whether the account is writable.
+    pub is_writable: bool,
+}
+
+impl ResolvedAccountMeta {
+    /// Return the `AccountMeta` of the account, to be appended to the accounts
+    /// of the transfer.
+    #[inline(always)]
+    pub fn as_account_meta(&self) -> AccountMeta {
+        AccountMeta::new(&self.pubkey, self.is_writable, self.is_signer)
+    }
+}
+
+/// Resolve the extra accounts required by a transfer hook program.
+///
+/// The extra account metas are read from the validation state account, which
+/// must be the PDA of the mint derived with [`find_validation_address`]. The
+/// metas are resolved in order, since the seeds of a meta may refer to the
+/// metas preceding it.
+///
+/// Seeds reading the data of an account are supported for the accounts of the
+/// transfer, the validation state account and the `extra_accounts`.
+pub struct ExtraAccountMetasResolver<'a> {
+    /// Transfer hook program.
+    pub program_id: &'a Pubkey,
+    /// Source Account.
+    pub source: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Destination Account.
+    pub destination: &'a AccountInfo,
+    /// Owner or Delegate of the Source Account.
+    pub authority: &'a AccountInfo,
+    /// Validation State Account.
+    pub validation_state: &'a AccountInfo,
+    /// Accounts whose data may be used as seeds.
+    pub extra_accounts: &'a [AccountInfo],
+    /// Amount of tokens to transfer.
+    pub amount: u64,
+}
+
+impl ExtraAccountMetasResolver<'_> {
+    /// Resolve the extra account metas into `resolved`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the validation state account is not the expected
+    /// PDA or its data is malformed, if a seed refers to a missing account or
+    /// data, or if `resolved` cannot hold all the extra account metas.
+    pub fn resolve<'r>(
+        &self,
+        resolved: &'r mut [MaybeUninit<ResolvedAccountMeta>],
+    ) -> Result<&'r [ResolvedAccountMeta], ProgramError> {
+        let (validation_address, _) = find_validation_address(self.program_id, self.mint.key());
+        if !self.validation_state.key_eq(&validation_address) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let data = self.validation_state.try_borrow_data()?;
+        let metas = get_extra_account_metas(&data)?;
+
+        if metas.len() > resolved.len() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        for (index, meta) in metas.iter().enumerate() {
+            // SAFETY: The metas preceding `index` were resolved.
+            let previous = unsafe {
+                core::slice::from_raw_parts(resolved.as_ptr() as *const ResolvedAccountMeta, index)
+            };
+
+            let pubkey = match meta.discriminator {
+                0 => meta.address_config,
+                1 => self.derive(&meta.address_config, self.program_id, previous)?,
+                discriminator if discriminator & EXTERNAL_PDA_FLAG != 0 => {
+                    let program_index = (discriminator & !EXTERNAL_PDA_FLAG) as usize;
+                    let program_id = *self.account_key(program_index, previous)?;
+                    self.derive(&meta.address_config, &program_id, previous)?
+                }
+                _ => return Err(ProgramError::InvalidAccountData),
+            };
+
+            resolved[index].write(ResolvedAccountMeta {
+                pubkey,
+                is_signer: meta.is_signer(),
+                is_writable: meta.is_writable(),
+            });
+        }
+
+        // SAFETY: The first `metas.len()` metas were resolved.
+        Ok(unsafe {
+            core::slice::from_raw_parts(
+                resolved.as_ptr() as *const ResolvedAccountMeta,
+                metas.len(),
+            )
+        })
+    }
+
+    /// Derive the PDA of `program_id` from the packed seeds `config`.
+    fn derive(
+        &self,
+        config: &[u8; 32],
+        program_id: &Pubkey,
+        previous: &[ResolvedAccountMeta],
+    ) -> Result<Pubkey, ProgramError> {
+        let mut buffer = [0u8; MAX_SEEDS * MAX_SEED_LEN];
+        let mut ranges = [(0, 0); MAX_SEEDS];
+        let mut num_seeds = 0;
+        let mut end = 0;
+
+        let mut instruction_data = [0u8; 16];
+        instruction_data[..8].copy_from_slice(&EXECUTE_DISCRIMINATOR);
+        instruction_data[8..].copy_from_slice(&self.amount.to_le_bytes());
+
+        let byte = |offset: usize| {
+            config
+                .get(offset)
+                .copied()
+                .ok_or(ProgramError::InvalidAccountData)
+        };
+        let mut offset = 0;
+
+        // Seed configurations:
+        //   * `0`: end of the seeds.
+        //   * `1`: literal, followed by the length and the bytes.
+        //   * `2`: instruction data, followed by the index and length.
+        //   * `3`: account key, followed by the account index.
+        //   * `4`: account data, followed by the account index, data index
+        //     and length.
+        while offset < config.len() && config[offset] != 0 {
+            if num_seeds == MAX_SEEDS {
+                return Err(ProgramError::MaxSeedLengthExceeded);
+            }
+
+            let seed = match config[offset] {
+                1 => {
+                    let length = byte(offset + 1)? as usize;
+                    let start = offset + 2;
+                    offset = start + length;
+                    config
+                        .get(start..offset)
+                        .ok_or(ProgramError::InvalidAccountData)?
+                }
+                2 => {
+                    let start = byte(offset + 1)? as usize;
+                    let length = byte(offset + 2)? as usize;
+                    offset += 3;
+                    instruction_data
+                        .get(start..start + length)
+                        .ok_or(ProgramError::InvalidInstructionData)?
+                }
+                3 => {
+                    let account_index = byte(offset + 1)? as usize;
+                    offset += 2;
+                    self.account_key(account_index, previous)?
+                }
+                4 => {
+                    let account_index = byte(offset + 1)? as usize;
+                    let start = byte(offset + 2)? as usize;
+                    let length = byte(offset + 3)? as usize;
+                    offset += 4;
+
+                    let key = self.account_key(account_index, previous)?;
+                    let account = self
+                        .account_info(key)
+                        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                    let data = account.try_borrow_data()?;
+                    let seed = data
+                        .get(start..start + length)
+                        .ok_or(ProgramError::InvalidAccountData)?;
+
+                    // The data is copied, so the borrow can be released.
+                    push_seed(&mut buffer, &mut ranges[num_seeds], &mut end, seed)?;
+                    num_seeds += 1;
+                    continue;
+                }
+                _ => return Err(ProgramError::InvalidAccountData),
+            };
+
+            push_seed(&mut buffer, &mut ranges[num_seeds], &mut end, seed)?;
+            num_seeds += 1;
+        }
+
+        let seeds: [&[u8]; MAX_SEEDS] = core::array::from_fn(|i| &buffer[ranges[i].0..ranges[i].1]);
+
+        try_find_program_address(&seeds[..num_seeds], program_id)
+            .map(|(address, _)| address)
+            .ok_or(ProgramError::InvalidSeeds)
+    }
+
+    /// Return the key of the account at `index` of the `Execute` instruction.
+    fn account_key<'k>(
+        &'k self,
+        index: usize,
+        previous: &'k [ResolvedAccountMeta],
+    ) -> Result<&'k Pubkey, ProgramError> {
+        Ok(match index {
+            0 => self.source.key(),
+            1 => self.mint.key(),
+            2 => self.destination.key(),
+            3 => self.authority.key(),
+            4 => self.validation_state.key(),
+            _ => {
+                &previous
+                    .get(index - EXECUTE_ACCOUNTS)
+                    .ok_or(ProgramError::InvalidAccountData)?
+                    .pubkey
+            }
+        })
+    }
+
+    /// Return the account info of the account `key`, if available.
+    fn account_info(&self, key: &Pubkey) -> Option<&AccountInfo> {
+        [
+            self.source,
+            self.mint,
+            self.destination,
+            self.authority,
+            self.validation_state,
+        ]
+        .into_iter()
+        .chain(self.extra_accounts)
+        .find(|account| account.key_eq(key))
+    }
+}
+
+/// Copy `seed` to the end of `buffer`, recording its range.
+#[inline(always)]
+fn push_seed(
+    buffer: &mut [u8; MAX_SEEDS * MAX_SEED_LEN],
+    range: &mut (usize, usize),
+    end: &mut usize,
+    seed: &[u8],
+) -> Result<(), ProgramError> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+
+    buffer[*end..*end + seed.len()].copy_from_slice(seed);
+    *range = (*end, *end + seed.len());
+    *end += seed.len();
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::{extensions::tests::account_with_extensions, test_utils::account};
+
+    /// Returns the bytes of an `ExtraAccountMeta`.
+    pub(crate) fn extra_account_meta(
+        discriminator: u8,
+        config: &[u8],
+        signer: bool,
+        writable: bool,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.push(discriminator);
+        bytes.extend_from_slice(config);
+        bytes.resize(33, 0);
+        bytes.extend_from_slice(&[signer as u8, writable as u8]);
+        bytes
+    }
+
+    /// Returns the validation state data holding `metas`.
+    pub(crate) fn validation_state(metas: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = Vec::new();
+        // an unrelated TLV entry preceding the metas
+        data.extend_from_slice(&[1; 8]);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+
+        data.extend_from_slice(&EXECUTE_DISCRIMINATOR);
+        data.extend_from_slice(&((4 + metas.len() * 35) as u32).to_le_bytes());
+        data.extend_from_slice(&(metas.len() as u32).to_le_bytes());
+        for meta in metas {
+            data.extend_from_slice(meta);
+        }
+        data
+    }
+
+    #[test]
+    fn test_transfer_hook() {
+        let mut extension = [0; 64];
+        extension[32..].copy_from_slice(&[5; 32]);
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TransferHook, &extension)],
+        );
+
+        let transfer_hook = TransferHook::from_bytes(&data).unwrap();
+        assert_eq!(transfer_hook.authority(), None);
+        assert_eq!(transfer_hook.program_id(), Some(&[5; 32]));
+    }
+
+    #[test]
+    fn test_get_extra_account_metas() {
+        let data = validation_state(&[
+            extra_account_meta(0, &[7; 32], false, true),
+            extra_account_meta(0, &[8; 32], true, false),
+        ]);
+
+        let metas = get_extra_account_metas(&data).unwrap();
+        assert_eq!(metas.len(), 2);
+        assert_eq!(metas[0].address_config(), &[7; 32]);
+        assert!(metas[0].is_writable() && !metas[0].is_signer());
+        assert!(metas[1].is_signer() && !metas[1].is_writable());
+
+        // truncated metas
+        assert_eq!(
+            get_extra_account_metas(&data[..data.len() - 1]).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+        // missing metas
+        assert_eq!(
+            get_extra_account_metas(&data[..12]).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let program_id = [9; 32];
+        let other_program = [10; 32];
+        let (validation_address, _) = find_validation_address(&program_id, &[2; 32]);
+
+        // seeds: literal "counter" and the key of the source (index 0)
+        let mut counter_seeds = Vec::new();
+        counter_seeds.extend_from_slice(&[1, 7]);
+        counter_seeds.extend_from_slice(b"counter");
+        counter_seeds.extend_from_slice(&[3, 0]);
+
+        // seeds: the amount from the instruction data and the key of the
+        // first extra account (index 5), derived by the program at index 5
+        let amount_seeds = [2, 8, 8, 3, 5];
+
+        // seeds: 4 bytes of the data of the mint
+        let data_seeds = [4, 1, 0, 4];
+
+        let data = validation_state(&[
+            extra_account_meta(0, &other_program, false, false),
+            extra_account_meta(1, &counter_seeds, false, true),
+            extra_account_meta(EXTERNAL_PDA_FLAG | 5, &amount_seeds, false, false),
+            extra_account_meta(1, &data_seeds, false, false),
+        ]);
+
+        let source = account([1; 32], crate::TOKEN_2022_ID, &[]);
+        let mint = account([2; 32], crate::TOKEN_2022_ID, &[11, 12, 13, 14, 15]);
+        let destination = account([3; 32], crate::TOKEN_2022_ID, &[]);
+        let authority = account([4; 32], [0; 32], &[]);
+        let validation = account(validation_address, program_id, &data);
+
+        let resolver = ExtraAccountMetasResolver {
+            program_id: &program_id,
+            source: &source.info,
+            mint: &mint.info,
+            destination: &destination.info,
+            authority: &authority.info,
+            validation_state: &validation.info,
+            extra_accounts: &[],
+            amount: 1_000,
+        };
+
+        let mut resolved = [MaybeUninit::uninit(); 4];
+        let metas = resolver.resolve(&mut resolved).unwrap();
+
+        let amount = 1_000u64.to_le_bytes();
+        let expected = [
+            (other_program, false),
+            (
+                find_program_address(&[b"counter", &[1; 32]], &program_id).0,
+                true,
+            ),
+            (
+                find_program_address(&[&amount, &other_program], &other_program).0,
+                false,
+            ),
+            (
+                find_program_address(&[&[11, 12, 13, 14]], &program_id).0,
+                false,
+            ),
+        ];
+
+        assert_eq!(metas.len(), expected.len());
+        for (meta, (pubkey, is_writable)) in metas.iter().zip(expected) {
+            assert_eq!(meta.pubkey, pubkey);
+            assert_eq!(meta.is_writable, is_writable);
+            assert!(!meta.is_signer);
+        }
+
+        // not enough space for the resolved metas
+        let mut resolved = [MaybeUninit::uninit(); 3];
+        assert_eq!(
+            resolver.resolve(&mut resolved).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+
+        // the validation state is not the PDA of the mint
+        let validation = account([6; 32], program_id, &data);
+        let resolver = ExtraAccountMetasResolver {
+            validation_state: &validation.info,
+            ..resolver
+        };
+        let mut resolved = [MaybeUninit::uninit(); 4];
+        assert_eq!(
+            resolver.resolve(&mut resolved).err(),
+            Some(ProgramError::InvalidSeeds)
+        );
+    }
+
+    #[test]
+    fn test_with_extra_hook_accounts() {
+        let program_id = [9; 32];
+        let keys = [[1; 32], [2; 32], [3; 32], [4; 32]];
+        let accounts = [
+            AccountMeta::writable(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+        ];
+        let instruction = Instruction {
+            program_id: &program_id,
+            accounts: &accounts,
+            data: &[1, 2, 3],
+        };
+
+        let extra = [
+            AccountMeta::writable_signer(&keys[2]),
+            AccountMeta::readonly(&keys[3]),
+        ];
+        let mut account_metas = [const { MaybeUninit::uninit() }; MAX_CPI_ACCOUNTS];
+        let extended = with_extra_hook_accounts(&instruction, &extra, &mut account_metas).unwrap();
+
+        assert_eq!(extended.program_id, &program_id);
+        assert_eq!(extended.data, &[1, 2, 3]);
+
+        let expected = [
+            (1, true, false),
+            (2, false, false),
+            (3, true, true),
+            (4, false, false),
+        ];
+        assert_eq!(extended.accounts.len(), expected.len());
+        for (meta, (key, is_writable, is_signer)) in extended.accounts.iter().zip(expected) {
+            assert_eq!(meta.pubkey, &[key; 32]);
+            assert_eq!(meta.is_writable, is_writable);
+            assert_eq!(meta.is_signer, is_signer);
+        }
+
+        // too many accounts
+        let extra: [AccountMeta; MAX_CPI_ACCOUNTS - 1] =
+            core::array::from_fn(|_| AccountMeta::readonly(&keys[3]));
+        let mut account_metas = [const { MaybeUninit::uninit() }; MAX_CPI_ACCOUNTS];
+        assert_eq!(
+            with_extra_hook_accounts(&instruction, &extra, &mut account_metas).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+}
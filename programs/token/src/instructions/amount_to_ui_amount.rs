@@ -0,0 +1,168 @@
+use core::slice::from_raw_parts;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::{get_return_data, invoke_signed, ReturnData},
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{write_bytes, TOKEN_2022_ID, UNINIT_BYTE};
+
+/// Convert an amount of tokens to its UI representation, taking into account
+/// the decimals of the mint and any interest or scaling extension.
+///
+/// The UI amount is set as the return data of the token program, and can be
+/// read with [`AmountToUiAmount::ui_amount`].
+///
+/// ### Accounts:
+///   0. `[]` The mint to calculate for.
+pub struct AmountToUiAmount<'a> {
+    /// Token program that owns the mint, either the Token or the Token-2022
+    /// program.
+    pub token_program: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Amount of tokens to convert.
+    pub amount: u64,
+}
+
+impl AmountToUiAmount<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let program_id = self.token_program.key();
+
+        if program_id != &crate::ID && program_id != &TOKEN_2022_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if !self.mint.is_owned_by(program_id) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // account metadata
+        let account_metas: [AccountMeta; 1] = [AccountMeta::readonly(self.mint.key())];
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..9]: amount (8 bytes, u64)
+        let mut instruction_data = [UNINIT_BYTE; 9];
+
+        // Set discriminator as u8 at offset [0]
+        write_bytes(&mut instruction_data, &[23]);
+        // Set amount as u64 at offset [1..9]
+        write_bytes(&mut instruction_data[1..], &self.amount.to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 9) },
+        };
+
+        invoke_signed(&instruction, &[self.mint], signers)
+    }
+
+    /// Invoke the instruction and return the UI amount set as return data.
+    ///
+    /// The returned data is a valid UTF-8 string, which can be read with
+    /// [`core::str::from_utf8`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidAccountData`] if the return data was not
+    /// set by the token program or is not a valid UTF-8 string.
+    pub fn ui_amount(&self) -> Result<ReturnData, ProgramError> {
+        self.invoke()?;
+
+        let return_data = get_return_data()
+            .filter(|data| self.token_program.key_eq(data.program_id()))
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        if core::str::from_utf8(&return_data).is_err() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(return_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::mock;
+
+    use super::*;
+    use crate::{test_utils::account, TOKEN_2022_ID};
+
+    #[test]
+    fn test_ui_amount() {
+        mock::reset();
+
+        let token_program = account(TOKEN_2022_ID, [0; 32], &[]);
+        let mint = account([1; 32], TOKEN_2022_ID, &[]);
+        let instruction = AmountToUiAmount {
+            token_program: &token_program.info,
+            mint: &mint.info,
+            amount: 1_500,
+        };
+
+        mock::set_return_data(&TOKEN_2022_ID, b"1.5");
+        let ui_amount = instruction.ui_amount().unwrap();
+        assert_eq!(core::str::from_utf8(&ui_amount), Ok("1.5"));
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(
+            invocations[0].instruction.data,
+            [23, 220, 5, 0, 0, 0, 0, 0, 0]
+        );
+
+        // return data set by another program
+        mock::set_return_data(&[9; 32], b"1.5");
+        assert_eq!(
+            instruction.ui_amount().err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        // invalid UTF-8 string
+        mock::set_return_data(&TOKEN_2022_ID, &[0xff]);
+        assert_eq!(
+            instruction.ui_amount().err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        // the mint is not owned by the token program
+        let token_program = account(crate::ID, [0; 32], &[]);
+        let instruction = AmountToUiAmount {
+            token_program: &token_program.info,
+            ..instruction
+        };
+        assert_eq!(instruction.invoke(), Err(ProgramError::IncorrectProgramId));
+    }
+
+    #[test]
+    fn test_ui_amount_not_token_program() {
+        mock::reset();
+
+        // a program that is not a token program, and a mint it owns
+        let program = account([9; 32], [0; 32], &[]);
+        let mint = account([1; 32], [9; 32], &[]);
+        let instruction = AmountToUiAmount {
+            token_program: &program.info,
+            mint: &mint.info,
+            amount: 1_500,
+        };
+
+        mock::set_return_data(&[9; 32], b"1.5");
+        assert_eq!(
+            instruction.ui_amount().err(),
+            Some(ProgramError::IncorrectProgramId)
+        );
+        assert!(mock::invocations().is_empty());
+    }
+}
@@ -5,6 +5,8 @@ use pinocchio::{
     ProgramResult,
 };
 
+use crate::state::Mint;
+
 /// Freeze an Initialized account using the Mint's freeze_authority
 ///
 /// ### Accounts:
@@ -26,6 +28,28 @@ impl FreezeAccount<'_> {
         self.invoke_signed(&[])
     }
 
+    /// Check that `freeze_authority` is the freeze authority of the mint before
+    /// invoking the instruction.
+    #[inline(always)]
+    pub fn invoke_checked(&self) -> ProgramResult {
+        self.invoke_signed_checked(&[])
+    }
+
+    /// Check that `freeze_authority` is the freeze authority of the mint before
+    /// invoking the instruction with the given signers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TokenHelperError::InvalidFreezeAuthority`] if the mint has no
+    /// freeze authority or `freeze_authority` does not match it, without
+    /// invoking the instruction.
+    ///
+    /// [`TokenHelperError::InvalidFreezeAuthority`]: crate::error::TokenHelperError::InvalidFreezeAuthority
+    pub fn invoke_signed_checked(&self, signers: &[Signer]) -> ProgramResult {
+        Mint::from_account_info(self.mint)?.check_freeze_authority(self.freeze_authority.key())?;
+        self.invoke_signed(signers)
+    }
+
     pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
         // account metadata
         let account_metas: [AccountMeta; 3] = [
@@ -47,3 +71,63 @@ impl FreezeAccount<'_> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::program_error::ProgramError;
+
+    use super::*;
+    use crate::{
+        error::TokenHelperError,
+        test_utils::{account, mint_bytes},
+        ID,
+    };
+
+    #[test]
+    fn test_invoke_checked() {
+        let authority = account([1; 32], [0; 32], &[]);
+        let token_account = account([2; 32], ID, &[]);
+        let mint = account([3; 32], ID, &mint_bytes(Some(&[1; 32])));
+
+        let freeze = FreezeAccount {
+            account: &token_account.info,
+            mint: &mint.info,
+            freeze_authority: &authority.info,
+        };
+        assert_eq!(freeze.invoke_checked(), Ok(()));
+
+        // wrong signer
+        let freeze = FreezeAccount {
+            freeze_authority: &token_account.info,
+            ..freeze
+        };
+        assert_eq!(
+            freeze.invoke_checked(),
+            Err(TokenHelperError::InvalidFreezeAuthority.into())
+        );
+
+        // mint without freeze authority
+        let mint = account([3; 32], ID, &mint_bytes(None));
+        let freeze = FreezeAccount {
+            account: &token_account.info,
+            mint: &mint.info,
+            freeze_authority: &authority.info,
+        };
+        assert_eq!(
+            freeze.invoke_checked(),
+            Err(TokenHelperError::InvalidFreezeAuthority.into())
+        );
+
+        // mint not owned by the token program
+        let mint = account([3; 32], [0; 32], &mint_bytes(Some(&[1; 32])));
+        let freeze = FreezeAccount {
+            account: &token_account.info,
+            mint: &mint.info,
+            freeze_authority: &authority.info,
+        };
+        assert_eq!(
+            freeze.invoke_checked(),
+            Err(ProgramError::InvalidAccountOwner)
+        );
+    }
+}
@@ -32,6 +32,16 @@ impl InitializeMint2<'_> {
     }
 
     pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.invoke_signed_with_program_id(&crate::ID, signers)
+    }
+
+    /// Invoke the instruction on the token program `program_id`, e.g., to
+    /// initialize a Token-2022 mint.
+    pub(crate) fn invoke_signed_with_program_id(
+        &self,
+        program_id: &Pubkey,
+        signers: &[Signer],
+    ) -> ProgramResult {
         // Account metadata
         let account_metas: [AccountMeta; 1] = [AccountMeta::writable(self.mint.key())];
 
@@ -58,7 +68,7 @@ impl InitializeMint2<'_> {
         }
 
         let instruction = Instruction {
-            program_id: &crate::ID,
+            program_id,
             accounts: &account_metas,
             data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 67) },
         };
@@ -1,3 +1,4 @@
+mod amount_to_ui_amount;
 mod approve;
 mod approve_checked;
 mod burn;
@@ -15,9 +16,12 @@ mod revoke;
 mod set_authority;
 mod sync_native;
 mod thaw_account;
+mod token_transfer;
 mod transfer;
 mod transfer_checked;
+mod ui_amount_to_amount;
 
+pub use amount_to_ui_amount::*;
 pub use approve::*;
 pub use approve_checked::*;
 pub use burn::*;
@@ -35,5 +39,7 @@ pub use revoke::*;
 pub use set_authority::*;
 pub use sync_native::*;
 pub use thaw_account::*;
+pub use token_transfer::*;
 pub use transfer::*;
 pub use transfer_checked::*;
+pub use ui_amount_to_amount::*;
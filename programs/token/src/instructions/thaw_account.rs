@@ -5,6 +5,8 @@ use pinocchio::{
     ProgramResult,
 };
 
+use crate::state::Mint;
+
 /// Thaw a Frozen account using the Mint's freeze_authority
 ///
 /// ### Accounts:
@@ -26,6 +28,28 @@ impl ThawAccount<'_> {
         self.invoke_signed(&[])
     }
 
+    /// Check that `freeze_authority` is the freeze authority of the mint before
+    /// invoking the instruction.
+    #[inline(always)]
+    pub fn invoke_checked(&self) -> ProgramResult {
+        self.invoke_signed_checked(&[])
+    }
+
+    /// Check that `freeze_authority` is the freeze authority of the mint before
+    /// invoking the instruction with the given signers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TokenHelperError::InvalidFreezeAuthority`] if the mint has no
+    /// freeze authority or `freeze_authority` does not match it, without
+    /// invoking the instruction.
+    ///
+    /// [`TokenHelperError::InvalidFreezeAuthority`]: crate::error::TokenHelperError::InvalidFreezeAuthority
+    pub fn invoke_signed_checked(&self, signers: &[Signer]) -> ProgramResult {
+        Mint::from_account_info(self.mint)?.check_freeze_authority(self.freeze_authority.key())?;
+        self.invoke_signed(signers)
+    }
+
     pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
         // account metadata
         let account_metas: [AccountMeta; 3] = [
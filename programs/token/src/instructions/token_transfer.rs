@@ -0,0 +1,486 @@
+use core::mem::MaybeUninit;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::{slice_invoke_signed, MAX_CPI_ACCOUNTS},
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    extensions::{
+        transfer_fee::{TransferCheckedWithFee, TransferFeeConfig},
+        transfer_hook::{
+            find_validation_address, with_extra_hook_accounts, ExtraAccountMetasResolver,
+            ResolvedAccountMeta, TransferHook,
+        },
+    },
+    instructions::TransferChecked,
+    TOKEN_2022_ID,
+};
+
+/// Number of accounts of the transfer instructions.
+const TRANSFER_ACCOUNTS: usize = 4;
+
+/// Transfer Tokens from one Token Account to another, routing the transfer to
+/// the token program that owns the mint.
+///
+/// The token program must be the owner of the mint, either the Token or the
+/// Token-2022 program. When the mint has a `TransferFeeConfig` extension, the
+/// fee for the current epoch is calculated and the transfer is made with
+/// `TransferCheckedWithFee`; otherwise, `TransferChecked` is used.
+///
+/// When the mint has a `TransferHook` extension with a program set, the extra
+/// accounts required by the hook program are resolved from its validation
+/// state account and appended to the accounts of the transfer, followed by
+/// the hook program and the validation state account. All of these accounts
+/// must be present in `hook_accounts`.
+///
+/// ### Accounts:
+///   0. `[WRITE]` The source account.
+///   1. `[]` The token mint.
+///   2. `[WRITE]` The destination account.
+///   3. `[SIGNER]` The source account's owner/delegate.
+///   4. `..4+M` `[]` The extra accounts required by the transfer hook program.
+///   5. `[]` The transfer hook program.
+///   6. `[]` The validation state account.
+pub struct TokenTransfer<'a> {
+    /// Token program that owns the mint.
+    pub token_program: &'a AccountInfo,
+    /// Sender account.
+    pub from: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// Recipient account.
+    pub to: &'a AccountInfo,
+    /// Authority account.
+    pub authority: &'a AccountInfo,
+    /// Amount of microtokens to transfer.
+    pub amount: u64,
+    /// Decimal for the Token.
+    pub decimals: u8,
+    /// Accounts available to the transfer hook program of the mint.
+    ///
+    /// Ignored when the mint does not have a transfer hook program.
+    pub hook_accounts: &'a [AccountInfo],
+}
+
+impl TokenTransfer<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let program_id = self.token_program.key();
+
+        if !self.mint.is_owned_by(program_id) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let transfer = TransferChecked {
+            from: self.from,
+            mint: self.mint,
+            to: self.to,
+            authority: self.authority,
+            amount: self.amount,
+            decimals: self.decimals,
+        };
+
+        if program_id == &crate::ID {
+            return transfer.invoke_signed(signers);
+        }
+
+        if program_id != &TOKEN_2022_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let hook_program_id = self.hook_program_id()?;
+
+        match (self.fee()?, hook_program_id) {
+            (Some(fee), hook_program_id) => {
+                let transfer = TransferCheckedWithFee {
+                    source: self.from,
+                    mint: self.mint,
+                    destination: self.to,
+                    authority: self.authority,
+                    amount: self.amount,
+                    decimals: self.decimals,
+                    fee,
+                };
+
+                match hook_program_id {
+                    Some(hook_program_id) => self.invoke_with_hook(
+                        &hook_program_id,
+                        &transfer.instruction_data(),
+                        signers,
+                    ),
+                    None => transfer.invoke_signed(signers),
+                }
+            }
+            (None, Some(hook_program_id)) => {
+                self.invoke_with_hook(&hook_program_id, &transfer.instruction_data(), signers)
+            }
+            (None, None) => transfer.invoke_signed_with_program_id(&TOKEN_2022_ID, signers),
+        }
+    }
+
+    /// Invoke the Token-2022 transfer with instruction data `data`, appending
+    /// the accounts required by the transfer hook program `hook_program_id`.
+    fn invoke_with_hook(
+        &self,
+        hook_program_id: &Pubkey,
+        data: &[u8],
+        signers: &[Signer],
+    ) -> ProgramResult {
+        const UNINIT_RESOLVED: MaybeUninit<ResolvedAccountMeta> =
+            MaybeUninit::<ResolvedAccountMeta>::uninit();
+        const UNINIT_META: MaybeUninit<AccountMeta> = MaybeUninit::<AccountMeta>::uninit();
+        const UNINIT_INFO: MaybeUninit<&AccountInfo> = MaybeUninit::<&AccountInfo>::uninit();
+
+        let (validation_address, _) = find_validation_address(hook_program_id, self.mint.key());
+        let hook_program = self.hook_account(hook_program_id)?;
+        let validation_state = self.hook_account(&validation_address)?;
+
+        // The hook program and the validation state account follow the
+        // resolved extra accounts.
+        let mut resolved = [UNINIT_RESOLVED; MAX_CPI_ACCOUNTS - TRANSFER_ACCOUNTS - 2];
+        let resolved = ExtraAccountMetasResolver {
+            program_id: hook_program_id,
+            source: self.from,
+            mint: self.mint,
+            destination: self.to,
+            authority: self.authority,
+            validation_state,
+            extra_accounts: self.hook_accounts,
+            amount: self.amount,
+        }
+        .resolve(&mut resolved)?;
+
+        let mut extra_metas = [UNINIT_META; MAX_CPI_ACCOUNTS];
+        for (extra_meta, meta) in extra_metas.iter_mut().zip(resolved) {
+            extra_meta.write(meta.as_account_meta());
+        }
+        extra_metas[resolved.len()].write(AccountMeta::readonly(hook_program.key()));
+        extra_metas[resolved.len() + 1].write(AccountMeta::readonly(validation_state.key()));
+
+        // SAFETY: The first `resolved.len() + 2` metas have been initialized.
+        let extra_metas =
+            unsafe { core::slice::from_raw_parts(extra_metas.as_ptr() as _, resolved.len() + 2) };
+
+        let account_metas: [AccountMeta; TRANSFER_ACCOUNTS] = [
+            AccountMeta::writable(self.from.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::writable(self.to.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        let mut extended_metas = [UNINIT_META; MAX_CPI_ACCOUNTS];
+        let instruction = with_extra_hook_accounts(
+            &Instruction {
+                program_id: &TOKEN_2022_ID,
+                accounts: &account_metas,
+                data,
+            },
+            extra_metas,
+            &mut extended_metas,
+        )?;
+
+        let mut account_infos = [UNINIT_INFO; MAX_CPI_ACCOUNTS];
+        account_infos[0].write(self.from);
+        account_infos[1].write(self.mint);
+        account_infos[2].write(self.to);
+        account_infos[3].write(self.authority);
+
+        for (account_info, meta) in account_infos[TRANSFER_ACCOUNTS..]
+            .iter_mut()
+            .zip(extra_metas)
+        {
+            account_info.write(self.hook_account(meta.pubkey)?);
+        }
+
+        // SAFETY: The first `instruction.accounts.len()` infos have been
+        // initialized.
+        let account_infos = unsafe {
+            core::slice::from_raw_parts(account_infos.as_ptr() as _, instruction.accounts.len())
+        };
+
+        slice_invoke_signed(&instruction, account_infos, signers)
+    }
+
+    /// Return the account `key` from the hook accounts.
+    #[inline]
+    fn hook_account(&self, key: &Pubkey) -> Result<&AccountInfo, ProgramError> {
+        self.hook_accounts
+            .iter()
+            .find(|account| account.key_eq(key))
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    }
+
+    /// Return the transfer hook program of the mint, or `None` if the mint
+    /// does not have a `TransferHook` extension or its program is not set.
+    #[inline]
+    fn hook_program_id(&self) -> Result<Option<Pubkey>, ProgramError> {
+        let data = self.mint.try_borrow_data()?;
+
+        Ok(TransferHook::from_bytes(&data).and_then(|hook| hook.program_id().copied()))
+    }
+
+    /// Return the fee assessed on the transfer for the current epoch, or
+    /// `None` if the mint does not have a `TransferFeeConfig` extension.
+    #[inline]
+    fn fee(&self) -> Result<Option<u64>, ProgramError> {
+        let data = self.mint.try_borrow_data()?;
+
+        let Some(config) = TransferFeeConfig::from_bytes(&data) else {
+            return Ok(None);
+        };
+
+        let epoch = Clock::get()?.epoch;
+
+        Ok(Some(config.current_fee(epoch).calculate_fee(self.amount)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::{mock, program_error::ProgramError, sysvars::clock::Clock};
+
+    use super::*;
+    use crate::{
+        extensions::{
+            tests::account_with_extensions,
+            transfer_fee::tests::transfer_fee_config,
+            transfer_hook::tests::{extra_account_meta, validation_state},
+            BaseState, ExtensionType,
+        },
+        test_utils::{account, mint_bytes},
+    };
+
+    #[test]
+    fn test_plain_mint() {
+        mock::reset();
+
+        let token_program = account(crate::ID, [0; 32], &[]);
+        let from = account([1; 32], crate::ID, &[]);
+        let mint = account([2; 32], crate::ID, &mint_bytes(None));
+        let to = account([3; 32], crate::ID, &[]);
+        let authority = account([4; 32], [0; 32], &[]);
+
+        TokenTransfer {
+            token_program: &token_program.info,
+            from: &from.info,
+            mint: &mint.info,
+            to: &to.info,
+            authority: &authority.info,
+            amount: 1_000,
+            decimals: 6,
+            hook_accounts: &[],
+        }
+        .invoke()
+        .unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, crate::ID);
+        // `TransferChecked` discriminator, amount and decimals
+        assert_eq!(instruction.data, [12, 232, 3, 0, 0, 0, 0, 0, 0, 6]);
+        assert_eq!(instruction.accounts[1].0, [2; 32]);
+    }
+
+    #[test]
+    fn test_token_2022_mint_without_fee() {
+        mock::reset();
+
+        let data = account_with_extensions(BaseState::Mint, &[]);
+        let token_program = account(TOKEN_2022_ID, [0; 32], &[]);
+        let from = account([1; 32], TOKEN_2022_ID, &[]);
+        let mint = account([2; 32], TOKEN_2022_ID, &data);
+        let to = account([3; 32], TOKEN_2022_ID, &[]);
+        let authority = account([4; 32], [0; 32], &[]);
+
+        TokenTransfer {
+            token_program: &token_program.info,
+            from: &from.info,
+            mint: &mint.info,
+            to: &to.info,
+            authority: &authority.info,
+            amount: 1_000,
+            decimals: 6,
+            hook_accounts: &[],
+        }
+        .invoke()
+        .unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations[0].instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(invocations[0].instruction.data[0], 12);
+    }
+
+    #[test]
+    fn test_fee_bearing_mint() {
+        mock::reset();
+        mock::set_clock(Clock {
+            epoch: 10,
+            ..Clock::default()
+        });
+
+        // 1% fee up to epoch 10, 2% fee from epoch 10
+        let config = transfer_fee_config([0; 32], [0; 32], 0, (0, 1_000, 100), (10, 1_000, 200));
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TransferFeeConfig, &config)],
+        );
+
+        let token_program = account(TOKEN_2022_ID, [0; 32], &[]);
+        let from = account([1; 32], TOKEN_2022_ID, &[]);
+        let mint = account([2; 32], TOKEN_2022_ID, &data);
+        let to = account([3; 32], TOKEN_2022_ID, &[]);
+        let authority = account([4; 32], [0; 32], &[]);
+
+        let transfer = TokenTransfer {
+            token_program: &token_program.info,
+            from: &from.info,
+            mint: &mint.info,
+            to: &to.info,
+            authority: &authority.info,
+            amount: 10_000,
+            decimals: 6,
+            hook_accounts: &[],
+        };
+        transfer.invoke().unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, TOKEN_2022_ID);
+        // `TransferCheckedWithFee` discriminators and the newer fee
+        assert_eq!(instruction.data[..2], [26, 1]);
+        assert_eq!(instruction.data[11..], 200u64.to_le_bytes());
+
+        // the fee depends on the clock
+        mock::reset();
+        assert_eq!(transfer.invoke(), Err(ProgramError::UnsupportedSysvar));
+        assert!(mock::invocations().is_empty());
+    }
+
+    #[test]
+    fn test_mismatched_program() {
+        mock::reset();
+
+        let token_program = account(TOKEN_2022_ID, [0; 32], &[]);
+        let account_info = account([1; 32], crate::ID, &mint_bytes(None));
+
+        let transfer = TokenTransfer {
+            token_program: &token_program.info,
+            from: &account_info.info,
+            mint: &account_info.info,
+            to: &account_info.info,
+            authority: &account_info.info,
+            amount: 1,
+            decimals: 0,
+            hook_accounts: &[],
+        };
+        assert_eq!(transfer.invoke(), Err(ProgramError::IncorrectProgramId));
+
+        // the mint is owned by a program other than the token programs
+        let program = account([9; 32], [0; 32], &[]);
+        let mint = account([2; 32], [9; 32], &mint_bytes(None));
+        let transfer = TokenTransfer {
+            token_program: &program.info,
+            mint: &mint.info,
+            ..transfer
+        };
+        assert_eq!(transfer.invoke(), Err(ProgramError::IncorrectProgramId));
+        assert!(mock::invocations().is_empty());
+    }
+
+    #[test]
+    fn test_transfer_hook_mint() {
+        mock::reset();
+
+        let hook_program_id = [9; 32];
+        let mut extension = [0; 64];
+        extension[32..].copy_from_slice(&hook_program_id);
+        let data = account_with_extensions(
+            BaseState::Mint,
+            &[(ExtensionType::TransferHook, &extension)],
+        );
+
+        let (validation_address, _) = find_validation_address(&hook_program_id, &[2; 32]);
+        let validation_data = validation_state(&[
+            extra_account_meta(0, &[7; 32], false, true),
+            extra_account_meta(0, &[8; 32], false, false),
+        ]);
+
+        let token_program = account(TOKEN_2022_ID, [0; 32], &[]);
+        let from = account([1; 32], TOKEN_2022_ID, &[]);
+        let mint = account([2; 32], TOKEN_2022_ID, &data);
+        let to = account([3; 32], TOKEN_2022_ID, &[]);
+        let authority = account([4; 32], [0; 32], &[]);
+
+        // the hook accounts may be passed in any order
+        let validation = account(validation_address, hook_program_id, &validation_data);
+        let extra_b = account([8; 32], [0; 32], &[]);
+        let hook_program = account(hook_program_id, [0; 32], &[]);
+        let extra_a = account([7; 32], [0; 32], &[]);
+        let hook_accounts = [
+            validation.info.clone(),
+            extra_b.info.clone(),
+            hook_program.info.clone(),
+            extra_a.info.clone(),
+        ];
+
+        let transfer = TokenTransfer {
+            token_program: &token_program.info,
+            from: &from.info,
+            mint: &mint.info,
+            to: &to.info,
+            authority: &authority.info,
+            amount: 1_000,
+            decimals: 6,
+            hook_accounts: &hook_accounts,
+        };
+        transfer.invoke().unwrap();
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, TOKEN_2022_ID);
+        assert_eq!(instruction.data, [12, 232, 3, 0, 0, 0, 0, 0, 0, 6]);
+
+        // the extra accounts follow the accounts of the transfer, then the
+        // hook program and the validation state account
+        let keys = [
+            [1; 32],
+            [2; 32],
+            [3; 32],
+            [4; 32],
+            [7; 32],
+            [8; 32],
+            hook_program_id,
+            validation_address,
+        ];
+        assert_eq!(instruction.accounts.len(), keys.len());
+        for ((key, _), expected) in instruction.accounts.iter().zip(keys) {
+            assert_eq!(*key, expected);
+        }
+        assert!(instruction.accounts[4].1.writable());
+        assert!(!instruction.accounts[5].1.writable());
+
+        // a missing hook account
+        let transfer = TokenTransfer {
+            hook_accounts: &hook_accounts[..3],
+            ..transfer
+        };
+        assert_eq!(transfer.invoke(), Err(ProgramError::NotEnoughAccountKeys));
+        assert!(mock::invocations().is_empty());
+    }
+}
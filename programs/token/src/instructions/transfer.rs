@@ -1,9 +1,9 @@
-use core::slice::from_raw_parts;
-
 use pinocchio::{
     account_info::AccountInfo,
+    cpi::invoke_signed,
     instruction::{AccountMeta, Instruction, Signer},
-    program::invoke_signed,
+    pubkey::Pubkey,
+    sysvars::instructions::IntrospectedAccountMeta,
     ProgramResult,
 };
 
@@ -33,16 +33,69 @@ impl Transfer<'_> {
     }
 
     pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
-        // account metadata
-        let account_metas: [AccountMeta; 3] = [
-            AccountMeta::writable(self.from.key()),
-            AccountMeta::writable(self.to.key()),
-            AccountMeta::readonly_signer(self.authority.key()),
-        ];
-
-        // Instruction data layout:
-        // -  [0]: instruction discriminator (1 byte, u8)
-        // -  [1..9]: amount (8 bytes, u64)
+        TransferKeys::from(self).invoke_signed(&[self.from, self.to, self.authority], signers)
+    }
+}
+
+/// Transfer Tokens from one Token Account to another, with the accounts
+/// given by their keys.
+///
+/// This builds the same instruction as [`Transfer`] from keys that are not
+/// tied to an `AccountInfo`, e.g. the account metas of an introspected
+/// instruction, which allows an instruction seen in the transaction to be
+/// re-issued.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Sender account
+///   1. `[WRITE]` Recipient account
+///   2. `[SIGNER]` Authority account
+pub struct TransferKeys<'a> {
+    /// Sender account.
+    pub from: &'a Pubkey,
+    /// Recipient account.
+    pub to: &'a Pubkey,
+    /// Authority account.
+    pub authority: &'a Pubkey,
+    /// Amount of microtokens to transfer.
+    pub amount: u64,
+}
+
+impl<'a> TransferKeys<'a> {
+    /// Creates a `TransferKeys` from the account metas of an introspected
+    /// instruction.
+    ///
+    /// Only the keys are used; the flags of the accounts are the ones of a
+    /// transfer.
+    #[inline(always)]
+    pub fn from_introspected(
+        from: &'a IntrospectedAccountMeta,
+        to: &'a IntrospectedAccountMeta,
+        authority: &'a IntrospectedAccountMeta,
+        amount: u64,
+    ) -> Self {
+        Self {
+            from: &from.key,
+            to: &to.key,
+            authority: &authority.key,
+            amount,
+        }
+    }
+
+    /// Returns the account metas of the instruction.
+    #[inline(always)]
+    pub fn account_metas(&self) -> [AccountMeta<'a>; 3] {
+        [
+            AccountMeta::writable(self.from),
+            AccountMeta::writable(self.to),
+            AccountMeta::readonly_signer(self.authority),
+        ]
+    }
+
+    // Instruction data layout:
+    // -  [0]: instruction discriminator (1 byte, u8)
+    // -  [1..9]: amount (8 bytes, u64)
+    #[inline(always)]
+    pub fn instruction_data(&self) -> [u8; 9] {
         let mut instruction_data = [UNINIT_BYTE; 9];
 
         // Set discriminator as u8 at offset [0]
@@ -50,12 +103,108 @@ impl Transfer<'_> {
         // Set amount as u64 at offset [1..9]
         write_bytes(&mut instruction_data[1..9], &self.amount.to_le_bytes());
 
+        // SAFETY: All bytes of the instruction data have been written.
+        unsafe { *(instruction_data.as_ptr() as *const [u8; 9]) }
+    }
+
+    /// Invoke the instruction with the `AccountInfo`s of its accounts, in the
+    /// order of the account metas.
+    #[inline(always)]
+    pub fn invoke(&self, account_infos: &[&AccountInfo; 3]) -> ProgramResult {
+        self.invoke_signed(account_infos, &[])
+    }
+
+    /// Invoke the instruction with signatures and the `AccountInfo`s of its
+    /// accounts, in the order of the account metas.
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if the keys of the
+    /// `AccountInfo`s do not match the keys of the instruction.
+    ///
+    /// [`ProgramError::InvalidArgument`]: pinocchio::program_error::ProgramError::InvalidArgument
+    pub fn invoke_signed(
+        &self,
+        account_infos: &[&AccountInfo; 3],
+        signers: &[Signer],
+    ) -> ProgramResult {
         let instruction = Instruction {
             program_id: &crate::ID,
-            accounts: &account_metas,
-            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 9) },
+            accounts: &self.account_metas(),
+            data: &self.instruction_data(),
+        };
+
+        invoke_signed(&instruction, account_infos, signers)
+    }
+}
+
+impl<'a> From<&Transfer<'a>> for TransferKeys<'a> {
+    fn from(transfer: &Transfer<'a>) -> Self {
+        Self {
+            from: transfer.from.key(),
+            to: transfer.to.key(),
+            authority: transfer.authority.key(),
+            amount: transfer.amount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pinocchio::{
+        mock,
+        program_error::ProgramError,
+        sysvars::instructions::{pack_instructions, Instructions},
+    };
+
+    use super::*;
+    use crate::{test_utils::account, ID};
+
+    #[test]
+    fn test_transfer_from_introspected_metas() {
+        let from = account([1; 32], ID, &[]);
+        let to = account([2; 32], ID, &[]);
+        let authority = account([3; 32], [0; 32], &[]);
+
+        mock::reset();
+
+        let transfer = Transfer {
+            from: &from.info,
+            to: &to.info,
+            authority: &authority.info,
+            amount: 1_000,
         };
+        transfer.invoke().unwrap();
+
+        // introspect the transfer and re-issue it from its account metas
+        let keys = TransferKeys::from(&transfer);
+        let data = pack_instructions(&[Instruction {
+            program_id: &ID,
+            accounts: &keys.account_metas(),
+            data: &keys.instruction_data(),
+        }]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let introspected = sysvar.load_instruction_at_checked(0).unwrap();
+        let metas: [&IntrospectedAccountMeta; 3] =
+            core::array::from_fn(|index| introspected.get_account_meta_at(index).unwrap());
+        assert!(introspected.program_id_is(&ID));
+
+        let reissued = TransferKeys::from_introspected(metas[0], metas[1], metas[2], 1_000);
+        assert!(introspected.accounts_eq(&reissued.account_metas()));
+        assert!(introspected.data_eq(&reissued.instruction_data()));
+        reissued
+            .invoke(&[&from.info, &to.info, &authority.info])
+            .unwrap();
+
+        // both invocations are the same
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 2);
+        assert_eq!(invocations[0].instruction, invocations[1].instruction);
+        assert_eq!(invocations[0].instruction.data[0], 3);
+        assert_eq!(invocations[0].accounts, invocations[1].accounts);
 
-        invoke_signed(&instruction, &[self.from, self.to, self.authority], signers)
+        // the accounts must match the keys
+        assert_eq!(
+            reissued.invoke(&[&to.info, &from.info, &authority.info]),
+            Err(ProgramError::InvalidArgument)
+        );
     }
 }
@@ -1,9 +1,8 @@
-use core::slice::from_raw_parts;
-
 use pinocchio::{
     account_info::AccountInfo,
     instruction::{AccountMeta, Instruction, Signer},
     program::invoke_signed,
+    pubkey::Pubkey,
     ProgramResult,
 };
 
@@ -37,7 +36,20 @@ impl TransferChecked<'_> {
         self.invoke_signed(&[])
     }
 
+    #[inline(always)]
     pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.invoke_signed_with_program_id(&crate::ID, signers)
+    }
+
+    /// Invoke the instruction on the token program `program_id`.
+    ///
+    /// The instruction has the same layout on both Token and Token-2022
+    /// programs.
+    pub(crate) fn invoke_signed_with_program_id(
+        &self,
+        program_id: &Pubkey,
+        signers: &[Signer],
+    ) -> ProgramResult {
         // account metadata
         let account_metas: [AccountMeta; 4] = [
             AccountMeta::writable(self.from.key()),
@@ -46,23 +58,10 @@ impl TransferChecked<'_> {
             AccountMeta::readonly_signer(self.authority.key()),
         ];
 
-        // Instruction data layout:
-        // -  [0]: instruction discriminator (1 byte, u8)
-        // -  [1..9]: amount (8 bytes, u64)
-        // -  [9]: decimals (1 byte, u8)
-        let mut instruction_data = [UNINIT_BYTE; 10];
-
-        // Set discriminator as u8 at offset [0]
-        write_bytes(&mut instruction_data, &[12]);
-        // Set amount as u64 at offset [1..9]
-        write_bytes(&mut instruction_data[1..9], &self.amount.to_le_bytes());
-        // Set decimals as u8 at offset [9]
-        write_bytes(&mut instruction_data[9..], &[self.decimals]);
-
         let instruction = Instruction {
-            program_id: &crate::ID,
+            program_id,
             accounts: &account_metas,
-            data: unsafe { from_raw_parts(instruction_data.as_ptr() as _, 10) },
+            data: &self.instruction_data(),
         };
 
         invoke_signed(
@@ -71,4 +70,23 @@ impl TransferChecked<'_> {
             signers,
         )
     }
+
+    // Instruction data layout:
+    // -  [0]: instruction discriminator (1 byte, u8)
+    // -  [1..9]: amount (8 bytes, u64)
+    // -  [9]: decimals (1 byte, u8)
+    #[inline(always)]
+    pub(crate) fn instruction_data(&self) -> [u8; 10] {
+        let mut instruction_data = [UNINIT_BYTE; 10];
+
+        // Set discriminator as u8 at offset [0]
+        write_bytes(&mut instruction_data, &[12]);
+        // Set amount as u64 at offset [1..9]
+        write_bytes(&mut instruction_data[1..9], &self.amount.to_le_bytes());
+        // Set decimals as u8 at offset [9]
+        write_bytes(&mut instruction_data[9..], &[self.decimals]);
+
+        // SAFETY: All bytes of the instruction data have been written.
+        unsafe { *(instruction_data.as_ptr() as *const [u8; 10]) }
+    }
 }
@@ -0,0 +1,166 @@
+use core::{mem::MaybeUninit, slice::from_raw_parts};
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::{get_return_data, invoke_signed},
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{write_bytes, TOKEN_2022_ID, UNINIT_BYTE};
+
+/// Maximum length of the UI amount string of [`UiAmountToAmount`].
+pub const MAX_UI_AMOUNT_LEN: usize = 256;
+
+/// Convert a UI representation of a token amount to its amount of tokens,
+/// taking into account the decimals of the mint and any interest or scaling
+/// extension.
+///
+/// The amount is set as the return data of the token program, and can be
+/// read with [`UiAmountToAmount::amount`].
+///
+/// ### Accounts:
+///   0. `[]` The mint to calculate for.
+pub struct UiAmountToAmount<'a, 'b> {
+    /// Token program that owns the mint, either the Token or the Token-2022
+    /// program.
+    pub token_program: &'a AccountInfo,
+    /// Mint Account.
+    pub mint: &'a AccountInfo,
+    /// UI amount to convert.
+    pub ui_amount: &'b str,
+}
+
+impl UiAmountToAmount<'_, '_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let program_id = self.token_program.key();
+
+        if program_id != &crate::ID && program_id != &TOKEN_2022_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if !self.mint.is_owned_by(program_id) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // account metadata
+        let account_metas: [AccountMeta; 1] = [AccountMeta::readonly(self.mint.key())];
+
+        let mut instruction_data = [UNINIT_BYTE; 1 + MAX_UI_AMOUNT_LEN];
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: self.instruction_data(&mut instruction_data)?,
+        };
+
+        invoke_signed(&instruction, &[self.mint], signers)
+    }
+
+    /// Invoke the instruction and return the amount set as return data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidAccountData`] if the return data was not
+    /// set by the token program or is not a `u64`.
+    pub fn amount(&self) -> Result<u64, ProgramError> {
+        self.invoke()?;
+
+        let return_data = get_return_data()
+            .filter(|data| self.token_program.key_eq(data.program_id()))
+            .ok_or(ProgramError::InvalidAccountData)?;
+
+        return_data
+            .as_slice()
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    // Instruction data layout:
+    // -  [0]: instruction discriminator (1 byte, u8)
+    // -  [1..]: ui_amount (UTF-8 bytes, up to `MAX_UI_AMOUNT_LEN`)
+    #[inline(always)]
+    fn instruction_data<'d>(
+        &self,
+        data: &'d mut [MaybeUninit<u8>; 1 + MAX_UI_AMOUNT_LEN],
+    ) -> Result<&'d [u8], ProgramError> {
+        let len = self.ui_amount.len();
+        if len > MAX_UI_AMOUNT_LEN {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        write_bytes(data, &[24]);
+        write_bytes(&mut data[1..], self.ui_amount.as_bytes());
+
+        // SAFETY: The first `1 + len` bytes have been initialized.
+        Ok(unsafe { from_raw_parts(data.as_ptr() as _, 1 + len) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use pinocchio::mock;
+
+    use super::*;
+    use crate::test_utils::account;
+
+    #[test]
+    fn test_amount() {
+        mock::reset();
+
+        let token_program = account(crate::ID, [0; 32], &[]);
+        let mint = account([1; 32], crate::ID, &[]);
+        let instruction = UiAmountToAmount {
+            token_program: &token_program.info,
+            mint: &mint.info,
+            ui_amount: "1.5",
+        };
+
+        mock::set_return_data(&crate::ID, &1_500u64.to_le_bytes());
+        assert_eq!(instruction.amount(), Ok(1_500));
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].instruction.program_id, crate::ID);
+        assert_eq!(invocations[0].instruction.data, b"\x181.5");
+
+        // return data that is not a `u64`
+        mock::set_return_data(&crate::ID, &[1, 2, 3]);
+        assert_eq!(instruction.amount(), Err(ProgramError::InvalidAccountData));
+
+        // UI amount too long
+        let ui_amount = "1".repeat(MAX_UI_AMOUNT_LEN + 1);
+        let instruction = UiAmountToAmount {
+            ui_amount: &ui_amount,
+            ..instruction
+        };
+        assert_eq!(instruction.invoke(), Err(ProgramError::InvalidArgument));
+    }
+
+    #[test]
+    fn test_amount_not_token_program() {
+        mock::reset();
+
+        // a program that is not a token program, and a mint it owns
+        let program = account([9; 32], [0; 32], &[]);
+        let mint = account([1; 32], [9; 32], &[]);
+        let instruction = UiAmountToAmount {
+            token_program: &program.info,
+            mint: &mint.info,
+            ui_amount: "1.5",
+        };
+
+        mock::set_return_data(&[9; 32], &1_500u64.to_le_bytes());
+        assert_eq!(instruction.amount(), Err(ProgramError::IncorrectProgramId));
+        assert!(mock::invocations().is_empty());
+    }
+}
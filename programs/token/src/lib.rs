@@ -1,10 +1,21 @@
 #![no_std]
 
+pub mod error;
+pub mod extensions;
 pub mod instructions;
 pub mod state;
 
+#[cfg(test)]
+mod test_utils;
+
 pinocchio_pubkey::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 
+/// The Token-2022 program ID.
+///
+/// Extension instructions are only supported by the Token-2022 program.
+pub const TOKEN_2022_ID: pinocchio::pubkey::Pubkey =
+    pinocchio_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
 use core::mem::MaybeUninit;
 
 const UNINIT_BYTE: MaybeUninit<u8> = MaybeUninit::<u8>::uninit();
@@ -15,3 +26,22 @@ fn write_bytes(destination: &mut [MaybeUninit<u8>], source: &[u8]) {
         d.write(*s);
     }
 }
+
+/// Write an optional pubkey using the `COption` encoding of the Token-2022
+/// instructions: a presence flag followed by the pubkey only when present.
+///
+/// Returns the number of bytes written.
+#[inline(always)]
+fn write_optional_pubkey(
+    destination: &mut [MaybeUninit<u8>],
+    pubkey: Option<&pinocchio::pubkey::Pubkey>,
+) -> usize {
+    if let Some(pubkey) = pubkey {
+        write_bytes(destination, &[1]);
+        write_bytes(&mut destination[1..], pubkey);
+        1 + pubkey.len()
+    } else {
+        write_bytes(destination, &[0]);
+        1
+    }
+}
@@ -1,10 +1,12 @@
 use pinocchio::{
     account_info::{AccountInfo, Ref},
+    fmt,
     program_error::ProgramError,
     pubkey::Pubkey,
 };
 
-use crate::ID;
+use super::{is_valid_coption_tag, read_coption_pubkey, state_bytes, Pack};
+use crate::{error::TokenHelperError, ID};
 
 /// Mint data.
 #[repr(C)]
@@ -38,6 +40,24 @@ impl Mint {
     /// The length of the `Mint` account data.
     pub const LEN: usize = core::mem::size_of::<Mint>();
 
+    /// Create an initialized `Mint`.
+    pub fn new(
+        mint_authority: Option<&Pubkey>,
+        supply: u64,
+        decimals: u8,
+        freeze_authority: Option<&Pubkey>,
+    ) -> Self {
+        Self {
+            mint_authority_flag: (mint_authority.is_some() as u32).to_le_bytes(),
+            mint_authority: mint_authority.copied().unwrap_or_default(),
+            supply: supply.to_le_bytes(),
+            decimals,
+            is_initialized: 1,
+            freeze_authority_flag: (freeze_authority.is_some() as u32).to_le_bytes(),
+            freeze_authority: freeze_authority.copied().unwrap_or_default(),
+        }
+    }
+
     /// Return a `Mint` from the given account info.
     ///
     /// This method performs owner and length validation on `AccountInfo`, safe borrowing
@@ -89,15 +109,14 @@ impl Mint {
 
     #[inline(always)]
     pub fn has_mint_authority(&self) -> bool {
-        self.mint_authority_flag[0] == 1
+        self.mint_authority().is_some()
     }
 
     pub fn mint_authority(&self) -> Option<&Pubkey> {
-        if self.has_mint_authority() {
-            Some(self.mint_authority_unchecked())
-        } else {
-            None
-        }
+        read_coption_pubkey(
+            state_bytes(self),
+            core::mem::offset_of!(Mint, mint_authority_flag),
+        )
     }
 
     /// Return the mint authority.
@@ -123,15 +142,14 @@ impl Mint {
 
     #[inline(always)]
     pub fn has_freeze_authority(&self) -> bool {
-        self.freeze_authority_flag[0] == 1
+        self.freeze_authority().is_some()
     }
 
     pub fn freeze_authority(&self) -> Option<&Pubkey> {
-        if self.has_freeze_authority() {
-            Some(self.freeze_authority_unchecked())
-        } else {
-            None
-        }
+        read_coption_pubkey(
+            state_bytes(self),
+            core::mem::offset_of!(Mint, freeze_authority_flag),
+        )
     }
 
     /// Return the freeze authority.
@@ -142,4 +160,243 @@ impl Mint {
     pub fn freeze_authority_unchecked(&self) -> &Pubkey {
         &self.freeze_authority
     }
+
+    /// Check that `authority` is the freeze authority of the mint.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TokenHelperError::InvalidFreezeAuthority`] if the mint has no
+    /// freeze authority or `authority` does not match it.
+    #[inline]
+    pub fn check_freeze_authority(&self, authority: &Pubkey) -> Result<(), ProgramError> {
+        match self.freeze_authority() {
+            Some(freeze_authority) if freeze_authority == authority => Ok(()),
+            _ => Err(TokenHelperError::InvalidFreezeAuthority.into()),
+        }
+    }
+}
+
+impl Pack for Mint {
+    const LEN: usize = Mint::LEN;
+
+    #[inline]
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..Self::LEN].copy_from_slice(state_bytes(self));
+    }
+
+    #[inline]
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // SAFETY: `src` is `Mint::LEN` bytes long and `Mint` has an alignment
+        // of 1.
+        let mint = unsafe { core::ptr::read(src.as_ptr() as *const Mint) };
+
+        if !is_valid_coption_tag(mint.mint_authority_flag)
+            || !is_valid_coption_tag(mint.freeze_authority_flag)
+            || mint.is_initialized > 1
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(mint)
+    }
+}
+
+/// Length of the buffer needed by [`ui_amount_string`] for any amount and
+/// decimals.
+///
+/// An amount with `u8::MAX` decimals takes a leading `0.` and `u8::MAX` digits.
+pub const MAX_UI_AMOUNT_STRING_LEN: usize = 2 + u8::MAX as usize;
+
+/// Write `amount` of a mint with `decimals` decimals as a UI amount string to
+/// `buffer`, as the `AmountToUiAmount` instruction of plain mints does.
+///
+/// The decimal point is placed `decimals` digits from the right and trailing
+/// zeros of the fractional part are trimmed, together with the point when
+/// nothing is left after it. Amounts smaller than one whole token have a
+/// leading `0.`.
+///
+/// Returns the formatted string, which borrows `buffer`.
+///
+/// # Panics
+///
+/// Panics if `buffer` is too small to hold the string; a buffer of
+/// [`MAX_UI_AMOUNT_STRING_LEN`] bytes fits any amount.
+pub fn ui_amount_string(amount: u64, decimals: u8, buffer: &mut [u8]) -> &str {
+    let mut digits_buffer = [0; fmt::MAX_LEN];
+    let digits = fmt::u64_to_str(&mut digits_buffer, amount).as_bytes();
+    let decimals = decimals as usize;
+
+    let (integer, fraction) = digits.split_at(digits.len().saturating_sub(decimals));
+    // the fraction is padded with leading zeros to `decimals` digits
+    let padding = decimals - fraction.len();
+    let fraction = match fraction.iter().rposition(|digit| *digit != b'0') {
+        Some(last) => &fraction[..=last],
+        None => &[],
+    };
+
+    let mut len = 0;
+    let mut write = |bytes: &[u8]| {
+        buffer[len..len + bytes.len()].copy_from_slice(bytes);
+        len += bytes.len();
+    };
+
+    write(if integer.is_empty() { b"0" } else { integer });
+    if !fraction.is_empty() {
+        write(b".");
+        for _ in 0..padding {
+            write(b"0");
+        }
+        write(fraction);
+    }
+
+    // SAFETY: Only ASCII digits and the decimal point were written.
+    unsafe { core::str::from_utf8_unchecked(&buffer[..len]) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mint_bytes;
+
+    #[test]
+    fn test_check_freeze_authority() {
+        let authority = [1; 32];
+
+        let bytes = mint_bytes(Some(&authority));
+        let mint = unsafe { Mint::from_bytes(&bytes) };
+        assert!(mint.is_initialized());
+        assert_eq!(mint.check_freeze_authority(&authority), Ok(()));
+
+        // wrong authority
+        assert_eq!(
+            mint.check_freeze_authority(&[2; 32]),
+            Err(TokenHelperError::InvalidFreezeAuthority.into())
+        );
+
+        // no freeze authority
+        let bytes = mint_bytes(None);
+        let mint = unsafe { Mint::from_bytes(&bytes) };
+        assert_eq!(
+            mint.check_freeze_authority(&authority),
+            Err(TokenHelperError::InvalidFreezeAuthority.into())
+        );
+        // a zeroed key does not match an absent authority
+        assert_eq!(
+            mint.check_freeze_authority(&[0; 32]),
+            Err(TokenHelperError::InvalidFreezeAuthority.into())
+        );
+    }
+
+    #[test]
+    fn test_pack_round_trip() {
+        let mint_authority = [1; 32];
+        let freeze_authority = [2; 32];
+
+        for (mint_authority, freeze_authority) in [
+            (Some(&mint_authority), Some(&freeze_authority)),
+            (Some(&mint_authority), None),
+            (None, Some(&freeze_authority)),
+            (None, None),
+        ] {
+            let mint = Mint::new(mint_authority, 1_000_000, 6, freeze_authority);
+
+            let mut bytes = [0xff; Mint::LEN];
+            mint.pack_into_slice(&mut bytes);
+
+            let unpacked = Mint::unpack_from_slice(&bytes).unwrap();
+            assert_eq!(unpacked.mint_authority(), mint_authority);
+            assert_eq!(unpacked.supply(), 1_000_000);
+            assert_eq!(unpacked.decimals(), 6);
+            assert!(unpacked.is_initialized());
+            assert_eq!(unpacked.freeze_authority(), freeze_authority);
+
+            let mut repacked = [0; Mint::LEN];
+            unpacked.pack_into_slice(&mut repacked);
+            assert_eq!(repacked, bytes);
+        }
+
+        // the `COption` encoding matches the one read in place
+        let mut bytes = [0; Mint::LEN];
+        Mint::new(None, 0, 0, Some(&freeze_authority)).pack_into_slice(&mut bytes);
+        assert_eq!(bytes, mint_bytes(Some(&freeze_authority)));
+    }
+
+    #[test]
+    fn test_unpack_invalid() {
+        let mut bytes = mint_bytes(None);
+        assert!(Mint::unpack_from_slice(&bytes).is_ok());
+
+        assert_eq!(
+            Mint::unpack_from_slice(&bytes[1..]).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+
+        // invalid `COption` tag
+        bytes[46] = 2;
+        assert_eq!(
+            Mint::unpack_from_slice(&bytes).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_authorities() {
+        let authority = [1; 32];
+
+        let mut bytes = mint_bytes(Some(&authority));
+        let mint = unsafe { Mint::from_bytes(&bytes) };
+        assert_eq!(mint.mint_authority(), None);
+        assert!(!mint.has_mint_authority());
+        assert_eq!(mint.freeze_authority(), Some(&authority));
+        assert!(mint.has_freeze_authority());
+
+        // a malformed tag is read as no authority
+        bytes[47] = 1;
+        let mint = unsafe { Mint::from_bytes(&bytes) };
+        assert_eq!(mint.freeze_authority(), None);
+        assert!(!mint.has_freeze_authority());
+    }
+
+    #[test]
+    fn test_ui_amount_string() {
+        let mut buffer = [0; MAX_UI_AMOUNT_STRING_LEN];
+
+        for (amount, decimals, expected) in [
+            // no decimal point without decimals
+            (0, 0, "0"),
+            (1, 0, "1"),
+            (1_000, 0, "1000"),
+            (u64::MAX, 0, "18446744073709551615"),
+            // whole tokens
+            (0, 6, "0"),
+            (1_000_000, 6, "1"),
+            (250_000_000, 6, "250"),
+            // trailing zeros are trimmed
+            (1_500_000, 6, "1.5"),
+            (1_234_567, 6, "1.234567"),
+            (10_010, 2, "100.1"),
+            // less than one whole token
+            (1, 6, "0.000001"),
+            (120, 6, "0.00012"),
+            (999_999, 6, "0.999999"),
+            (1, 9, "0.000000001"),
+            (u64::MAX, 9, "18446744073.709551615"),
+            (u64::MAX, 20, "0.18446744073709551615"),
+        ] {
+            assert_eq!(ui_amount_string(amount, decimals, &mut buffer), expected);
+        }
+
+        let string = ui_amount_string(1, u8::MAX, &mut buffer);
+        assert_eq!(string.len(), MAX_UI_AMOUNT_STRING_LEN);
+        assert!(string.starts_with("0.000"));
+        assert!(string.ends_with("0001"));
+
+        // the buffer only needs to fit the string
+        let mut buffer = [0; 3];
+        assert_eq!(ui_amount_string(150, 2, &mut buffer), "1.5");
+    }
 }
@@ -1,7 +1,71 @@
 mod account_state;
 mod mint;
+mod pack;
 mod token;
 
 pub use account_state::*;
 pub use mint::*;
+pub use pack::*;
 pub use token::*;
+
+use pinocchio::pubkey::{Pubkey, PUBKEY_BYTES};
+
+/// Length of a `COption<Pubkey>`: a 4-byte tag followed by the pubkey.
+pub const COPTION_PUBKEY_LEN: usize = 4 + PUBKEY_BYTES;
+
+/// Read a `COption<Pubkey>` at `offset` of `bytes`.
+///
+/// The option is encoded as a little-endian `u32` tag followed by the pubkey,
+/// which is only meaningful when the tag is `1`. A tag of `0` is `None`; any
+/// other tag is malformed and is also read as `None`, as are `bytes` too short
+/// to hold the option.
+#[inline(always)]
+pub fn read_coption_pubkey(bytes: &[u8], offset: usize) -> Option<&Pubkey> {
+    let bytes = bytes.get(offset..offset.checked_add(COPTION_PUBKEY_LEN)?)?;
+    let (tag, pubkey) = bytes.split_at(4);
+    if u32::from_le_bytes([tag[0], tag[1], tag[2], tag[3]]) == 1 {
+        // SAFETY: `pubkey` is exactly `PUBKEY_BYTES` long.
+        Some(unsafe { &*(pubkey.as_ptr() as *const Pubkey) })
+    } else {
+        None
+    }
+}
+
+/// Return the bytes of a `#[repr(C)]` state struct.
+#[inline(always)]
+fn state_bytes<T>(state: &T) -> &[u8] {
+    // SAFETY: The state structs only contain byte fields, so every byte of
+    // `state` is initialized.
+    unsafe {
+        core::slice::from_raw_parts(state as *const T as *const u8, core::mem::size_of::<T>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_coption_pubkey() {
+        let mut bytes = [0u8; 2 + COPTION_PUBKEY_LEN];
+        bytes[6..].copy_from_slice(&[7; 32]);
+
+        // None
+        assert_eq!(read_coption_pubkey(&bytes, 2), None);
+
+        // Some
+        bytes[2] = 1;
+        assert_eq!(read_coption_pubkey(&bytes, 2), Some(&[7; 32]));
+
+        // malformed tags
+        for tag in [2u32, 0x0100_0001, u32::MAX] {
+            bytes[2..6].copy_from_slice(&tag.to_le_bytes());
+            assert_eq!(read_coption_pubkey(&bytes, 2), None);
+        }
+
+        // out of range
+        bytes[2..6].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(read_coption_pubkey(&bytes, 3), None);
+        assert_eq!(read_coption_pubkey(&bytes, usize::MAX), None);
+    }
+}
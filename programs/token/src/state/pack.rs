@@ -0,0 +1,29 @@
+use pinocchio::program_error::ProgramError;
+
+/// Serialization of the token state types to and from account data.
+pub trait Pack: Sized {
+    /// The length of the serialized state.
+    const LEN: usize;
+
+    /// Write the state to the first [`Self::LEN`] bytes of `dst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dst` is shorter than [`Self::LEN`].
+    fn pack_into_slice(&self, dst: &mut [u8]);
+
+    /// Read the state from `src`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidAccountData`] if `src` is not
+    /// [`Self::LEN`] bytes long or does not hold a valid representation of
+    /// the state, e.g., an optional field with an invalid `COption` tag.
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError>;
+}
+
+/// Indicates whether `tag` is a valid `COption` tag, i.e., `0` or `1`.
+#[inline(always)]
+pub(crate) fn is_valid_coption_tag(tag: [u8; 4]) -> bool {
+    u32::from_le_bytes(tag) <= 1
+}
@@ -1,4 +1,4 @@
-use super::AccountState;
+use super::{is_valid_coption_tag, read_coption_pubkey, state_bytes, AccountState, Pack};
 use pinocchio::{
     account_info::{AccountInfo, Ref},
     program_error::ProgramError,
@@ -116,15 +116,14 @@ impl TokenAccount {
 
     #[inline(always)]
     pub fn has_delegate(&self) -> bool {
-        self.delegate_flag[0] == 1
+        self.delegate().is_some()
     }
 
     pub fn delegate(&self) -> Option<&Pubkey> {
-        if self.has_delegate() {
-            Some(self.delegate_unchecked())
-        } else {
-            None
-        }
+        read_coption_pubkey(
+            state_bytes(self),
+            core::mem::offset_of!(TokenAccount, delegate_flag),
+        )
     }
 
     /// Use this when you know the account will have a delegate and want to skip the `Option` check.
@@ -166,15 +165,14 @@ impl TokenAccount {
 
     #[inline(always)]
     pub fn has_close_authority(&self) -> bool {
-        self.close_authority_flag[0] == 1
+        self.close_authority().is_some()
     }
 
     pub fn close_authority(&self) -> Option<&Pubkey> {
-        if self.has_close_authority() {
-            Some(self.close_authority_unchecked())
-        } else {
-            None
-        }
+        read_coption_pubkey(
+            state_bytes(self),
+            core::mem::offset_of!(TokenAccount, close_authority_flag),
+        )
     }
 
     /// Return the close authority.
@@ -196,3 +194,73 @@ impl TokenAccount {
         self.state == AccountState::Frozen as u8
     }
 }
+
+impl Pack for TokenAccount {
+    const LEN: usize = TokenAccount::LEN;
+
+    #[inline]
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[..Self::LEN].copy_from_slice(state_bytes(self));
+    }
+
+    #[inline]
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // SAFETY: `src` is `TokenAccount::LEN` bytes long and `TokenAccount`
+        // has an alignment of 1.
+        let account = unsafe { core::ptr::read(src.as_ptr() as *const TokenAccount) };
+
+        if !is_valid_coption_tag(account.delegate_flag)
+            || !is_valid_coption_tag(account.is_native)
+            || !is_valid_coption_tag(account.close_authority_flag)
+            || account.state > AccountState::Frozen as u8
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_round_trip() {
+        let mut bytes = [0; TokenAccount::LEN];
+        bytes[..32].copy_from_slice(&[1; 32]);
+        bytes[32..64].copy_from_slice(&[2; 32]);
+        bytes[64..72].copy_from_slice(&100u64.to_le_bytes());
+        // delegate
+        bytes[72] = 1;
+        bytes[76..108].copy_from_slice(&[3; 32]);
+        bytes[108] = AccountState::Frozen as u8;
+        // close authority
+        bytes[129] = 1;
+        bytes[133..].copy_from_slice(&[4; 32]);
+
+        let account = TokenAccount::unpack_from_slice(&bytes).unwrap();
+        assert_eq!(account.mint(), &[1; 32]);
+        assert_eq!(account.owner(), &[2; 32]);
+        assert_eq!(account.amount(), 100);
+        assert_eq!(account.delegate(), Some(&[3; 32]));
+        assert!(account.is_frozen());
+        assert_eq!(account.native_amount(), None);
+        assert_eq!(account.close_authority(), Some(&[4; 32]));
+
+        let mut repacked = [0; TokenAccount::LEN];
+        account.pack_into_slice(&mut repacked);
+        assert_eq!(repacked, bytes);
+
+        // invalid account state
+        bytes[108] = 3;
+        assert_eq!(
+            TokenAccount::unpack_from_slice(&bytes).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+}
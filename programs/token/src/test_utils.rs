@@ -0,0 +1,53 @@
+//! Helpers to create accounts for host tests.
+
+extern crate std;
+
+use std::vec::Vec;
+
+use pinocchio::{
+    account_info::AccountInfo,
+    mock::{serialized_input, SerializedAccount},
+    pubkey::Pubkey,
+};
+
+use crate::state::Mint;
+
+/// An `AccountInfo` backed by a runtime-like serialized input buffer.
+pub(crate) struct TestAccount {
+    /// The input buffer holding the account; must outlive `info`.
+    _buffer: Vec<u64>,
+    pub(crate) info: AccountInfo,
+}
+
+/// Creates an `AccountInfo` with the given key, owner and data.
+///
+/// The account is a signer and writable, and is deserialized from a
+/// runtime-like input by the `pinocchio` entrypoint.
+pub(crate) fn account(key: Pubkey, owner: Pubkey, data: &[u8]) -> TestAccount {
+    let (buffer, [info]) = serialized_input([SerializedAccount {
+        is_signer: true,
+        is_writable: true,
+        key,
+        owner,
+        lamports: 1_000_000_000,
+        data,
+        ..SerializedAccount::default()
+    }]);
+
+    TestAccount {
+        _buffer: buffer,
+        info,
+    }
+}
+
+/// Returns the bytes of an initialized `Mint` with the given freeze authority.
+pub(crate) fn mint_bytes(freeze_authority: Option<&Pubkey>) -> [u8; Mint::LEN] {
+    let mut bytes = [0; Mint::LEN];
+    // is_initialized
+    bytes[45] = 1;
+    if let Some(freeze_authority) = freeze_authority {
+        bytes[46] = 1;
+        bytes[50..].copy_from_slice(freeze_authority);
+    }
+    bytes
+}
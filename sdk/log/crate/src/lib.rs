@@ -52,7 +52,11 @@ pub use pinocchio_log_macro::*;
 
 #[cfg(test)]
 mod tests {
-    use crate::logger::{Argument, Logger};
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use crate::logger::{Argument, BatchLogger, Logger};
 
     /// Helper macro to generate test cases for numeric types.
     ///
@@ -100,6 +104,53 @@ mod tests {
         assert!(&*logger == "balance=1000000000".as_bytes());
     }
 
+    #[test]
+    fn test_batch_logger() {
+        let mut logs = Vec::new();
+        let mut logger = BatchLogger::<32>::default();
+
+        logger.push_with(b"first", |m| logs.push(m.to_vec()));
+        logger.push_with(b"second", |m| logs.push(m.to_vec()));
+        logger.push_with(b"third", |m| logs.push(m.to_vec()));
+        assert!(logs.is_empty());
+        assert_eq!(&*logger, b"first | second | third");
+
+        logger.flush_with(|m| logs.push(m.to_vec()));
+        assert_eq!(logs, [b"first | second | third".to_vec()]);
+        assert!(logger.is_empty());
+
+        // flushing an empty batch does not log
+        logger.flush_with(|m| logs.push(m.to_vec()));
+        assert_eq!(logs.len(), 1);
+    }
+
+    #[test]
+    fn test_batch_logger_overflow() {
+        let mut logs = Vec::new();
+        let mut logger = BatchLogger::<16>::default();
+
+        logger.push_with(b"0123456789", |m| logs.push(m.to_vec()));
+        assert!(logs.is_empty());
+
+        // the message and separator do not fit, so the batch is flushed early
+        logger.push_with(b"abcd", |m| logs.push(m.to_vec()));
+        assert_eq!(logs, [b"0123456789".to_vec()]);
+        assert_eq!(&*logger, b"abcd");
+        assert_eq!(logger.remaining(), 12);
+
+        // a message longer than the buffer is logged on its own
+        logger.push_with(b"this message is too long", |m| logs.push(m.to_vec()));
+        assert_eq!(
+            logs,
+            [
+                b"0123456789".to_vec(),
+                b"abcd".to_vec(),
+                b"this message is too long".to_vec()
+            ]
+        );
+        assert!(logger.is_empty());
+    }
+
     #[test]
     fn test_logger_truncated() {
         let mut logger = Logger::<8>::default();
@@ -117,6 +117,133 @@ impl<const BUFFER: usize> Logger<BUFFER> {
     }
 }
 
+/// Bytes separating the messages of a batch.
+pub const BATCH_SEPARATOR: &[u8] = b" | ";
+
+/// Logger that batches messages into a single log call.
+///
+/// Each log syscall has a base cost, so logging several short messages
+/// separately is more expensive than logging them at once. Messages pushed to
+/// the logger are accumulated in a fixed size buffer, separated by
+/// [`BATCH_SEPARATOR`], and logged together on [`BatchLogger::flush`].
+///
+/// When a message does not fit in the remaining space of the buffer, the
+/// pending messages are flushed first. A message longer than the buffer is
+/// logged on its own. Pending messages are not logged unless the logger is
+/// flushed.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio_log::logger::BatchLogger;
+///
+/// let mut logger = BatchLogger::<100>::default();
+/// logger.push("validating accounts").push("transferring");
+///
+/// // Logs "validating accounts | transferring".
+/// logger.flush();
+/// ```
+pub struct BatchLogger<const N: usize> {
+    // Byte buffer to store the pending messages.
+    buffer: [MaybeUninit<u8>; N],
+
+    // Length of the pending messages.
+    len: usize,
+}
+
+impl<const N: usize> Default for BatchLogger<N> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            buffer: [UNINIT_BYTE; N],
+            len: 0,
+        }
+    }
+}
+
+impl<const N: usize> Deref for BatchLogger<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the slice is created from the buffer up to the length
+        // of the pending messages.
+        unsafe { from_raw_parts(self.buffer.as_ptr() as *const _, self.len) }
+    }
+}
+
+impl<const N: usize> BatchLogger<N> {
+    /// Push a message to the batch, flushing the pending messages first if
+    /// the message does not fit.
+    #[inline(always)]
+    pub fn push(&mut self, message: &str) -> &mut Self {
+        self.push_with(message.as_bytes(), log_message);
+        self
+    }
+
+    /// Log the pending messages as a single message and clear the batch.
+    #[inline(always)]
+    pub fn flush(&mut self) {
+        self.flush_with(log_message);
+    }
+
+    /// Push `message` to the batch, using `log` to flush.
+    pub(crate) fn push_with(&mut self, message: &[u8], mut log: impl FnMut(&[u8])) {
+        let separator = if self.len == 0 {
+            0
+        } else {
+            BATCH_SEPARATOR.len()
+        };
+
+        if self.len + separator + message.len() > N {
+            self.flush_with(&mut log);
+        }
+
+        if message.len() > N {
+            // The message can only be logged on its own.
+            log(message);
+            return;
+        }
+
+        if self.len > 0 {
+            write_bytes(&mut self.buffer[self.len..], BATCH_SEPARATOR);
+            self.len += BATCH_SEPARATOR.len();
+        }
+
+        write_bytes(&mut self.buffer[self.len..], message);
+        self.len += message.len();
+    }
+
+    /// Log the pending messages using `log` and clear the batch.
+    pub(crate) fn flush_with(&mut self, mut log: impl FnMut(&[u8])) {
+        if self.len > 0 {
+            log(self);
+            self.len = 0;
+        }
+    }
+
+    /// Check whether there are no pending messages.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the remaining space in the batch buffer.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        N - self.len
+    }
+}
+
+/// Copy `source` to the start of `destination`.
+///
+/// The caller must ensure that `destination` is large enough.
+#[inline(always)]
+fn write_bytes(destination: &mut [MaybeUninit<u8>], source: &[u8]) {
+    for (d, s) in destination.iter_mut().zip(source) {
+        d.write(*s);
+    }
+}
+
 /// Log a message.
 #[inline(always)]
 pub fn log_message(message: &[u8]) {
@@ -7,14 +7,28 @@ use core::{
 };
 
 #[cfg(target_os = "solana")]
-use crate::syscalls::sol_memset_;
+use crate::{memory::sol_memcmp, syscalls::sol_memset_};
+
+use crate::{
+    cpi::invoke_signed,
+    instruction::{AccountMeta, Instruction, Signer},
+    pod::Pod,
+    program_error::ProgramError,
+    pubkey::{pubkey_eq, Pubkey},
+    sysvars::rent::Rent,
+    ProgramResult,
+};
 
-use crate::{program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+/// The ID of the system program.
+const SYSTEM_PROGRAM_ID: Pubkey = [0; 32];
 
 /// Maximum number of bytes a program may add to an account during a
 /// single top-level instruction.
 pub const MAX_PERMITTED_DATA_INCREASE: usize = 1_024 * 10;
 
+/// Maximum length of the data of an account.
+pub const MAX_PERMITTED_DATA_LENGTH: usize = 10 * 1_024 * 1_024;
+
 /// Raw account data.
 ///
 /// This data is wrapped in an `AccountInfo` struct, which provides safe access
@@ -46,14 +60,16 @@ pub(crate) struct Account {
     /// Account's original data length when it was serialized for the
     /// current program invocation.
     ///
-    /// The value of this field is lazily initialized to the current data length
-    /// and the [`SET_LEN_MASK`] flag on first access. When reading this field,
-    /// the flag is cleared to retrieve the original data length by using the
-    /// [`GET_LEN_MASK`] mask.
+    /// The value of this field is set to the serialized data length and the
+    /// [`SET_LEN_MASK`] flag when the entrypoint deserializes the account, or
+    /// lazily on the first `realloc` for accounts created otherwise. When
+    /// reading this field, the flag is cleared to retrieve the original data
+    /// length by using the [`GET_LEN_MASK`] mask.
     ///
-    /// Currently, this value is only used for `realloc` to determine if the
-    /// account data length has changed from the original serialized length beyond
-    /// the maximum permitted data increase.
+    /// This value is used by `realloc` to determine if the account data length
+    /// has changed from the original serialized length beyond the maximum
+    /// permitted data increase, and to locate the rent epoch, which does not
+    /// move when the data length changes.
     original_data_len: u32,
 
     /// Public key of the account.
@@ -83,6 +99,17 @@ const SET_LEN_MASK: u32 = 1 << 31;
 /// by clearing the flag that indicates the original data length has been set.
 const GET_LEN_MASK: u32 = !SET_LEN_MASK;
 
+impl Account {
+    /// Record the current data length as the original data length.
+    ///
+    /// This must be called when the account is deserialized, before its data
+    /// length can change through a `realloc`, `close` or CPI.
+    #[inline(always)]
+    pub(crate) fn init_original_data_len(&mut self) {
+        self.original_data_len = (self.data_len as u32) | SET_LEN_MASK;
+    }
+}
+
 /// Wrapper struct for an `Account`.
 ///
 /// This struct provides safe access to the data in an `Account`. It is also
@@ -104,6 +131,15 @@ impl AccountInfo {
         unsafe { &(*self.raw).key }
     }
 
+    /// Checks if the public key of the account is `id`.
+    ///
+    /// The keys are compared using [`pubkey_eq`], which is cheaper than
+    /// comparing the result of [`Self::key`] with `==`.
+    #[inline(always)]
+    pub fn key_eq(&self, id: &Pubkey) -> bool {
+        pubkey_eq(self.key(), id)
+    }
+
     /// Program that owns this account.
     ///
     /// # Safety
@@ -135,12 +171,61 @@ impl AccountInfo {
         unsafe { (*self.raw).executable != 0 }
     }
 
+    /// The epoch at which this account will next owe rent.
+    ///
+    /// The runtime serializes the rent epoch after the account data and the
+    /// space reserved for data increases, so the value is read from the
+    /// program input based on the original data length of the account, which
+    /// is recorded by the entrypoint.
+    #[inline(always)]
+    pub fn rent_epoch(&self) -> u64 {
+        // SAFETY: `AccountInfo`s are created from the program input, where the
+        // rent epoch follows the data and the realloc padding, aligned to 8 bytes.
+        unsafe {
+            let ptr = self
+                .data_ptr()
+                .add(self.original_data_len() + MAX_PERMITTED_DATA_INCREASE);
+            let ptr = ptr.add(ptr.align_offset(crate::BPF_ALIGN_OF_U128));
+            (ptr as *const u64).read_unaligned()
+        }
+    }
+
     /// Returns the size of the data in the account.
     #[inline(always)]
     pub fn data_len(&self) -> usize {
         unsafe { (*self.raw).data_len as usize }
     }
 
+    /// Returns the length of the data when the account was serialized for the
+    /// current program invocation.
+    ///
+    /// The original length is recorded by the entrypoint, or on the first
+    /// `realloc` for accounts not created by the entrypoint; until then, the
+    /// current data length is the original one.
+    #[inline(always)]
+    fn original_data_len(&self) -> usize {
+        let length = unsafe { (*self.raw).original_data_len };
+
+        if length & SET_LEN_MASK == SET_LEN_MASK {
+            (length & GET_LEN_MASK) as usize
+        } else {
+            self.data_len()
+        }
+    }
+
+    /// Returns the maximum length the account data can be reallocated to in the
+    /// current program invocation.
+    ///
+    /// The data can grow by up to [`MAX_PERMITTED_DATA_INCREASE`] bytes from its
+    /// original length, without exceeding [`MAX_PERMITTED_DATA_LENGTH`].
+    #[inline(always)]
+    pub fn max_realloc_len(&self) -> usize {
+        core::cmp::min(
+            self.original_data_len() + MAX_PERMITTED_DATA_INCREASE,
+            MAX_PERMITTED_DATA_LENGTH,
+        )
+    }
+
     /// Returns the lamports in the account.
     #[inline(always)]
     pub fn lamports(&self) -> u64 {
@@ -197,10 +282,27 @@ impl AccountInfo {
 
     /// Returns a read-only reference to the data in the account.
     ///
+    /// This skips the borrow state check and update of [`Self::try_borrow_data`],
+    /// which is useful in performance-critical code that already knows the data
+    /// is not mutably borrowed, e.g., after checking the accounts are not
+    /// duplicated.
+    ///
     /// # Safety
     ///
     /// This method is unsafe because it does not return a `Ref`, thus leaving the borrow
     /// flag untouched. Useful when an instruction has verified non-duplicate accounts.
+    ///
+    /// The borrow checks of the other borrow methods do not see the returned
+    /// reference, so the caller must ensure that while it is held:
+    ///
+    /// - the data is not mutably borrowed, through this `AccountInfo` or any of its
+    ///   duplicates, including with [`Self::borrow_mut_data_unchecked`];
+    /// - the account is not resized with [`Self::realloc`] or closed, which would
+    ///   leave the reference with a stale length;
+    /// - the data is not modified by a cross-program invocation that receives the
+    ///   account as writable.
+    ///
+    /// Breaking any of these conditions is undefined behavior.
     #[inline(always)]
     pub unsafe fn borrow_data_unchecked(&self) -> &[u8] {
         core::slice::from_raw_parts(self.data_ptr(), self.data_len())
@@ -208,10 +310,23 @@ impl AccountInfo {
 
     /// Returns a mutable reference to the data in the account.
     ///
+    /// This skips the borrow state check and update of
+    /// [`Self::try_borrow_mut_data`]; see [`Self::borrow_data_unchecked`].
+    ///
     /// # Safety
     ///
     /// This method is unsafe because it does not return a `Ref`, thus leaving the borrow
     /// flag untouched. Useful when an instruction has verified non-duplicate accounts.
+    ///
+    /// The borrow checks of the other borrow methods do not see the returned
+    /// reference, so the caller must ensure that while it is held:
+    ///
+    /// - the data is not borrowed in any other way, through this `AccountInfo` or
+    ///   any of its duplicates, including with a second call to this method;
+    /// - the account is not resized with [`Self::realloc`] or closed;
+    /// - the account is not passed to a cross-program invocation.
+    ///
+    /// Breaking any of these conditions is undefined behavior.
     #[allow(clippy::mut_from_ref)]
     #[inline(always)]
     pub unsafe fn borrow_mut_data_unchecked(&self) -> &mut [u8] {
@@ -256,6 +371,33 @@ impl AccountInfo {
         })
     }
 
+    /// Adds `amount` to the lamports in the account.
+    ///
+    /// Fails with [`ProgramError::ArithmeticOverflow`] if the lamports would
+    /// overflow, or if the lamports are already borrowed in any form.
+    #[inline]
+    pub fn try_add_lamports(&self, amount: u64) -> ProgramResult {
+        let mut lamports = self.try_borrow_mut_lamports()?;
+        *lamports = lamports
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Subtracts `amount` from the lamports in the account.
+    ///
+    /// Fails with [`ProgramError::InsufficientFunds`] if the account holds
+    /// fewer than `amount` lamports, or if the lamports are already borrowed in
+    /// any form.
+    #[inline]
+    pub fn try_sub_lamports(&self, amount: u64) -> ProgramResult {
+        let mut lamports = self.try_borrow_mut_lamports()?;
+        *lamports = lamports
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        Ok(())
+    }
+
     /// Checks if it is possible to get a read-only reference to the lamport field,
     /// failing if the field is already mutable borrowed or if 7 borrows already exist.
     #[inline(always)]
@@ -308,6 +450,111 @@ impl AccountInfo {
         })
     }
 
+    /// Indicates whether the account data starts with `prefix`, e.g., an
+    /// account type discriminator.
+    ///
+    /// The bytes are compared with the `sol_memcmp_` syscall, and data shorter
+    /// than `prefix` does not start with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::AccountBorrowFailed`] if the account data is
+    /// already mutably borrowed.
+    #[inline]
+    pub fn data_starts_with(&self, prefix: &[u8]) -> Result<bool, ProgramError> {
+        let data = self.try_borrow_data()?;
+
+        if prefix.len() > data.len() {
+            return Ok(false);
+        }
+
+        // SAFETY: The data holds at least `prefix.len()` bytes.
+        #[cfg(target_os = "solana")]
+        return Ok(unsafe { sol_memcmp(&data, prefix, prefix.len()) } == 0);
+
+        #[cfg(not(target_os = "solana"))]
+        Ok(data.starts_with(prefix))
+    }
+
+    /// Split `accounts` into a fixed-size prefix of `N` accounts and the
+    /// remaining accounts.
+    ///
+    /// This allows handlers to destructure the accounts they expect while
+    /// keeping any trailing accounts:
+    ///
+    /// ```ignore
+    /// let ([payer, vault], remaining) = AccountInfo::split_first_n::<2>(accounts)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::NotEnoughAccountKeys`] if there are fewer than `N`
+    /// accounts.
+    #[inline(always)]
+    pub fn split_first_n<const N: usize>(
+        accounts: &[AccountInfo],
+    ) -> Result<(&[AccountInfo; N], &[AccountInfo]), ProgramError> {
+        accounts
+            .split_first_chunk::<N>()
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    }
+
+    /// Indicates whether the account is a duplicate of `other`, i.e., both refer to
+    /// the same serialized account.
+    ///
+    /// The runtime serializes an account passed more than once to an instruction only
+    /// once; the duplicates point to the same underlying data, so mutations through
+    /// either are visible to the other. The comparison uses the data pointers rather
+    /// than the keys.
+    #[inline(always)]
+    pub fn is_duplicate_of(&self, other: &AccountInfo) -> bool {
+        core::ptr::eq(self.raw, other.raw)
+    }
+
+    /// Returns the indices of the first pair of duplicate accounts in `accounts`, if
+    /// any.
+    ///
+    /// See [`Self::is_duplicate_of`] for how duplicates are detected.
+    #[inline]
+    pub fn find_duplicate(accounts: &[AccountInfo]) -> Option<(usize, usize)> {
+        accounts.iter().enumerate().find_map(|(i, account)| {
+            accounts[i + 1..]
+                .iter()
+                .position(|other| account.is_duplicate_of(other))
+                .map(|offset| (i, i + 1 + offset))
+        })
+    }
+
+    /// Tries to get read-only references to the data field split into a typed header
+    /// and the remaining bytes, failing if the field is already mutable borrowed or
+    /// if the references would exceed 7 borrows.
+    ///
+    /// Each of the returned references holds its own borrow of the data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::AccountDataTooSmall`] if the data is shorter than the
+    /// header, and [`ProgramError::InvalidAccountData`] if the data is not suitably
+    /// aligned for the header type.
+    pub fn split_data<H: Pod>(&self) -> Result<(Ref<H>, Ref<[u8]>), ProgramError> {
+        if self.data_len() < core::mem::size_of::<H>() {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        if self.data_ptr().align_offset(core::mem::align_of::<H>()) != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let header = self.try_borrow_data()?;
+        let tail = self.try_borrow_data()?;
+
+        Ok((
+            // SAFETY: The data length and alignment were validated and `H` is `Pod`.
+            Ref::map(header, |data| unsafe { &*(data.as_ptr() as *const H) }),
+            Ref::map(tail, |data| &data[core::mem::size_of::<H>()..]),
+        ))
+    }
+
     /// Tries to get a mutable reference to the data field, failing if the field
     /// is already borrowed in any form.
     pub fn try_borrow_mut_data(&self) -> Result<RefMut<[u8]>, ProgramError> {
@@ -327,6 +574,35 @@ impl AccountInfo {
         })
     }
 
+    /// Tries to get a mutable typed reference to the start of the data field of an
+    /// account owned by `owner`, failing if the field is already borrowed in any form.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::IllegalOwner`] if the account is not owned by `owner`,
+    /// [`ProgramError::AccountDataTooSmall`] if the data is shorter than `T`, and
+    /// [`ProgramError::InvalidAccountData`] if the data is not suitably aligned for `T`.
+    pub fn as_mut<T: Pod>(&self, owner: &Pubkey) -> Result<RefMut<T>, ProgramError> {
+        if !self.is_owned_by(owner) {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        if self.data_len() < core::mem::size_of::<T>() {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        if self.data_ptr().align_offset(core::mem::align_of::<T>()) != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let data = self.try_borrow_mut_data()?;
+
+        // SAFETY: The data length and alignment were validated and `T` is `Pod`.
+        Ok(RefMut::map(data, |data| unsafe {
+            &mut *(data.as_mut_ptr() as *mut T)
+        }))
+    }
+
     /// Checks if it is possible to get a read-only reference to the data field, failing
     /// if the field is already mutable borrowed or if 7 borrows already exist.
     #[inline(always)]
@@ -365,7 +641,8 @@ impl AccountInfo {
     /// memory.
     ///
     /// Note:  Account data can be increased within a single call by up to
-    /// [`MAX_PERMITTED_DATA_INCREASE`] bytes.
+    /// [`MAX_PERMITTED_DATA_INCREASE`] bytes and to at most
+    /// [`MAX_PERMITTED_DATA_LENGTH`] bytes; see [`Self::max_realloc_len`].
     ///
     /// Note: Memory used to grow is already zero-initialized upon program
     /// entrypoint and re-zeroing it wastes compute units.  If within the same
@@ -373,6 +650,13 @@ impl AccountInfo {
     /// the new space could contain stale data.  Pass `true` for `zero_init` in
     /// this case, otherwise compute units will be wasted re-zero-initializing.
     ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::AccountBorrowFailed`] if the account data is
+    /// borrowed, since a held reference would keep the previous length; the
+    /// borrow must be released before reallocating. Returns
+    /// [`ProgramError::InvalidRealloc`] if the new length exceeds the limits.
+    ///
     /// # Safety
     ///
     /// This method makes assumptions about the layout and location of memory
@@ -388,6 +672,10 @@ impl AccountInfo {
             return Ok(());
         }
 
+        if new_len > MAX_PERMITTED_DATA_LENGTH {
+            return Err(ProgramError::InvalidRealloc);
+        }
+
         let original_len = {
             let length = unsafe { (*self.raw).original_data_len };
 
@@ -436,6 +724,63 @@ impl AccountInfo {
         Ok(())
     }
 
+    /// Realloc the account's data, topping up its lamports from `payer` so that
+    /// it remains rent exempt at the new length.
+    ///
+    /// The lamports are transferred with a system program `Transfer`, so `payer`
+    /// must be a signer owned by the system program; `signers` can be used when
+    /// `payer` is a PDA. No lamports are transferred if the account already holds
+    /// the minimum balance for the new length.
+    ///
+    /// The new memory is not zero-initialized; see [`Self::realloc`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InsufficientFunds`] if `payer` cannot cover the
+    /// top-up, or any error returned by [`Self::realloc`] or the transfer.
+    pub fn realloc_rent_exempt(
+        &self,
+        new_len: usize,
+        payer: &AccountInfo,
+        rent: &Rent,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        let top_up = rent
+            .minimum_balance(new_len)
+            .saturating_sub(self.lamports());
+
+        if top_up > payer.lamports() {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        self.realloc(new_len, false)?;
+
+        if top_up == 0 {
+            return Ok(());
+        }
+
+        // account metadata
+        let account_metas: [AccountMeta; 2] = [
+            AccountMeta::writable_signer(payer.key()),
+            AccountMeta::writable(self.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0..4]: system instruction discriminator (4 bytes, u32)
+        // -  [4..12]: lamports (8 bytes, u64)
+        let mut instruction_data = [0; 12];
+        instruction_data[0] = 2;
+        instruction_data[4..].copy_from_slice(&top_up.to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: &SYSTEM_PROGRAM_ID,
+            accounts: &account_metas,
+            data: &instruction_data,
+        };
+
+        invoke_signed(&instruction, &[payer, self], signers)
+    }
+
     /// Zero out the the account's data length, lamports and owner fields, effectively
     /// closing the account.
     ///
@@ -493,6 +838,8 @@ impl AccountInfo {
         // So we can zero out them directly.
         #[cfg(target_os = "solana")]
         sol_memset_(self.data_ptr().sub(48), 0, 48);
+        #[cfg(not(target_os = "solana"))]
+        core::ptr::write_bytes(self.data_ptr().sub(48), 0, 48);
     }
 
     /// Returns the memory address of the account data.
@@ -651,10 +998,585 @@ impl<T: ?Sized> Drop for RefMut<'_, T> {
     }
 }
 
+/// Bind the accounts of an instruction to names, validating their roles.
+///
+/// Each entry is a role followed by the name to bind the account to:
+///
+/// - `account name`: no checks.
+/// - `signer name`: the account must be a signer.
+/// - `writable name`: the account must be writable.
+/// - `writable_signer name`: the account must be a writable signer.
+/// - `owned name(OWNER)`: the account must be owned by `OWNER`.
+/// - `program name(ID)`: the account must be the executable program `ID`.
+///
+/// The macro returns early from the enclosing function with
+/// [`ProgramError::NotEnoughAccountKeys`] if there are fewer accounts than
+/// entries, and with the corresponding error if a role check fails. Any
+/// accounts after the expected ones are ignored.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::{account_info::AccountInfo, expect_accounts, ProgramResult};
+///
+/// const SYSTEM_ID: [u8; 32] = [0; 32];
+///
+/// fn process(accounts: &[AccountInfo]) -> ProgramResult {
+///     expect_accounts!(accounts, [signer payer, writable vault, program system(SYSTEM_ID)]);
+///     // `payer`, `vault` and `system` are now `&AccountInfo`s.
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! expect_accounts {
+    ( $accounts:expr, [ $( $role:ident $name:ident $( ( $arg:expr ) )? ),+ $(,)? ] ) => {
+        let [ $( $name ),+ , .. ] = $accounts else {
+            return Err($crate::program_error::ProgramError::NotEnoughAccountKeys.into());
+        };
+        $( $crate::expect_accounts!(@check $role $name $( ( $arg ) )?); )+
+    };
+    (@check account $name:ident) => {};
+    (@check signer $name:ident) => {
+        if !$name.is_signer() {
+            return Err($crate::program_error::ProgramError::MissingRequiredSignature.into());
+        }
+    };
+    (@check writable $name:ident) => {
+        if !$name.is_writable() {
+            return Err($crate::program_error::ProgramError::Immutable.into());
+        }
+    };
+    (@check writable_signer $name:ident) => {
+        $crate::expect_accounts!(@check signer $name);
+        $crate::expect_accounts!(@check writable $name);
+    };
+    (@check owned $name:ident ( $owner:expr )) => {
+        if !$name.is_owned_by(&$owner) {
+            return Err($crate::program_error::ProgramError::InvalidAccountOwner.into());
+        }
+    };
+    (@check program $name:ident ( $id:expr )) => {
+        if !$name.key_eq(&$id) || !$name.executable() {
+            return Err($crate::program_error::ProgramError::IncorrectProgramId.into());
+        }
+    };
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
+    /// Creates an `AccountInfo` backed by `buffer`, which holds the `Account`
+    /// followed by a copy of `data`.
+    pub(crate) fn account_info(buffer: &mut [u64], data: &[u8]) -> AccountInfo {
+        let account_len = core::mem::size_of::<Account>();
+        assert!(buffer.len() * 8 >= account_len + data.len());
+
+        let raw = buffer.as_mut_ptr() as *mut Account;
+        unsafe {
+            raw.write(Account {
+                data_len: data.len() as u64,
+                ..Account::default()
+            });
+            core::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                (raw as *mut u8).add(account_len),
+                data.len(),
+            );
+        }
+
+        AccountInfo { raw }
+    }
+
+    /// Set the key of an account created with [`account_info`].
+    pub(crate) fn set_key(account: &AccountInfo, key: Pubkey) {
+        unsafe { (*account.raw).key = key };
+    }
+
+    /// Set the signer and writable flags of an account created with
+    /// [`account_info`].
+    pub(crate) fn set_flags(account: &AccountInfo, is_signer: bool, is_writable: bool) {
+        unsafe {
+            (*account.raw).is_signer = is_signer as u8;
+            (*account.raw).is_writable = is_writable as u8;
+        }
+    }
+
+    fn expect_accounts(accounts: &[AccountInfo]) -> ProgramResult {
+        expect_accounts!(accounts, [signer payer, writable vault, program system([7; 32])]);
+
+        assert_eq!(payer.key(), &[1; 32]);
+        assert_eq!(vault.key(), &[2; 32]);
+        assert_eq!(system.key(), &[7; 32]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_expect_accounts() {
+        let mut buffers = [[0u64; 16]; 4];
+        let [payer, vault, system, extra] = buffers.each_mut();
+        let accounts = [
+            account_info(payer, &[]),
+            account_info(vault, &[]),
+            account_info(system, &[]),
+            account_info(extra, &[]),
+        ];
+        unsafe {
+            (*accounts[0].raw).key = [1; 32];
+            (*accounts[0].raw).is_signer = 1;
+            (*accounts[1].raw).key = [2; 32];
+            (*accounts[1].raw).is_writable = 1;
+            (*accounts[2].raw).key = [7; 32];
+            (*accounts[2].raw).executable = 1;
+        }
+
+        assert_eq!(expect_accounts(&accounts), Ok(()));
+        assert_eq!(expect_accounts(&accounts[..3]), Ok(()));
+
+        // Too few accounts.
+        assert_eq!(
+            expect_accounts(&accounts[..2]),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+
+        // Role mismatches.
+        unsafe { (*accounts[1].raw).is_writable = 0 };
+        assert_eq!(expect_accounts(&accounts), Err(ProgramError::Immutable));
+        unsafe { (*accounts[1].raw).is_writable = 1 };
+
+        unsafe { (*accounts[2].raw).executable = 0 };
+        assert_eq!(
+            expect_accounts(&accounts),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        unsafe { (*accounts[2].raw).executable = 1 };
+
+        unsafe { (*accounts[0].raw).is_signer = 0 };
+        assert_eq!(
+            expect_accounts(&accounts),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+    }
+
+    #[test]
+    fn test_key_eq() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[]);
+        unsafe { (*account.raw).key = [3; 32] };
+
+        assert!(account.key_eq(&[3; 32]));
+        assert!(!account.key_eq(&[4; 32]));
+
+        let mut other = [3; 32];
+        other[31] = 0;
+        assert!(!account.key_eq(&other));
+    }
+
+    #[test]
+    fn test_add_and_sub_lamports() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[]);
+        assert_eq!(account.lamports(), 0);
+
+        assert_eq!(account.try_add_lamports(10), Ok(()));
+        assert_eq!(account.try_sub_lamports(4), Ok(()));
+        assert_eq!(account.lamports(), 6);
+
+        // Underflow and overflow leave the lamports unchanged.
+        assert_eq!(
+            account.try_sub_lamports(7),
+            Err(ProgramError::InsufficientFunds)
+        );
+        assert_eq!(
+            account.try_add_lamports(u64::MAX - 5),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+        assert_eq!(account.lamports(), 6);
+
+        assert_eq!(account.try_add_lamports(u64::MAX - 6), Ok(()));
+        assert_eq!(account.lamports(), u64::MAX);
+        assert_eq!(account.try_sub_lamports(u64::MAX), Ok(()));
+        assert_eq!(account.lamports(), 0);
+
+        // The lamports cannot be updated while borrowed.
+        let lamports = account.try_borrow_lamports().unwrap();
+        assert_eq!(
+            account.try_add_lamports(1),
+            Err(ProgramError::AccountBorrowFailed)
+        );
+        drop(lamports);
+        assert_eq!(account.try_add_lamports(1), Ok(()));
+    }
+
+    #[test]
+    fn test_as_mut() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[1, 0, 0, 0, 0, 0, 0, 0, 2]);
+        unsafe { (*account.raw).owner = [5; 32] };
+
+        {
+            let mut value = account.as_mut::<u64>(&[5; 32]).unwrap();
+            assert_eq!(*value, 1);
+            *value = 300;
+
+            // The data is borrowed while the reference is alive.
+            assert_eq!(
+                account.try_borrow_data().err(),
+                Some(ProgramError::AccountBorrowFailed)
+            );
+        }
+        assert_eq!(
+            *account.try_borrow_data().unwrap(),
+            [44, 1, 0, 0, 0, 0, 0, 0, 2]
+        );
+
+        // Wrong owner.
+        assert_eq!(
+            account.as_mut::<u64>(&[6; 32]).err(),
+            Some(ProgramError::IllegalOwner)
+        );
+
+        // Too small.
+        assert_eq!(
+            account.as_mut::<[u64; 2]>(&[5; 32]).err(),
+            Some(ProgramError::AccountDataTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_realloc_limits() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[1; 8]);
+        assert_eq!(account.max_realloc_len(), 8 + MAX_PERMITTED_DATA_INCREASE);
+
+        assert_eq!(
+            account.realloc(MAX_PERMITTED_DATA_LENGTH + 1, false),
+            Err(ProgramError::InvalidRealloc)
+        );
+        assert_eq!(
+            account.realloc(account.max_realloc_len() + 1, false),
+            Err(ProgramError::InvalidRealloc)
+        );
+        assert_eq!(account.data_len(), 8);
+
+        // the limit is relative to the original length
+        assert_eq!(account.realloc(4, false), Ok(()));
+        assert_eq!(account.max_realloc_len(), 8 + MAX_PERMITTED_DATA_INCREASE);
+
+        // close to the maximum length, the increase is capped
+        unsafe { (*account.raw).original_data_len = 0 };
+        unsafe { (*account.raw).data_len = (MAX_PERMITTED_DATA_LENGTH - 8) as u64 };
+        assert_eq!(account.max_realloc_len(), MAX_PERMITTED_DATA_LENGTH);
+    }
+
+    #[test]
+    fn test_realloc_with_borrow() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[1; 8]);
+
+        // a shared borrow is held
+        {
+            let data = account.try_borrow_data().unwrap();
+            assert_eq!(
+                account.realloc(16, true),
+                Err(ProgramError::AccountBorrowFailed)
+            );
+            assert_eq!(data.len(), 8);
+        }
+
+        // a mutable borrow is held
+        {
+            let _data = account.try_borrow_mut_data().unwrap();
+            assert_eq!(
+                account.realloc(4, false),
+                Err(ProgramError::AccountBorrowFailed)
+            );
+        }
+        assert_eq!(account.data_len(), 8);
+
+        // the borrows were released
+        assert_eq!(account.realloc(16, true), Ok(()));
+        assert_eq!(account.data_len(), 16);
+        assert_eq!(
+            *account.try_borrow_data().unwrap(),
+            [[1; 8], [0; 8]].concat()
+        );
+
+        // the borrow taken by `realloc` is released as well
+        assert!(account.try_borrow_mut_data().is_ok());
+    }
+
+    #[test]
+    fn test_realloc_rent_exempt() {
+        use crate::{mock, sysvars::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR};
+
+        mock::reset();
+
+        let rent = Rent {
+            lamports_per_byte_year: DEFAULT_LAMPORTS_PER_BYTE_YEAR,
+            exemption_threshold: 2.0,
+            burn_percent: 50,
+        };
+
+        let mut buffers = [[0u64; 32]; 2];
+        let [account_buffer, payer_buffer] = buffers.each_mut();
+        let account = account_info(account_buffer, &[1; 8]);
+        let payer = account_info(payer_buffer, &[]);
+        unsafe {
+            (*account.raw).key = [1; 32];
+            (*account.raw).lamports = rent.minimum_balance(8);
+            (*payer.raw).key = [2; 32];
+            (*payer.raw).lamports = 1_000_000;
+        }
+
+        // Growing requires a top-up for the 8 new bytes.
+        let top_up = rent.minimum_balance(16) - rent.minimum_balance(8);
+        assert_eq!(account.realloc_rent_exempt(16, &payer, &rent, &[]), Ok(()));
+        assert_eq!(account.data_len(), 16);
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 1);
+        let instruction = &invocations[0].instruction;
+        assert_eq!(instruction.program_id, SYSTEM_PROGRAM_ID);
+        assert_eq!(instruction.accounts[0].0, [2; 32]);
+        assert_eq!(instruction.accounts[1].0, [1; 32]);
+        assert_eq!(instruction.data[..4], [2, 0, 0, 0]);
+        assert_eq!(instruction.data[4..], top_up.to_le_bytes());
+
+        // The account already holds enough lamports.
+        unsafe { (*account.raw).lamports = rent.minimum_balance(64) };
+        assert_eq!(account.realloc_rent_exempt(32, &payer, &rent, &[]), Ok(()));
+        assert_eq!(account.data_len(), 32);
+        assert!(mock::take_invocations().is_empty());
+
+        // The payer cannot cover the top-up.
+        unsafe { (*payer.raw).lamports = 1 };
+        assert_eq!(
+            account.realloc_rent_exempt(128, &payer, &rent, &[]),
+            Err(ProgramError::InsufficientFunds)
+        );
+        assert_eq!(account.data_len(), 32);
+        assert!(mock::take_invocations().is_empty());
+    }
+
+    #[test]
+    fn test_borrow_data_unchecked() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[1, 2, 3, 4]);
+
+        // a single mutable reference at a time, released before reading
+        {
+            let data = unsafe { account.borrow_mut_data_unchecked() };
+            for byte in data.iter_mut() {
+                *byte *= 2;
+            }
+        }
+
+        let data = unsafe { account.borrow_data_unchecked() };
+        assert_eq!(data, [2, 4, 6, 8]);
+        // shared borrows can coexist with the unchecked one
+        assert_eq!(*account.try_borrow_data().unwrap(), [2, 4, 6, 8]);
+
+        // the borrow state is left untouched, so a mutable borrow succeeds once
+        // the unchecked reference is no longer used
+        assert!(account.try_borrow_mut_data().is_ok());
+    }
+
+    #[test]
+    fn test_data_starts_with() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[1, 2, 3, 4]);
+
+        // matching prefixes
+        assert_eq!(account.data_starts_with(&[1, 2]), Ok(true));
+        assert_eq!(account.data_starts_with(&[1, 2, 3, 4]), Ok(true));
+        assert_eq!(account.data_starts_with(&[]), Ok(true));
+
+        // non-matching prefixes
+        assert_eq!(account.data_starts_with(&[2]), Ok(false));
+        assert_eq!(account.data_starts_with(&[1, 2, 3, 5]), Ok(false));
+
+        // a prefix longer than the data
+        assert_eq!(account.data_starts_with(&[1, 2, 3, 4, 5]), Ok(false));
+
+        // the data must not be mutably borrowed
+        let _data = account.try_borrow_mut_data().unwrap();
+        assert_eq!(
+            account.data_starts_with(&[1]),
+            Err(ProgramError::AccountBorrowFailed)
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate() {
+        use crate::mock::{serialized_input, SerializedAccount};
+
+        let account = |index| SerializedAccount {
+            key: [index; 32],
+            lamports: 1,
+            ..SerializedAccount::default()
+        };
+        // the fourth account is a duplicate of the second one
+        let (_buffer, accounts) = serialized_input([
+            account(0),
+            account(1),
+            account(2),
+            SerializedAccount {
+                duplicate_of: Some(1),
+                ..SerializedAccount::default()
+            },
+        ]);
+
+        assert!(accounts[3].is_duplicate_of(&accounts[1]));
+        assert!(accounts[1].is_duplicate_of(&accounts[3]));
+        assert!(!accounts[0].is_duplicate_of(&accounts[1]));
+        assert_eq!(AccountInfo::find_duplicate(&accounts), Some((1, 3)));
+        assert_eq!(AccountInfo::find_duplicate(&accounts[..3]), None);
+        assert_eq!(AccountInfo::find_duplicate(&[]), None);
+
+        // Accounts with the same key but different data are not duplicates.
+        let mut buffers = [[0u64; 16]; 2];
+        let [first, second] = buffers.each_mut();
+        let accounts = [account_info(first, &[]), account_info(second, &[])];
+        assert_eq!(accounts[0].key(), accounts[1].key());
+        assert_eq!(AccountInfo::find_duplicate(&accounts), None);
+    }
+
+    #[test]
+    fn test_executable_and_rent_epoch() {
+        use crate::mock::{serialized_input, SerializedAccount};
+
+        let (_buffer, [program, account]) = serialized_input([
+            SerializedAccount {
+                executable: true,
+                key: [0; 32],
+                lamports: 1,
+                data: &[1, 2, 3],
+                rent_epoch: u64::MAX,
+                ..SerializedAccount::default()
+            },
+            SerializedAccount {
+                key: [1; 32],
+                lamports: 1,
+                rent_epoch: 42,
+                ..SerializedAccount::default()
+            },
+        ]);
+
+        assert!(program.executable());
+        assert_eq!(program.rent_epoch(), u64::MAX);
+
+        assert!(!account.executable());
+        assert_eq!(account.rent_epoch(), 42);
+
+        // The rent epoch does not move when the account is resized.
+        program.realloc(1, false).unwrap();
+        assert_eq!(program.rent_epoch(), u64::MAX);
+    }
+
+    #[test]
+    fn test_rent_epoch_after_data_len_change() {
+        use crate::mock::{serialized_input, SerializedAccount};
+
+        let (_buffer, [closed, resized, next]) = serialized_input([
+            SerializedAccount {
+                key: [0; 32],
+                lamports: 1,
+                data: &[1; 24],
+                rent_epoch: 7,
+                ..SerializedAccount::default()
+            },
+            SerializedAccount {
+                key: [1; 32],
+                lamports: 1,
+                data: &[2; 16],
+                rent_epoch: 42,
+                ..SerializedAccount::default()
+            },
+            SerializedAccount {
+                key: [2; 32],
+                lamports: 1,
+                rent_epoch: 99,
+                ..SerializedAccount::default()
+            },
+        ]);
+
+        closed.close().unwrap();
+        assert_eq!(closed.data_len(), 0);
+        assert_eq!(closed.lamports(), 0);
+        assert_eq!(closed.rent_epoch(), 7);
+
+        // The runtime updates the data length after a CPI, without a realloc.
+        unsafe { (*resized.raw).data_len = 0 };
+        assert_eq!(resized.rent_epoch(), 42);
+        unsafe { (*resized.raw).data_len = 16 + 8 };
+        assert_eq!(resized.rent_epoch(), 42);
+        // The original length still limits the data increase.
+        assert_eq!(resized.max_realloc_len(), 16 + MAX_PERMITTED_DATA_INCREASE);
+
+        assert_eq!(next.rent_epoch(), 99);
+    }
+
+    #[test]
+    fn test_split_first_n() {
+        let mut buffers = [[0u64; 16]; 3];
+        let [first, second, third] = buffers.each_mut();
+        let accounts = [
+            account_info(first, &[1]),
+            account_info(second, &[2]),
+            account_info(third, &[3]),
+        ];
+
+        // Exact length.
+        let ([a, b, c], remaining) = AccountInfo::split_first_n::<3>(&accounts).unwrap();
+        assert_eq!(unsafe { a.borrow_data_unchecked() }, &[1]);
+        assert_eq!(unsafe { b.borrow_data_unchecked() }, &[2]);
+        assert_eq!(unsafe { c.borrow_data_unchecked() }, &[3]);
+        assert!(remaining.is_empty());
+
+        // Over-length.
+        let ([a], remaining) = AccountInfo::split_first_n::<1>(&accounts).unwrap();
+        assert_eq!(unsafe { a.borrow_data_unchecked() }, &[1]);
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(unsafe { remaining[0].borrow_data_unchecked() }, &[2]);
+
+        // Under-length.
+        assert_eq!(
+            AccountInfo::split_first_n::<4>(&accounts).err(),
+            Some(ProgramError::NotEnoughAccountKeys)
+        );
+    }
+
+    #[test]
+    fn test_split_data() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[1, 0, 0, 0, 0, 0, 0, 0, 2, 3]);
+
+        let (header, tail) = account.split_data::<u64>().unwrap();
+        assert_eq!(*header, 1);
+        assert_eq!(&*tail, &[2, 3]);
+
+        // Each reference holds its own borrow.
+        assert!(account.check_borrow_mut_data().is_err());
+        drop(header);
+        assert!(account.check_borrow_mut_data().is_err());
+        drop(tail);
+        assert!(account.check_borrow_mut_data().is_ok());
+
+        // Empty tail.
+        let (header, tail) = account.split_data::<[u8; 10]>().unwrap();
+        assert_eq!(*header, [1, 0, 0, 0, 0, 0, 0, 0, 2, 3]);
+        assert!(tail.is_empty());
+        drop((header, tail));
+
+        // Header larger than the data.
+        assert_eq!(
+            account.split_data::<[u8; 11]>().err(),
+            Some(ProgramError::AccountDataTooSmall)
+        );
+    }
+
     #[test]
     fn test_data_ref() {
         let data: [u8; 4] = [0, 1, 2, 3];
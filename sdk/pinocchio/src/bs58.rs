@@ -0,0 +1,118 @@
+//! Base58 encoding without allocations.
+//!
+//! Encoding is expensive in compute units, so [`pubkey::log`] should be
+//! preferred to only log a key; this module is useful when the encoded string
+//! itself is needed, e.g. to compare it or to add it to a longer message:
+//!
+//! ```
+//! use pinocchio::{bs58, log::sol_log};
+//!
+//! let mut buffer = [0; bs58::MAX_PUBKEY_LEN];
+//! sol_log(bs58::encode_into(&[0; 32], &mut buffer));
+//! ```
+//!
+//! [`pubkey::log`]: crate::pubkey::log
+
+/// Length of the buffer needed to encode any 32-byte `Pubkey`.
+pub const MAX_PUBKEY_LEN: usize = 44;
+
+/// The base58 alphabet used by Solana, which is the Bitcoin alphabet.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Write `bytes` as a base58 string to the start of `out`.
+///
+/// Returns the encoded string, which borrows `out`. A 32-byte `Pubkey` takes
+/// at most [`MAX_PUBKEY_LEN`] characters; in general, `n` bytes take at most
+/// `n * 138 / 100 + 1` characters.
+///
+/// # Panics
+///
+/// Panics if `out` is too small to hold the encoded string.
+pub fn encode_into<'a>(bytes: &[u8], out: &'a mut [u8]) -> &'a str {
+    // Each leading zero byte is encoded as a leading `1`.
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+
+    // The digits of the remaining bytes are computed in `out`, least
+    // significant first.
+    let mut len = 0;
+
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as u32;
+
+        for digit in out[..len].iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            out[len] = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+    }
+
+    let encoded = &mut out[..len + zeros];
+    encoded[len..].fill(0);
+    encoded.reverse();
+
+    for digit in encoded.iter_mut() {
+        *digit = ALPHABET[*digit as usize];
+    }
+
+    // SAFETY: Only characters of the base58 alphabet were written.
+    unsafe { core::str::from_utf8_unchecked(encoded) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysvars::ids::{CLOCK_ID, INSTRUCTIONS_ID, RENT_ID};
+
+    #[test]
+    fn test_encode_pubkeys() {
+        let mut buffer = [0; MAX_PUBKEY_LEN];
+
+        assert_eq!(
+            encode_into(&[0; 32], &mut buffer),
+            "11111111111111111111111111111111"
+        );
+        assert_eq!(
+            encode_into(&CLOCK_ID, &mut buffer),
+            "SysvarC1ock11111111111111111111111111111111"
+        );
+        assert_eq!(
+            encode_into(&RENT_ID, &mut buffer),
+            "SysvarRent111111111111111111111111111111111"
+        );
+        assert_eq!(
+            encode_into(&INSTRUCTIONS_ID, &mut buffer),
+            "Sysvar1nstructions1111111111111111111111111"
+        );
+
+        // the longest encoding of a pubkey
+        assert_eq!(
+            encode_into(&[u8::MAX; 32], &mut buffer),
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG"
+        );
+    }
+
+    #[test]
+    fn test_encode_bytes() {
+        let mut buffer = [0; 8];
+
+        assert_eq!(encode_into(&[], &mut buffer), "");
+        assert_eq!(encode_into(&[0], &mut buffer), "1");
+        assert_eq!(encode_into(&[57], &mut buffer), "z");
+        assert_eq!(encode_into(&[58], &mut buffer), "21");
+        assert_eq!(encode_into(&[0, 0, 1], &mut buffer), "112");
+        assert_eq!(encode_into(b"hello", &mut buffer), "Cn8eVZg");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_encode_buffer_too_small() {
+        let mut buffer = [0; 43];
+        encode_into(&[u8::MAX; 32], &mut buffer);
+    }
+}
@@ -0,0 +1,83 @@
+//! Bounds-checked writers of little-endian integers into account data.
+
+use crate::program_error::ProgramError;
+
+macro_rules! impl_write_le {
+    ( $( $name:ident: $t:ty ),* ) => {
+        $(
+            #[doc = concat!("Write `value` as a little-endian `", stringify!($t), "` at `offset` of `data`.")]
+            ///
+            /// # Errors
+            ///
+            /// Returns [`ProgramError::AccountDataTooSmall`] if the value does not
+            /// fit in `data` at `offset`.
+            #[inline(always)]
+            pub fn $name(data: &mut [u8], offset: usize, value: $t) -> Result<(), ProgramError> {
+                offset
+                    .checked_add(core::mem::size_of::<$t>())
+                    .and_then(|end| data.get_mut(offset..end))
+                    .ok_or(ProgramError::AccountDataTooSmall)?
+                    .copy_from_slice(&value.to_le_bytes());
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl_write_le!(
+    write_u16_le: u16,
+    write_u32_le: u32,
+    write_u64_le: u64,
+    write_i16_le: i16,
+    write_i32_le: i32,
+    write_i64_le: i64
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_le() {
+        let mut data = [0u8; 10];
+
+        assert_eq!(write_u64_le(&mut data, 2, 0x0102_0304_0506_0708), Ok(()));
+        assert_eq!(data, [0, 0, 8, 7, 6, 5, 4, 3, 2, 1]);
+
+        assert_eq!(write_i64_le(&mut data, 0, -2), Ok(()));
+        assert_eq!(data[..8], [0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+
+        assert_eq!(write_u32_le(&mut data, 6, u32::MAX), Ok(()));
+        assert_eq!(write_i32_le(&mut data, 6, i32::MIN), Ok(()));
+        assert_eq!(data[6..], [0, 0, 0, 0x80]);
+
+        assert_eq!(write_u16_le(&mut data, 8, 0x0102), Ok(()));
+        assert_eq!(write_i16_le(&mut data, 0, -1), Ok(()));
+        assert_eq!(data, [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 2, 1]);
+    }
+
+    #[test]
+    fn test_write_le_out_of_range() {
+        let mut data = [0u8; 10];
+
+        assert_eq!(
+            write_u64_le(&mut data, 3, 1),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+        assert_eq!(
+            write_i32_le(&mut data, 7, 1),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+        assert_eq!(
+            write_u16_le(&mut data, 10, 1),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+        assert_eq!(
+            write_i64_le(&mut data, usize::MAX, 1),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+
+        // Failed writes leave the data unchanged.
+        assert_eq!(data, [0; 10]);
+    }
+}
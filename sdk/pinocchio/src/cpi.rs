@@ -1,4 +1,14 @@
 //! Cross-program invocation helpers.
+//!
+//! Invocations are made with the `sol_invoke_signed_c` syscall, which takes
+//! the instruction and the accounts using the C ABI. The [`Account`]s received
+//! by the entrypoint already have the layout expected by the syscall, so the
+//! accounts are passed without any conversion.
+//!
+//! The `sol_invoke_signed_rust` syscall is not used: it expects the account
+//! infos of `solana-program`, which hold their lamports and data behind
+//! `Rc<RefCell<_>>`. Building them for each invocation would cost more compute
+//! units and code size than the C ABI, rather than less.
 
 use core::{mem::MaybeUninit, ops::Deref};
 
@@ -6,13 +16,24 @@ use crate::{
     account_info::AccountInfo,
     instruction::{Account, AccountMeta, Instruction, Signer},
     program_error::ProgramError,
-    pubkey::Pubkey,
+    pubkey::{pubkey_eq, Pubkey},
+    sysvars::instructions::Instructions,
     ProgramResult,
 };
 
+#[cfg(not(target_os = "solana"))]
+use crate::mock as syscalls;
+#[cfg(target_os = "solana")]
+use crate::syscalls;
+
 /// Maximum number of accounts that can be passed to a cross-program invocation.
 pub const MAX_CPI_ACCOUNTS: usize = 64;
 
+/// Stack height of the instructions of a transaction.
+///
+/// Each cross-program invocation increases the stack height by one.
+pub const TRANSACTION_LEVEL_STACK_HEIGHT: usize = 1;
+
 /// An `Instruction` as expected by `sol_invoke_signed_c`.
 ///
 /// DO NOT EXPOSE THIS STRUCT:
@@ -22,21 +43,21 @@ pub const MAX_CPI_ACCOUNTS: usize = 64;
 /// discarded immediately after.
 #[repr(C)]
 #[derive(Debug, PartialEq, Clone)]
-struct CInstruction<'a> {
+pub(crate) struct CInstruction<'a> {
     /// Public key of the program.
-    program_id: *const Pubkey,
+    pub(crate) program_id: *const Pubkey,
 
     /// Accounts expected by the program instruction.
-    accounts: *const AccountMeta<'a>,
+    pub(crate) accounts: *const AccountMeta<'a>,
 
     /// Number of accounts expected by the program instruction.
-    accounts_len: u64,
+    pub(crate) accounts_len: u64,
 
     /// Data expected by the program instruction.
-    data: *const u8,
+    pub(crate) data: *const u8,
 
     /// Length of the data expected by the program instruction.
-    data_len: u64,
+    pub(crate) data_len: u64,
 }
 
 impl<'a> From<&Instruction<'a, '_, '_, '_>> for CInstruction<'a> {
@@ -65,6 +86,47 @@ pub fn invoke<const ACCOUNTS: usize>(
     invoke_signed(instruction, account_infos, &[])
 }
 
+/// Invoke a cross-program instruction, checking the privileges of the
+/// accounts first.
+///
+/// A signer or writable `AccountMeta` of the `instruction` must correspond to
+/// an `AccountInfo` that is respectively a signer or writable; otherwise the
+/// runtime fails the invocation with an error that does not name the account.
+/// This function reports the problem with a specific error instead, which is
+/// useful while developing a program. The checks cost compute units, so
+/// [`invoke`] is cheaper once the accounts are known to be correct.
+///
+/// Since no PDA signs the invocation, signer accounts must have signed the
+/// transaction.
+///
+/// # Important
+///
+/// The accounts on the `account_infos` slice must be in the same order as the
+/// `accounts` field of the `instruction`.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::MissingRequiredSignature`] if a signer meta maps to
+/// an account that is not a signer, or [`ProgramError::Immutable`] if a
+/// writable meta maps to an account that is not writable. Otherwise, returns
+/// the errors of [`invoke`].
+pub fn invoke_checked<const ACCOUNTS: usize>(
+    instruction: &Instruction,
+    account_infos: &[&AccountInfo; ACCOUNTS],
+) -> ProgramResult {
+    for (account_info, account_meta) in account_infos.iter().zip(instruction.accounts.iter()) {
+        if account_meta.is_signer && !account_info.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if account_meta.is_writable && !account_info.is_writable() {
+            return Err(ProgramError::Immutable);
+        }
+    }
+
+    invoke(instruction, account_infos)
+}
+
 /// Invoke a cross-program instruction from a slice of `AccountInfo`s.
 ///
 /// # Important
@@ -82,11 +144,21 @@ pub fn slice_invoke(instruction: &Instruction, account_infos: &[&AccountInfo]) -
 ///
 /// The accounts on the `account_infos` slice must be in the same order as the
 /// `accounts` field of the `instruction`.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if `ACCOUNTS` is greater than
+/// [`MAX_CPI_ACCOUNTS`]. Since `ACCOUNTS` is known at compile time, the check
+/// is resolved by the compiler.
 pub fn invoke_signed<const ACCOUNTS: usize>(
     instruction: &Instruction,
     account_infos: &[&AccountInfo; ACCOUNTS],
     signers_seeds: &[Signer],
 ) -> ProgramResult {
+    if ACCOUNTS > MAX_CPI_ACCOUNTS {
+        return Err(ProgramError::InvalidArgument);
+    }
+
     if instruction.accounts.len() < ACCOUNTS {
         return Err(ProgramError::NotEnoughAccountKeys);
     }
@@ -182,6 +254,112 @@ pub fn slice_invoke_signed(
     Ok(())
 }
 
+/// A fixed set of accounts translated once for multiple cross-program
+/// invocations.
+///
+/// The [`Account`]s expected by `sol_invoke_signed_c` are built when the
+/// `CpiAccounts` is created and reused by every invocation, instead of being
+/// built again for each one. All the accounts are passed to each invocation,
+/// and the runtime selects the ones of the instruction by their keys, so the
+/// order of the accounts does not need to match the instruction.
+///
+/// The length of the data of each account is part of the translation. If an
+/// account was resized, e.g. with [`AccountInfo::realloc`], the accounts are
+/// translated again before the next invocation; [`CpiAccounts::generation`]
+/// counts the translations.
+pub struct CpiAccounts<'a, const N: usize> {
+    /// The accounts that can be passed to the invocations.
+    account_infos: [&'a AccountInfo; N],
+
+    /// The translated accounts.
+    accounts: [Account<'a>; N],
+
+    /// Number of times the accounts were translated.
+    generation: u64,
+}
+
+impl<'a, const N: usize> CpiAccounts<'a, N> {
+    /// Creates a new `CpiAccounts`, translating the accounts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if `N` is greater than
+    /// [`MAX_CPI_ACCOUNTS`].
+    #[inline]
+    pub fn new(account_infos: [&'a AccountInfo; N]) -> Result<Self, ProgramError> {
+        if N > MAX_CPI_ACCOUNTS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Ok(Self {
+            account_infos,
+            accounts: account_infos.map(Account::from),
+            generation: 1,
+        })
+    }
+
+    /// Returns the number of times the accounts were translated, starting at
+    /// `1` for the translation made by [`CpiAccounts::new`].
+    #[inline(always)]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Invoke a cross-program instruction.
+    ///
+    /// See [`CpiAccounts::invoke_signed`].
+    #[inline(always)]
+    pub fn invoke(&mut self, instruction: &Instruction) -> ProgramResult {
+        self.invoke_signed(instruction, &[])
+    }
+
+    /// Invoke a cross-program instruction with signatures.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::NotEnoughAccountKeys`] if an account of the
+    /// instruction is not one of the accounts, or
+    /// [`ProgramError::AccountBorrowFailed`] if an account cannot be borrowed
+    /// as required by the instruction.
+    pub fn invoke_signed(
+        &mut self,
+        instruction: &Instruction,
+        signers_seeds: &[Signer],
+    ) -> ProgramResult {
+        for account_meta in instruction.accounts {
+            let account_info = self
+                .account_infos
+                .iter()
+                .find(|account_info| pubkey_eq(account_info.key(), account_meta.pubkey))
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+            if account_meta.is_writable {
+                account_info.check_borrow_mut_data()?;
+                account_info.check_borrow_mut_lamports()?;
+            } else {
+                account_info.check_borrow_data()?;
+                account_info.check_borrow_lamports()?;
+            }
+        }
+
+        let stale = self
+            .account_infos
+            .iter()
+            .zip(self.accounts.iter())
+            .any(|(account_info, account)| account_info.data_len() as u64 != account.data_len());
+
+        if stale {
+            self.accounts = self.account_infos.map(Account::from);
+            self.generation += 1;
+        }
+
+        // SAFETY: The accounts of the instruction have been validated.
+        unsafe { invoke_signed_unchecked(instruction, &self.accounts, signers_seeds) };
+
+        Ok(())
+    }
+}
+
 /// Invoke a cross-program instruction but don't enforce Rust's aliasing rules.
 ///
 /// This function does not check that [`Account`]s are properly borrowable.
@@ -215,22 +393,16 @@ pub unsafe fn invoke_signed_unchecked(
     accounts: &[Account],
     signers_seeds: &[Signer],
 ) {
-    #[cfg(target_os = "solana")]
-    {
-        let instruction = CInstruction::from(instruction);
-        unsafe {
-            crate::syscalls::sol_invoke_signed_c(
-                &instruction as *const _ as *const u8,
-                accounts as *const _ as *const u8,
-                accounts.len() as u64,
-                signers_seeds as *const _ as *const u8,
-                signers_seeds.len() as u64,
-            )
-        };
-    }
-
-    #[cfg(not(target_os = "solana"))]
-    core::hint::black_box((instruction, accounts, signers_seeds));
+    let instruction = CInstruction::from(instruction);
+    unsafe {
+        syscalls::sol_invoke_signed_c(
+            &instruction as *const _ as *const u8,
+            accounts as *const _ as *const u8,
+            accounts.len() as u64,
+            signers_seeds as *const _ as *const u8,
+            signers_seeds.len() as u64,
+        )
+    };
 }
 
 /// Maximum size that can be set using [`set_return_data`].
@@ -244,13 +416,7 @@ pub const MAX_RETURN_DATA: usize = 1024;
 /// The maximum size of return data is [`MAX_RETURN_DATA`]. Return data is
 /// retrieved by the caller with [`get_return_data`].
 pub fn set_return_data(data: &[u8]) {
-    #[cfg(target_os = "solana")]
-    unsafe {
-        crate::syscalls::sol_set_return_data(data.as_ptr(), data.len() as u64)
-    };
-
-    #[cfg(not(target_os = "solana"))]
-    core::hint::black_box(data);
+    unsafe { syscalls::sol_set_return_data(data.as_ptr(), data.len() as u64) };
 }
 
 /// Get the return data from an invoked program.
@@ -283,33 +449,27 @@ pub fn set_return_data(data: &[u8]) {
 ///
 /// [rdp]: https://docs.solanalabs.com/proposals/return-data
 pub fn get_return_data() -> Option<ReturnData> {
-    #[cfg(target_os = "solana")]
-    {
-        const UNINIT_BYTE: core::mem::MaybeUninit<u8> = core::mem::MaybeUninit::<u8>::uninit();
-        let mut data = [UNINIT_BYTE; MAX_RETURN_DATA];
-        let mut program_id = Pubkey::default();
-
-        let size = unsafe {
-            crate::syscalls::sol_get_return_data(
-                data.as_mut_ptr() as *mut u8,
-                data.len() as u64,
-                &mut program_id,
-            )
-        };
+    const UNINIT_BYTE: core::mem::MaybeUninit<u8> = core::mem::MaybeUninit::<u8>::uninit();
+    let mut data = [UNINIT_BYTE; MAX_RETURN_DATA];
+    let mut program_id = Pubkey::default();
+
+    let size = unsafe {
+        syscalls::sol_get_return_data(
+            data.as_mut_ptr() as *mut u8,
+            data.len() as u64,
+            &mut program_id,
+        )
+    };
 
-        if size == 0 {
-            None
-        } else {
-            Some(ReturnData {
-                program_id,
-                data,
-                size: core::cmp::min(size as usize, MAX_RETURN_DATA),
-            })
-        }
+    if size == 0 {
+        None
+    } else {
+        Some(ReturnData {
+            program_id,
+            data,
+            size: core::cmp::min(size as usize, MAX_RETURN_DATA),
+        })
     }
-
-    #[cfg(not(target_os = "solana"))]
-    core::hint::black_box(None)
 }
 
 /// Struct to hold the return data from an invoked program.
@@ -343,3 +503,283 @@ impl Deref for ReturnData {
         self.as_slice()
     }
 }
+
+/// Get the current stack height.
+///
+/// The stack height is [`TRANSACTION_LEVEL_STACK_HEIGHT`] for the instructions
+/// of a transaction and is increased by one for each nested cross-program
+/// invocation.
+#[inline(always)]
+pub fn get_stack_height() -> usize {
+    unsafe { syscalls::sol_get_stack_height() as usize }
+}
+
+/// Check that the program `program_id` is not re-entered through a
+/// cross-program invocation.
+///
+/// When the program runs in a cross-program invocation, the transaction
+/// instruction at the bottom of the call stack is read from the instructions
+/// sysvar and must not target the program. This detects a program invoking
+/// itself, directly or through other programs. The intermediate invocations are
+/// not visible to programs, so a program that is first invoked through a
+/// cross-program invocation and then re-entered further down the stack is not
+/// detected.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::UnsupportedSysvar`] if `instructions_sysvar` is not
+/// the instructions sysvar account, [`ProgramError::InvalidArgument`] if its
+/// data is malformed and [`ProgramError::InvalidInstructionData`] if the
+/// program is re-entered.
+#[inline]
+pub fn assert_no_reentrancy(
+    program_id: &Pubkey,
+    instructions_sysvar: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if get_stack_height() <= TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+
+    let instructions = Instructions::try_from(instructions_sysvar)?;
+    let instruction =
+        instructions.load_instruction_at(instructions.load_current_index() as usize)?;
+
+    if pubkey_eq(instruction.try_get_program_id()?, program_id) {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account_info::tests::account_info;
+
+    #[test]
+    fn test_max_cpi_accounts() {
+        let mut buffer = [0u64; 16];
+        let account = account_info(&mut buffer, &[]);
+
+        let account_metas: [AccountMeta; MAX_CPI_ACCOUNTS + 1] =
+            core::array::from_fn(|_| AccountMeta::readonly(account.key()));
+        let instruction = |accounts| Instruction {
+            program_id: &[1; 32],
+            accounts,
+            data: &[],
+        };
+
+        assert_eq!(
+            invoke(
+                &instruction(&account_metas[..MAX_CPI_ACCOUNTS]),
+                &[&account; MAX_CPI_ACCOUNTS]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            slice_invoke(
+                &instruction(&account_metas[..MAX_CPI_ACCOUNTS]),
+                &[&account; MAX_CPI_ACCOUNTS]
+            ),
+            Ok(())
+        );
+
+        assert_eq!(
+            invoke(
+                &instruction(&account_metas),
+                &[&account; MAX_CPI_ACCOUNTS + 1]
+            ),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            slice_invoke(
+                &instruction(&account_metas),
+                &[&account; MAX_CPI_ACCOUNTS + 1]
+            ),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_invoke_checked() {
+        use crate::{
+            account_info::tests::{set_flags, set_key},
+            mock,
+        };
+
+        let mut buffers = [[0u64; 16]; 2];
+        let [payer, vault] = buffers.each_mut();
+        let payer = account_info(payer, &[]);
+        let vault = account_info(vault, &[]);
+        set_key(&payer, [1; 32]);
+        set_key(&vault, [2; 32]);
+        set_flags(&payer, true, true);
+
+        mock::reset();
+
+        let instruction = |accounts| Instruction {
+            program_id: &[3; 32],
+            accounts,
+            data: &[],
+        };
+
+        // satisfiable privileges
+        let account_metas = [
+            AccountMeta::writable_signer(&[1; 32]),
+            AccountMeta::readonly(&[2; 32]),
+        ];
+        assert_eq!(
+            invoke_checked(&instruction(&account_metas), &[&payer, &vault]),
+            Ok(())
+        );
+        assert_eq!(mock::take_invocations().len(), 1);
+
+        // the vault is not a signer
+        let account_metas = [
+            AccountMeta::writable_signer(&[1; 32]),
+            AccountMeta::readonly_signer(&[2; 32]),
+        ];
+        assert_eq!(
+            invoke_checked(&instruction(&account_metas), &[&payer, &vault]),
+            Err(ProgramError::MissingRequiredSignature)
+        );
+
+        // the vault is not writable
+        let account_metas = [
+            AccountMeta::readonly(&[1; 32]),
+            AccountMeta::writable(&[2; 32]),
+        ];
+        assert_eq!(
+            invoke_checked(&instruction(&account_metas), &[&payer, &vault]),
+            Err(ProgramError::Immutable)
+        );
+
+        assert!(mock::invocations().is_empty());
+        mock::reset();
+    }
+
+    #[test]
+    fn test_cpi_accounts() {
+        use crate::{account_info::tests::set_key, mock};
+
+        let mut buffers = [[0u64; 16]; 3];
+        let [payer, vault, program] = buffers.each_mut();
+        let payer = account_info(payer, &[]);
+        let vault = account_info(vault, &[1; 8]);
+        let program = account_info(program, &[]);
+        set_key(&payer, [1; 32]);
+        set_key(&vault, [2; 32]);
+        set_key(&program, [3; 32]);
+
+        mock::reset();
+
+        let mut accounts = CpiAccounts::new([&payer, &vault, &program]).unwrap();
+        assert_eq!(accounts.generation(), 1);
+
+        let account_metas = [
+            AccountMeta::writable(&[2; 32]),
+            AccountMeta::readonly(&[1; 32]),
+        ];
+        let instruction = Instruction {
+            program_id: &[3; 32],
+            accounts: &account_metas,
+            data: &[],
+        };
+
+        // the accounts are reused across invocations
+        assert_eq!(accounts.invoke(&instruction), Ok(()));
+        assert_eq!(accounts.invoke(&instruction), Ok(()));
+        assert_eq!(accounts.generation(), 1);
+
+        // the vault is resized, so the accounts are translated again
+        vault.realloc(4, false).unwrap();
+        assert_eq!(accounts.invoke(&instruction), Ok(()));
+        assert_eq!(accounts.generation(), 2);
+        assert_eq!(accounts.invoke(&instruction), Ok(()));
+        assert_eq!(accounts.generation(), 2);
+
+        let invocations = mock::take_invocations();
+        assert_eq!(invocations.len(), 4);
+        for (invocation, vault_len) in invocations.iter().zip([8, 8, 4, 4]) {
+            assert_eq!(
+                invocation.accounts,
+                [([1; 32], 0), ([2; 32], vault_len), ([3; 32], 0)]
+            );
+        }
+
+        // an account that is not part of the set
+        let missing = [AccountMeta::readonly(&[4; 32])];
+        assert_eq!(
+            accounts.invoke(&Instruction {
+                program_id: &[3; 32],
+                accounts: &missing,
+                data: &[],
+            }),
+            Err(ProgramError::NotEnoughAccountKeys)
+        );
+
+        // a writable account that is borrowed
+        {
+            let _data = vault.try_borrow_data().unwrap();
+            assert_eq!(
+                accounts.invoke(&instruction),
+                Err(ProgramError::AccountBorrowFailed)
+            );
+        }
+
+        assert!(mock::invocations().is_empty());
+        mock::reset();
+    }
+
+    #[test]
+    fn test_assert_no_reentrancy() {
+        extern crate std;
+
+        use crate::{
+            account_info::tests::set_key,
+            mock,
+            sysvars::instructions::{pack_instructions, store_current_index, INSTRUCTIONS_ID},
+        };
+
+        let program_id = [1; 32];
+        let other_program_id = [2; 32];
+        let instruction = |program_id| Instruction {
+            program_id,
+            accounts: &[],
+            data: &[],
+        };
+
+        let mut data =
+            pack_instructions(&[instruction(&other_program_id), instruction(&program_id)]);
+        let mut buffer = std::vec![0u64; 16 + data.len().div_ceil(8)];
+        let sysvar = account_info(&mut buffer, &data);
+        set_key(&sysvar, INSTRUCTIONS_ID);
+
+        mock::reset();
+
+        // transaction-level instruction, the sysvar is not read
+        let mut other_buffer = [0u64; 16];
+        let other = account_info(&mut other_buffer, &[]);
+        assert_eq!(get_stack_height(), TRANSACTION_LEVEL_STACK_HEIGHT);
+        assert_eq!(assert_no_reentrancy(&program_id, &other), Ok(()));
+
+        // single entry: invoked by the program of the current instruction
+        mock::set_stack_height(TRANSACTION_LEVEL_STACK_HEIGHT + 1);
+        assert_eq!(assert_no_reentrancy(&program_id, &sysvar), Ok(()));
+        assert_eq!(
+            assert_no_reentrancy(&program_id, &other),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+
+        // re-entrant: the current instruction targets the program
+        store_current_index(&mut data, 1).unwrap();
+        sysvar.try_borrow_mut_data().unwrap().copy_from_slice(&data);
+        assert_eq!(
+            assert_no_reentrancy(&program_id, &sysvar),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(assert_no_reentrancy(&other_program_id, &sysvar), Ok(()));
+
+        mock::reset();
+    }
+}
@@ -261,6 +261,7 @@ unsafe fn read_account(input: *mut u8, offset: &mut usize) -> MaybeAccount {
     if (*account).borrow_state == NON_DUP_MARKER {
         // repurpose the borrow state to track borrows
         (*account).borrow_state = 0b_0000_0000;
+        (*account).init_original_data_len();
 
         *offset += core::mem::size_of::<Account>();
         *offset += (*account).data_len as usize;
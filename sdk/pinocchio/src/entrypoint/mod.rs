@@ -4,6 +4,8 @@
 pub mod lazy;
 pub use lazy::{InstructionContext, MaybeAccount};
 
+pub mod panic;
+
 #[cfg(target_os = "solana")]
 pub use alloc::BumpAllocator;
 
@@ -163,6 +165,7 @@ pub unsafe fn deserialize<'a, const MAX_ACCOUNTS: usize>(
             if (*account_info).borrow_state == NON_DUP_MARKER {
                 // repurpose the borrow state to track borrows
                 (*account_info).borrow_state = 0b_0000_0000;
+                (*account_info).init_original_data_len();
 
                 offset += core::mem::size_of::<Account>();
                 offset += (*account_info).data_len as usize;
@@ -243,7 +246,8 @@ macro_rules! default_panic_handler {
 
 /// Default panic hook (no std).
 ///
-/// This macro sets up a default panic hook that logs the file where the panic occurred.
+/// This macro sets up a default panic hook that logs the file where the panic occurred. When
+/// the `panic-log` feature is enabled, the location and message of the panic are logged instead.
 ///
 /// This is used when the `"std"` feature is disabled and program is `std`.
 #[cfg(not(feature = "std"))]
@@ -254,7 +258,9 @@ macro_rules! default_panic_handler {
         #[cfg(target_os = "solana")]
         #[no_mangle]
         fn custom_panic(info: &core::panic::PanicInfo<'_>) {
-            if let Some(location) = info.location() {
+            if $crate::entrypoint::panic::LOG_PANICS {
+                $crate::entrypoint::panic::log_panic(info);
+            } else if let Some(location) = info.location() {
                 $crate::log::sol_log(location.file());
             }
             // Panic reporting.
@@ -266,7 +272,8 @@ macro_rules! default_panic_handler {
 /// A rust panic handler for `no_std`.
 ///
 /// When all crates are `no_std`, we need to define a global `#[panic_handler]`.
-/// It takes over the default rust panic handler.
+/// It takes over the default rust panic handler. When the `panic-log` feature is
+/// enabled, the location and message of the panic are logged before aborting.
 ///
 /// This macro is used when the `"std"` feature is disabled.
 #[cfg(not(feature = "std"))]
@@ -278,6 +285,10 @@ macro_rules! nostd_panic_handler {
         #[no_mangle]
         #[panic_handler]
         fn handler(info: &core::panic::PanicInfo<'_>) -> ! {
+            if $crate::entrypoint::panic::LOG_PANICS {
+                $crate::entrypoint::panic::log_panic(info);
+            }
+
             if let Some(location) = info.location() {
                 unsafe {
                     $crate::syscalls::sol_panic_(
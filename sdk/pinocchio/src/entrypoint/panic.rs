@@ -0,0 +1,131 @@
+//! Logging of panics without allocations.
+//!
+//! On SBF, a panic aborts the program with a generic failure. With the
+//! `panic-log` feature enabled, the panic handlers set up by the entrypoint
+//! macros log the location and the message of the panic before aborting, so
+//! failures can be traced back to their source.
+
+use core::{
+    fmt::{self, Write},
+    panic::{Location, PanicInfo},
+};
+
+use crate::log::sol_log;
+
+/// Indicates whether the panic handlers log the location and message of
+/// panics, which is the case when the `panic-log` feature is enabled.
+pub const LOG_PANICS: bool = cfg!(feature = "panic-log");
+
+/// Maximum length of a formatted panic message.
+///
+/// Longer messages are truncated.
+pub const MAX_PANIC_MESSAGE_LEN: usize = 256;
+
+/// Log the location and message of a panic.
+///
+/// The message is formatted into a stack buffer, so this does not allocate.
+#[inline(never)]
+pub fn log_panic(info: &PanicInfo<'_>) {
+    let mut buffer = [0; MAX_PANIC_MESSAGE_LEN];
+    sol_log(format_panic(&mut buffer, info.location(), info.message()));
+}
+
+/// Format a panic as `panicked at <file>:<line>:<column>: <message>` into
+/// `buffer`.
+///
+/// The message is truncated, on a character boundary, if it does not fit in
+/// the buffer. Returns the formatted message, which borrows `buffer`.
+pub fn format_panic<'b>(
+    buffer: &'b mut [u8; MAX_PANIC_MESSAGE_LEN],
+    location: Option<&Location<'_>>,
+    message: impl fmt::Display,
+) -> &'b str {
+    let mut writer = Writer { buffer, len: 0 };
+
+    // The writer truncates instead of failing, so the results can be ignored.
+    let _ = match location {
+        Some(location) => write!(
+            writer,
+            "panicked at {}:{}:{}: {}",
+            location.file(),
+            location.line(),
+            location.column(),
+            message
+        ),
+        None => write!(writer, "panicked: {}", message),
+    };
+
+    let len = writer.len;
+    // SAFETY: Only whole UTF-8 characters were written to the first `len`
+    // bytes.
+    unsafe { core::str::from_utf8_unchecked(&buffer[..len]) }
+}
+
+/// Writer over a fixed buffer that truncates what does not fit.
+struct Writer<'b> {
+    buffer: &'b mut [u8; MAX_PANIC_MESSAGE_LEN],
+    len: usize,
+}
+
+impl Write for Writer<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut end = core::cmp::min(s.len(), MAX_PANIC_MESSAGE_LEN - self.len);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        self.buffer[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{format, string::String};
+
+    use super::*;
+
+    #[test]
+    fn test_format_panic() {
+        let mut buffer = [0; MAX_PANIC_MESSAGE_LEN];
+
+        let location = Location::caller();
+        let expected = format!(
+            "panicked at {}:{}:{}: balance too low: 42",
+            file!(),
+            location.line(),
+            location.column()
+        );
+        assert_eq!(
+            format_panic(
+                &mut buffer,
+                Some(location),
+                format_args!("balance too low: {}", 42)
+            ),
+            expected
+        );
+
+        assert_eq!(
+            format_panic(&mut buffer, None, "no location"),
+            "panicked: no location"
+        );
+    }
+
+    #[test]
+    fn test_format_panic_truncated() {
+        let mut buffer = [0; MAX_PANIC_MESSAGE_LEN];
+
+        let message: String = "a".repeat(MAX_PANIC_MESSAGE_LEN);
+        let formatted = format_panic(&mut buffer, None, &message);
+        assert_eq!(formatted.len(), MAX_PANIC_MESSAGE_LEN);
+        assert!(formatted.starts_with("panicked: aaa"));
+
+        // multi-byte characters are not split
+        let message = format!("a{}", "é".repeat(MAX_PANIC_MESSAGE_LEN));
+        let formatted = format_panic(&mut buffer, None, &message);
+        assert_eq!(formatted.len(), MAX_PANIC_MESSAGE_LEN - 1);
+        assert!(formatted.ends_with('é'));
+    }
+}
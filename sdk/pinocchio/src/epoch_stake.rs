@@ -0,0 +1,60 @@
+//! Access to the stake delegated to vote accounts in the current epoch.
+
+#[cfg(not(target_os = "solana"))]
+use crate::mock as syscalls;
+use crate::pubkey::Pubkey;
+#[cfg(target_os = "solana")]
+use crate::syscalls;
+
+/// Get the current epoch's total stake.
+///
+/// The total stake is the sum of the activated stake of all vote accounts
+/// in the current epoch.
+#[inline]
+pub fn get_total_epoch_stake() -> u64 {
+    // Passing a null vote address returns the total epoch stake.
+    get_epoch_stake_for_vote_account(core::ptr::null())
+}
+
+/// Get the current epoch's activated stake for a vote account.
+///
+/// Returns `0` if the vote account does not exist or has no stake
+/// delegated to it.
+#[inline]
+pub fn get_epoch_stake(vote_account: &Pubkey) -> u64 {
+    get_epoch_stake_for_vote_account(vote_account as *const _ as *const u8)
+}
+
+#[inline(always)]
+fn get_epoch_stake_for_vote_account(vote_address: *const u8) -> u64 {
+    // SAFETY: `vote_address` is either null or points to a `Pubkey`.
+    unsafe { syscalls::sol_get_epoch_stake(vote_address) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    #[test]
+    fn test_epoch_stake() {
+        mock::reset();
+        assert_eq!(get_epoch_stake(&[1; 32]), 0);
+        assert_eq!(get_total_epoch_stake(), 0);
+
+        mock::set_epoch_stake(&[1; 32], 100);
+        mock::set_epoch_stake(&[2; 32], 250);
+        mock::set_total_epoch_stake(1_000);
+
+        // the vote address is forwarded to the syscall
+        assert_eq!(get_epoch_stake(&[1; 32]), 100);
+        assert_eq!(get_epoch_stake(&[2; 32]), 250);
+        assert_eq!(get_epoch_stake(&[3; 32]), 0);
+
+        // a null vote address returns the total stake
+        assert_eq!(get_total_epoch_stake(), 1_000);
+
+        mock::set_epoch_stake(&[1; 32], 50);
+        assert_eq!(get_epoch_stake(&[1; 32]), 50);
+    }
+}
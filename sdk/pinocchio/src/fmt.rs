@@ -0,0 +1,89 @@
+//! Formatting of numbers without allocations.
+//!
+//! Numbers are written as decimal strings into a stack buffer, so they can be
+//! logged without the `format!` machinery:
+//!
+//! ```
+//! use pinocchio::{fmt, log::sol_log};
+//!
+//! let mut buffer = [0; fmt::MAX_LEN];
+//! sol_log(fmt::u64_to_str(&mut buffer, 1_000_000));
+//! ```
+
+/// Length of the buffer needed to format any `u64` or `i64`.
+///
+/// Both `u64::MAX` and `i64::MIN` take 20 characters.
+pub const MAX_LEN: usize = 20;
+
+/// Write `value` as a decimal string to the end of `buffer`.
+///
+/// Returns the formatted string, which borrows `buffer`.
+#[inline]
+pub fn u64_to_str(buffer: &mut [u8; MAX_LEN], value: u64) -> &str {
+    let start = write_digits(buffer, value);
+    // SAFETY: Only ASCII digits were written from `start`.
+    unsafe { core::str::from_utf8_unchecked(&buffer[start..]) }
+}
+
+/// Write `value` as a decimal string to the end of `buffer`, preceded by a
+/// `-` sign when negative.
+///
+/// Returns the formatted string, which borrows `buffer`.
+#[inline]
+pub fn i64_to_str(buffer: &mut [u8; MAX_LEN], value: i64) -> &str {
+    let mut start = write_digits(buffer, value.unsigned_abs());
+
+    if value < 0 {
+        start -= 1;
+        buffer[start] = b'-';
+    }
+
+    // SAFETY: Only ASCII digits and the sign were written from `start`.
+    unsafe { core::str::from_utf8_unchecked(&buffer[start..]) }
+}
+
+/// Write the digits of `value` to the end of `buffer`, returning the index of
+/// the first digit.
+#[inline(always)]
+fn write_digits(buffer: &mut [u8; MAX_LEN], mut value: u64) -> usize {
+    let mut start = buffer.len();
+
+    loop {
+        start -= 1;
+        buffer[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+
+        if value == 0 {
+            return start;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u64_to_str() {
+        let mut buffer = [0; MAX_LEN];
+
+        assert_eq!(u64_to_str(&mut buffer, 0), "0");
+        assert_eq!(u64_to_str(&mut buffer, 7), "7");
+        assert_eq!(u64_to_str(&mut buffer, 10), "10");
+        assert_eq!(u64_to_str(&mut buffer, 1_000_000), "1000000");
+        assert_eq!(u64_to_str(&mut buffer, 4_294_967_296), "4294967296");
+        assert_eq!(u64_to_str(&mut buffer, u64::MAX), "18446744073709551615");
+    }
+
+    #[test]
+    fn test_i64_to_str() {
+        let mut buffer = [0; MAX_LEN];
+
+        assert_eq!(i64_to_str(&mut buffer, 0), "0");
+        assert_eq!(i64_to_str(&mut buffer, -1), "-1");
+        assert_eq!(i64_to_str(&mut buffer, 123_456), "123456");
+        assert_eq!(i64_to_str(&mut buffer, -123_456), "-123456");
+        assert_eq!(i64_to_str(&mut buffer, i64::MAX), "9223372036854775807");
+        assert_eq!(i64_to_str(&mut buffer, i64::MIN), "-9223372036854775808");
+    }
+}
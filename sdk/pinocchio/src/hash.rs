@@ -0,0 +1,181 @@
+//! SHA-256 hashing.
+
+/// Number of bytes in a hash.
+pub const HASH_BYTES: usize = 32;
+
+/// Return the SHA-256 hash of the concatenation of `vals`.
+#[inline]
+pub fn hashv(vals: &[&[u8]]) -> [u8; HASH_BYTES] {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = core::mem::MaybeUninit::<[u8; HASH_BYTES]>::uninit();
+        // SAFETY: `vals` is a slice of slices, which has the layout expected by
+        // the syscall, and `hash` has space for `HASH_BYTES` bytes.
+        unsafe {
+            crate::syscalls::sol_sha256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr() as *mut u8,
+            );
+            hash.assume_init()
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        let mut hasher = sha256::Sha256::new();
+        vals.iter().for_each(|val| hasher.update(val));
+        hasher.finalize()
+    }
+}
+
+/// Return the SHA-256 hash of `val`.
+#[inline(always)]
+pub fn hash(val: &[u8]) -> [u8; HASH_BYTES] {
+    hashv(&[val])
+}
+
+/// Pure Rust implementation of SHA-256 used on non-Solana targets.
+#[cfg(not(target_os = "solana"))]
+mod sha256 {
+    use super::HASH_BYTES;
+
+    const BLOCK_LEN: usize = 64;
+
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    const H: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    pub(super) struct Sha256 {
+        state: [u32; 8],
+        buffer: [u8; BLOCK_LEN],
+        buffer_len: usize,
+        total_len: u64,
+    }
+
+    impl Sha256 {
+        pub(super) fn new() -> Self {
+            Self {
+                state: H,
+                buffer: [0; BLOCK_LEN],
+                buffer_len: 0,
+                total_len: 0,
+            }
+        }
+
+        pub(super) fn update(&mut self, mut data: &[u8]) {
+            self.total_len += data.len() as u64;
+
+            while !data.is_empty() {
+                let len = (BLOCK_LEN - self.buffer_len).min(data.len());
+                self.buffer[self.buffer_len..self.buffer_len + len].copy_from_slice(&data[..len]);
+                self.buffer_len += len;
+                data = &data[len..];
+
+                if self.buffer_len == BLOCK_LEN {
+                    let block = self.buffer;
+                    self.compress(&block);
+                    self.buffer_len = 0;
+                }
+            }
+        }
+
+        pub(super) fn finalize(mut self) -> [u8; HASH_BYTES] {
+            let bit_len = self.total_len.wrapping_mul(8);
+
+            self.update(&[0x80]);
+            while self.buffer_len != BLOCK_LEN - 8 {
+                self.update(&[0]);
+            }
+            self.update(&bit_len.to_be_bytes());
+
+            let mut hash = [0u8; HASH_BYTES];
+            hash.chunks_exact_mut(4)
+                .zip(self.state)
+                .for_each(|(chunk, word)| chunk.copy_from_slice(&word.to_be_bytes()));
+            hash
+        }
+
+        fn compress(&mut self, block: &[u8; BLOCK_LEN]) {
+            let mut w = [0u32; 64];
+            for (i, chunk) in block.chunks_exact(4).enumerate() {
+                w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            }
+            for i in 16..64 {
+                let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+                let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+                w[i] = w[i - 16]
+                    .wrapping_add(s0)
+                    .wrapping_add(w[i - 7])
+                    .wrapping_add(s1);
+            }
+
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+
+            for i in 0..64 {
+                let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+                let ch = (e & f) ^ (!e & g);
+                let t1 = h
+                    .wrapping_add(s1)
+                    .wrapping_add(ch)
+                    .wrapping_add(K[i])
+                    .wrapping_add(w[i]);
+                let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+                let maj = (a & b) ^ (a & c) ^ (b & c);
+                let t2 = s0.wrapping_add(maj);
+
+                h = g;
+                g = f;
+                f = e;
+                e = d.wrapping_add(t1);
+                d = c;
+                c = b;
+                b = a;
+                a = t1.wrapping_add(t2);
+            }
+
+            for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+                *state = state.wrapping_add(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(
+            hash(b"abc"),
+            [
+                186, 120, 22, 191, 143, 1, 207, 234, 65, 65, 64, 222, 93, 174, 34, 35, 176, 3, 97,
+                163, 150, 23, 122, 156, 180, 16, 255, 97, 242, 0, 21, 173
+            ]
+        );
+
+        // spans multiple blocks and slices
+        let data = [b'a'; 100];
+        let expected = [
+            40, 22, 89, 120, 136, 228, 160, 211, 163, 107, 130, 184, 51, 22, 171, 50, 104, 14, 184,
+            240, 15, 140, 211, 185, 4, 214, 129, 36, 109, 40, 90, 14,
+        ];
+        assert_eq!(hash(&data), expected);
+        assert_eq!(hashv(&[&data[..30], &data[30..70], &data[70..]]), expected);
+    }
+}
@@ -1,8 +1,8 @@
 //! Instruction types.
 
-use core::{marker::PhantomData, ops::Deref};
+use core::{marker::PhantomData, mem::MaybeUninit, ops::Deref};
 
-use crate::{account_info::AccountInfo, pubkey::Pubkey};
+use crate::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 
 /// Information about a CPI instruction.
 #[derive(Debug, Clone)]
@@ -20,6 +20,44 @@ where
     pub accounts: &'b [AccountMeta<'a>],
 }
 
+impl<'a, 'b, 'c, 'd> Instruction<'a, 'b, 'c, 'd> {
+    /// Creates a new `Instruction`.
+    ///
+    /// The instruction borrows its program ID, account metas and data, so it can
+    /// be built on the stack without copying them. Each account meta in turn
+    /// borrows its pubkey for `'a`, which must outlive the slice of metas.
+    #[inline(always)]
+    pub const fn new(
+        program_id: &'c Pubkey,
+        accounts: &'b [AccountMeta<'a>],
+        data: &'d [u8],
+    ) -> Self {
+        Self {
+            program_id,
+            data,
+            accounts,
+        }
+    }
+
+    /// Returns the public key of the program.
+    #[inline(always)]
+    pub const fn program_id(&self) -> &'c Pubkey {
+        self.program_id
+    }
+
+    /// Returns the metadata of the accounts passed to the program.
+    #[inline(always)]
+    pub const fn accounts(&self) -> &'b [AccountMeta<'a>] {
+        self.accounts
+    }
+
+    /// Returns the data expected by the program instruction.
+    #[inline(always)]
+    pub const fn data(&self) -> &'d [u8] {
+        self.data
+    }
+}
+
 /// Use to query and convey information about the sibling instruction components
 /// when calling the `sol_get_processed_sibling_instruction` syscall.
 #[repr(C)]
@@ -73,6 +111,23 @@ pub struct Account<'a> {
     _account_info: PhantomData<&'a AccountInfo>,
 }
 
+impl Account<'_> {
+    /// Returns the key of the account.
+    #[cfg(not(target_os = "solana"))]
+    #[inline(always)]
+    pub(crate) fn key(&self) -> &Pubkey {
+        // SAFETY: The key points into the `AccountInfo` the `Account` was
+        // created from, which outlives it.
+        unsafe { &*self.key }
+    }
+
+    /// Returns the length of the account data when the `Account` was created.
+    #[inline(always)]
+    pub(crate) fn data_len(&self) -> u64 {
+        self.data_len
+    }
+}
+
 #[inline(always)]
 const fn offset<T, U>(ptr: *const T, offset: usize) -> *const U {
     unsafe { (ptr as *const u8).add(offset) as *const U }
@@ -164,6 +219,164 @@ impl<'a> From<&'a AccountInfo> for AccountMeta<'a> {
     }
 }
 
+/// A fixed-capacity list of `AccountMeta`s stored on the stack.
+///
+/// This avoids building a new array of account metas for every CPI when
+/// instructions are created in a loop: the same list can be filled, used
+/// through [`MetaList::as_slice`] and cleared for the next instruction.
+pub struct MetaList<'a, const N: usize> {
+    /// Storage for the account metas; only the first `len` are initialized.
+    metas: [MaybeUninit<AccountMeta<'a>>; N],
+
+    /// Number of account metas in the list.
+    len: usize,
+}
+
+impl<'a, const N: usize> MetaList<'a, N> {
+    /// Creates a new empty `MetaList`.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            metas: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    /// Appends an account meta to the list.
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if the list already holds `N`
+    /// account metas.
+    #[inline(always)]
+    pub fn push(&mut self, meta: AccountMeta<'a>) -> Result<(), ProgramError> {
+        let slot = self
+            .metas
+            .get_mut(self.len)
+            .ok_or(ProgramError::InvalidArgument)?;
+        slot.write(meta);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Removes all account metas from the list, keeping its capacity.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        // `AccountMeta` does not need to be dropped, so resetting the length
+        // is enough.
+        self.len = 0;
+    }
+
+    /// Returns the account metas in the list.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[AccountMeta<'a>] {
+        // SAFETY: The first `len` account metas are initialized.
+        unsafe { core::slice::from_raw_parts(self.metas.as_ptr() as *const AccountMeta, self.len) }
+    }
+
+    /// Returns the number of account metas in the list.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the list is empty.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the maximum number of account metas the list can hold.
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Default for MetaList<'_, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, const N: usize> Deref for MetaList<'a, N> {
+    type Target = [AccountMeta<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+/// Signer and writable flags of an account packed into a single byte.
+///
+/// This is the representation used by the instructions sysvar to store the
+/// flags of each account of an instruction:
+///   * bit `0`: signer
+///   * bit `1`: writable
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MetaFlags(u8);
+
+impl MetaFlags {
+    /// The bit position of the signer flag.
+    pub const SIGNER: u8 = 0b0000_0001;
+
+    /// The bit position of the writable flag.
+    pub const WRITABLE: u8 = 0b0000_0010;
+
+    /// Creates a new `MetaFlags` from the writable and signer flags.
+    #[inline(always)]
+    pub const fn new(is_writable: bool, is_signer: bool) -> Self {
+        Self(((is_writable as u8) * Self::WRITABLE) | ((is_signer as u8) * Self::SIGNER))
+    }
+
+    /// Creates a new `MetaFlags` from its byte representation.
+    ///
+    /// Bits other than the signer and writable bits are preserved but ignored.
+    #[inline(always)]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the byte representation of the flags.
+    #[inline(always)]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Indicates whether the signer flag is set.
+    #[inline(always)]
+    pub const fn signer(self) -> bool {
+        (self.0 & Self::SIGNER) != 0
+    }
+
+    /// Indicates whether the writable flag is set.
+    #[inline(always)]
+    pub const fn writable(self) -> bool {
+        (self.0 & Self::WRITABLE) != 0
+    }
+}
+
+impl From<&AccountMeta<'_>> for MetaFlags {
+    #[inline(always)]
+    fn from(meta: &AccountMeta<'_>) -> Self {
+        MetaFlags::new(meta.is_writable, meta.is_signer)
+    }
+}
+
+impl<'a> AccountMeta<'a> {
+    /// Creates a new `AccountMeta` from packed `MetaFlags`.
+    #[inline(always)]
+    pub fn with_flags(pubkey: &'a Pubkey, flags: MetaFlags) -> Self {
+        Self::new(pubkey, flags.writable(), flags.signer())
+    }
+
+    /// Returns the signer and writable flags packed into a `MetaFlags`.
+    #[inline(always)]
+    pub fn flags(&self) -> MetaFlags {
+        MetaFlags::from(self)
+    }
+}
+
 /// Represents a signer seed.
 ///
 /// This struct contains the same information as a `[u8]`, but
@@ -298,3 +511,90 @@ macro_rules! seeds {
         )*]
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_new() {
+        let program_id = [1; 32];
+        let keys = [[2; 32], [3; 32]];
+        let accounts = [
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+        ];
+
+        let instruction = Instruction::new(&program_id, &accounts, &[4, 5, 6]);
+
+        assert_eq!(instruction.program_id(), &[1; 32]);
+        assert_eq!(instruction.data(), &[4, 5, 6]);
+
+        let metas = instruction.accounts();
+        assert_eq!(metas.len(), 2);
+        assert_eq!(metas[0].pubkey, &[2; 32]);
+        assert!(metas[0].is_writable && metas[0].is_signer);
+        assert_eq!(metas[1].pubkey, &[3; 32]);
+        assert!(!metas[1].is_writable && !metas[1].is_signer);
+    }
+
+    #[test]
+    fn test_meta_list_reuse() {
+        let program_id = [1; 32];
+        let keys = [[2; 32], [3; 32], [4; 32]];
+        let mut metas = MetaList::<2>::new();
+        assert!(metas.is_empty());
+        assert_eq!(metas.capacity(), 2);
+
+        for (index, key) in keys[1..].iter().enumerate() {
+            metas.clear();
+            metas.push(AccountMeta::writable(&keys[0])).unwrap();
+            metas.push(AccountMeta::readonly_signer(key)).unwrap();
+            // the list is full
+            assert_eq!(
+                metas.push(AccountMeta::readonly(key)),
+                Err(ProgramError::InvalidArgument)
+            );
+
+            let data = [index as u8];
+            let instruction = Instruction::new(&program_id, metas.as_slice(), &data);
+
+            assert_eq!(instruction.data(), &[index as u8]);
+            let accounts = instruction.accounts();
+            assert_eq!(accounts.len(), 2);
+            assert_eq!(accounts[0].pubkey, &[2; 32]);
+            assert!(accounts[0].is_writable && !accounts[0].is_signer);
+            assert_eq!(accounts[1].pubkey, key);
+            assert!(!accounts[1].is_writable && accounts[1].is_signer);
+        }
+
+        metas.clear();
+        assert!(metas.is_empty());
+        assert!(metas.as_slice().is_empty());
+    }
+
+    #[test]
+    fn test_meta_flags() {
+        let key = Pubkey::default();
+
+        for (is_writable, is_signer, bits) in [
+            (false, false, 0b00),
+            (false, true, 0b01),
+            (true, false, 0b10),
+            (true, true, 0b11),
+        ] {
+            let flags = MetaFlags::new(is_writable, is_signer);
+
+            assert_eq!(flags.bits(), bits);
+            assert_eq!(flags.writable(), is_writable);
+            assert_eq!(flags.signer(), is_signer);
+            assert_eq!(MetaFlags::from_bits(bits), flags);
+
+            let meta = AccountMeta::with_flags(&key, flags);
+
+            assert_eq!(meta.is_writable, is_writable);
+            assert_eq!(meta.is_signer, is_signer);
+            assert_eq!(meta.flags(), flags);
+        }
+    }
+}
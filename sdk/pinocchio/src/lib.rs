@@ -220,15 +220,27 @@
 
 #![no_std]
 
-#[cfg(feature = "std")]
+#[cfg(any(test, feature = "alloc"))]
+extern crate alloc;
+
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
 pub mod account_info;
+pub mod bs58;
+pub mod bytes;
 pub mod cpi;
 pub mod entrypoint;
+pub mod epoch_stake;
+pub mod fmt;
+pub mod hash;
 pub mod instruction;
 pub mod log;
 pub mod memory;
+#[cfg(not(target_os = "solana"))]
+pub mod mock;
+pub mod pod;
+pub mod prelude;
 #[deprecated(since = "0.8.0", note = "Use the `cpi` module instead")]
 pub mod program {
     pub use crate::cpi::*;
@@ -0,0 +1,1094 @@
+//! Host implementation of the syscalls.
+//!
+//! On non-Solana targets the syscall wrappers (e.g., [`invoke`], [`Clock::get`],
+//! [`get_sysvar`] and [`get_epoch_stake`]) call the functions of this module instead of the
+//! `sol_*` syscalls. The functions have the same signatures and return values
+//! as the syscalls they replace.
+//!
+//! When either the `std` feature or `cfg(test)` is enabled, the functions are
+//! backed by a thread-local registry that can be used to set the values
+//! returned by the syscalls and to inspect the cross-program invocations made
+//! by the program. Otherwise, sysvars are not available and cross-program
+//! invocations are ignored.
+//!
+//! Program derived addresses are derived as by the runtime in both cases.
+//!
+//! The registry is thread-local, so tests running in parallel do not interfere
+//! with each other. With the registry, [`serialized_input`] also builds the
+//! `AccountInfo`s of a serialized program input, as received by the
+//! entrypoint.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use pinocchio::{
+//!     instruction::{AccountMeta, Instruction},
+//!     mock,
+//!     sysvars::{clock::Clock, Sysvar},
+//! };
+//!
+//! mock::set_clock(Clock {
+//!     slot: 10,
+//!     ..Clock::default()
+//! });
+//! assert_eq!(Clock::get().unwrap().slot, 10);
+//!
+//! let program_id = [1; 32];
+//! let instruction = Instruction {
+//!     program_id: &program_id,
+//!     accounts: &[],
+//!     data: &[1, 2, 3],
+//! };
+//! pinocchio::cpi::invoke::<0>(&instruction, &[]).unwrap();
+//!
+//! let invocations = mock::take_invocations();
+//! assert_eq!(invocations.len(), 1);
+//! assert_eq!(invocations[0].instruction.data, [1, 2, 3]);
+//! ```
+//!
+//! [`invoke`]: crate::cpi::invoke
+//! [`Clock::get`]: crate::sysvars::Sysvar::get
+//! [`get_sysvar`]: crate::sysvars::get_sysvar
+//! [`get_epoch_stake`]: crate::epoch_stake::get_epoch_stake
+
+use crate::{
+    hash::hashv,
+    program_error::MAX_SEED_LENGTH_EXCEEDED,
+    pubkey::{Pubkey, CURVE25519_EDWARDS, MAX_SEEDS, MAX_SEED_LEN, PDA_MARKER},
+    SUCCESS,
+};
+
+#[cfg(any(test, feature = "std"))]
+pub use registry::*;
+#[cfg(not(any(test, feature = "std")))]
+pub use stubs::*;
+
+/// Create a program derived address from `seeds_addr` and `program_id_addr`,
+/// writing it to `address_bytes_addr`.
+///
+/// Returns `1` if the address lies on the ed25519 curve, like the syscall.
+///
+/// # Safety
+///
+/// `seeds_addr` must point to `seeds_len` seeds, `program_id_addr` to a
+/// `Pubkey` and `address_bytes_addr` must be valid for writes of a `Pubkey`.
+pub unsafe fn sol_create_program_address(
+    seeds_addr: *const u8,
+    seeds_len: u64,
+    program_id_addr: *const u8,
+    address_bytes_addr: *const u8,
+) -> u64 {
+    let seeds = core::slice::from_raw_parts(seeds_addr as *const &[u8], seeds_len as usize);
+    let program_id = &*(program_id_addr as *const Pubkey);
+
+    match program_address(seeds, None, program_id) {
+        Ok(address) => {
+            (address_bytes_addr as *mut Pubkey).write_unaligned(address);
+            SUCCESS
+        }
+        Err(error) => error,
+    }
+}
+
+/// Find a program derived address and its bump seed from `seeds_addr` and
+/// `program_id_addr`, writing them to `address_bytes_addr` and
+/// `bump_seed_addr`.
+///
+/// Returns `1` if no bump seed derives a valid address, like the syscall.
+///
+/// # Safety
+///
+/// `seeds_addr` must point to `seeds_len` seeds, `program_id_addr` to a
+/// `Pubkey`, `address_bytes_addr` must be valid for writes of a `Pubkey` and
+/// `bump_seed_addr` for writes of a `u8`.
+pub unsafe fn sol_try_find_program_address(
+    seeds_addr: *const u8,
+    seeds_len: u64,
+    program_id_addr: *const u8,
+    address_bytes_addr: *const u8,
+    bump_seed_addr: *const u8,
+) -> u64 {
+    let seeds = core::slice::from_raw_parts(seeds_addr as *const &[u8], seeds_len as usize);
+    let program_id = &*(program_id_addr as *const Pubkey);
+
+    for bump in (0..=u8::MAX).rev() {
+        if let Ok(address) = program_address(seeds, Some(bump), program_id) {
+            (address_bytes_addr as *mut Pubkey).write_unaligned(address);
+            *(bump_seed_addr as *mut u8) = bump;
+            return SUCCESS;
+        }
+    }
+
+    1
+}
+
+/// Check whether the point at `point_addr` is a valid point of the curve
+/// `curve_id`.
+///
+/// Only the ed25519 curve is supported. Returns `0` if the point is valid
+/// and `1` otherwise, like the syscall.
+///
+/// # Safety
+///
+/// `point_addr` must point to 32 bytes.
+pub unsafe fn sol_curve_validate_point(
+    curve_id: u64,
+    point_addr: *const u8,
+    _result: *mut u8,
+) -> u64 {
+    let point = &*(point_addr as *const [u8; 32]);
+
+    if curve_id == CURVE25519_EDWARDS && edwards::is_on_curve(point) {
+        SUCCESS
+    } else {
+        1
+    }
+}
+
+/// Derive the program address of `seeds`, followed by the `bump` seed if
+/// present.
+///
+/// Returns the value of the syscall as the error.
+fn program_address(seeds: &[&[u8]], bump: Option<u8>, program_id: &Pubkey) -> Result<Pubkey, u64> {
+    let bump = bump.map(|bump| [bump]);
+    let bump = bump.as_ref().map(|bump| bump.as_slice());
+
+    let num_seeds = seeds.len() + bump.is_some() as usize;
+    if num_seeds > MAX_SEEDS || seeds.iter().any(|seed| seed.len() > MAX_SEED_LEN) {
+        return Err(MAX_SEED_LENGTH_EXCEEDED);
+    }
+
+    let mut vals: [&[u8]; MAX_SEEDS + 2] = [&[]; MAX_SEEDS + 2];
+    vals[..seeds.len()].copy_from_slice(seeds);
+    if let Some(bump) = bump {
+        vals[seeds.len()] = bump;
+    }
+    vals[num_seeds] = program_id;
+    vals[num_seeds + 1] = PDA_MARKER;
+
+    let address = hashv(&vals[..num_seeds + 2]);
+
+    if edwards::is_on_curve(&address) {
+        Err(1)
+    } else {
+        Ok(address)
+    }
+}
+
+/// Arithmetic over the ed25519 curve, used to check whether an address has a
+/// private key.
+mod edwards {
+    /// Element of the field of integers modulo `2^255 - 19`, as little-endian
+    /// `u64` limbs.
+    ///
+    /// Elements are kept reduced, so equal elements have equal limbs.
+    type Fe = [u64; 4];
+
+    /// The prime `2^255 - 19`.
+    const P: Fe = [
+        0xffff_ffff_ffff_ffed,
+        u64::MAX,
+        u64::MAX,
+        0x7fff_ffff_ffff_ffff,
+    ];
+
+    const ZERO: Fe = [0; 4];
+
+    const ONE: Fe = [1, 0, 0, 0];
+
+    /// Indicate whether `bytes` is the compressed form of a point of the
+    /// curve `-x^2 + y^2 = 1 + d x^2 y^2`.
+    ///
+    /// The sign bit is ignored and `y` is not required to be reduced, which
+    /// matches the point decompression used by the runtime.
+    pub(super) fn is_on_curve(bytes: &[u8; 32]) -> bool {
+        let mut y = [0u64; 4];
+        for (limb, chunk) in y.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+        y[3] &= 0x7fff_ffff_ffff_ffff;
+        let y = reduce(y, 0);
+
+        // d = -121665 / 121666
+        let d = mul(sub(ZERO, [121_665, 0, 0, 0]), invert([121_666, 0, 0, 0]));
+
+        // x^2 = u / v
+        let yy = mul(y, y);
+        let u = sub(yy, ONE);
+        let v = add(mul(d, yy), ONE);
+
+        if u == ZERO {
+            return true;
+        }
+        if v == ZERO {
+            return false;
+        }
+
+        // u / v has a square root if its Legendre symbol is one.
+        let exponent = shr1(sub(ZERO, ONE));
+        pow(mul(u, invert(v)), exponent) == ONE
+    }
+
+    fn add(a: Fe, b: Fe) -> Fe {
+        let mut result = [0; 4];
+        let mut carry = 0;
+        for i in 0..4 {
+            let sum = a[i] as u128 + b[i] as u128 + carry;
+            result[i] = sum as u64;
+            carry = sum >> 64;
+        }
+        reduce(result, carry as u64)
+    }
+
+    fn sub(a: Fe, b: Fe) -> Fe {
+        // `b` is reduced, so `P - b` does not underflow.
+        let mut negated = [0; 4];
+        let mut borrow = 0;
+        for i in 0..4 {
+            let (difference, underflow_1) = P[i].overflowing_sub(b[i]);
+            let (difference, underflow_2) = difference.overflowing_sub(borrow);
+            negated[i] = difference;
+            borrow = (underflow_1 || underflow_2) as u64;
+        }
+        add(a, negated)
+    }
+
+    fn mul(a: Fe, b: Fe) -> Fe {
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let value = product[i + j] as u128 + a[i] as u128 * b[j] as u128 + carry;
+                product[i + j] = value as u64;
+                carry = value >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+
+        // 2^256 = 38 (mod p)
+        let mut result = [0; 4];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let value = product[i] as u128 + product[i + 4] as u128 * 38 + carry;
+            result[i] = value as u64;
+            carry = value >> 64;
+        }
+        reduce(result, carry as u64)
+    }
+
+    fn pow(base: Fe, exponent: Fe) -> Fe {
+        let mut result = ONE;
+        for i in (0..256).rev() {
+            result = mul(result, result);
+            if (exponent[i / 64] >> (i % 64)) & 1 == 1 {
+                result = mul(result, base);
+            }
+        }
+        result
+    }
+
+    fn invert(a: Fe) -> Fe {
+        // a^(p - 2) = a^-1 (mod p)
+        pow(a, sub(ZERO, [2, 0, 0, 0]))
+    }
+
+    fn shr1(a: Fe) -> Fe {
+        let mut result = [0; 4];
+        for i in 0..4 {
+            result[i] = a[i] >> 1 | a.get(i + 1).map_or(0, |next| next << 63);
+        }
+        result
+    }
+
+    /// Reduce `value + carry * 2^256` modulo `p`.
+    fn reduce(mut value: Fe, mut carry: u64) -> Fe {
+        // 2^256 = 38 (mod p)
+        while carry != 0 {
+            let mut overflow = carry as u128 * 38;
+            for limb in value.iter_mut() {
+                let sum = *limb as u128 + overflow;
+                *limb = sum as u64;
+                overflow = sum >> 64;
+            }
+            carry = overflow as u64;
+        }
+
+        while !less_than(value, P) {
+            let mut borrow = 0;
+            for i in 0..4 {
+                let (difference, underflow_1) = value[i].overflowing_sub(P[i]);
+                let (difference, underflow_2) = difference.overflowing_sub(borrow);
+                value[i] = difference;
+                borrow = (underflow_1 || underflow_2) as u64;
+            }
+        }
+
+        value
+    }
+
+    fn less_than(a: Fe, b: Fe) -> bool {
+        for i in (0..4).rev() {
+            if a[i] != b[i] {
+                return a[i] < b[i];
+            }
+        }
+        false
+    }
+}
+
+/// Syscalls backed by the thread-local registry.
+#[cfg(any(test, feature = "std"))]
+mod registry {
+    use core::{
+        cell::RefCell,
+        mem::{size_of, MaybeUninit},
+        slice::from_raw_parts,
+    };
+    use std::{thread_local, vec::Vec};
+
+    use crate::{
+        account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE},
+        cpi::{CInstruction, TRANSACTION_LEVEL_STACK_HEIGHT},
+        entrypoint::deserialize,
+        instruction::{Account, MetaFlags, Signer},
+        program_error::UNSUPPORTED_SYSVAR,
+        pubkey::Pubkey,
+        sysvars::{
+            clock::{Clock, CLOCK_ID},
+            instructions::OwnedInstruction,
+            rent::Rent,
+            OFFSET_LENGTH_EXCEEDS_SYSVAR, SYSVAR_NOT_FOUND,
+        },
+        NON_DUP_MARKER, SUCCESS,
+    };
+
+    /// An account of the input built by [`serialized_input`].
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct SerializedAccount<'a> {
+        /// Index of the account this account is a duplicate of, if any.
+        ///
+        /// The other fields are ignored for duplicate accounts.
+        pub duplicate_of: Option<u8>,
+
+        /// Whether the account is a signer.
+        pub is_signer: bool,
+
+        /// Whether the account is writable.
+        pub is_writable: bool,
+
+        /// Whether the account is executable.
+        pub executable: bool,
+
+        /// Key of the account.
+        pub key: Pubkey,
+
+        /// Program that owns the account.
+        pub owner: Pubkey,
+
+        /// Lamports of the account.
+        pub lamports: u64,
+
+        /// Data of the account.
+        pub data: &'a [u8],
+
+        /// Rent epoch of the account.
+        pub rent_epoch: u64,
+    }
+
+    /// Return the input buffer holding `accounts`, serialized with the same
+    /// layout as the runtime input, and their `AccountInfo`s deserialized by
+    /// the entrypoint.
+    ///
+    /// The instruction data of the input is empty and the program id is zeroed.
+    /// The infos point into the buffer, so the buffer must outlive them.
+    pub fn serialized_input<const N: usize>(
+        accounts: [SerializedAccount; N],
+    ) -> (Vec<u64>, [AccountInfo; N]) {
+        let mut input = Vec::new();
+        input.extend_from_slice(&(N as u64).to_le_bytes());
+        for account in accounts {
+            if let Some(index) = account.duplicate_of {
+                input.extend_from_slice(&[index, 0, 0, 0, 0, 0, 0, 0]);
+                continue;
+            }
+
+            // borrow state, signer, writable and executable flags
+            input.extend_from_slice(&[
+                NON_DUP_MARKER,
+                account.is_signer as u8,
+                account.is_writable as u8,
+                account.executable as u8,
+            ]);
+            // original data length
+            input.extend_from_slice(&[0; 4]);
+            input.extend_from_slice(&account.key);
+            input.extend_from_slice(&account.owner);
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(8), 0);
+            input.extend_from_slice(&account.rent_epoch.to_le_bytes());
+        }
+        // instruction data length and program id
+        input.extend_from_slice(&0u64.to_le_bytes());
+        input.extend_from_slice(&[0; 32]);
+
+        let mut buffer = std::vec![0u64; input.len().div_ceil(8)];
+        // SAFETY: `buffer` has at least `input.len()` bytes.
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                input.as_ptr(),
+                buffer.as_mut_ptr() as *mut u8,
+                input.len(),
+            );
+        }
+
+        let mut infos = [const { MaybeUninit::<AccountInfo>::uninit() }; N];
+        // SAFETY: `buffer` holds a valid serialized input with `N` accounts.
+        let (_, count, _) = unsafe { deserialize::<N>(buffer.as_mut_ptr() as *mut u8, &mut infos) };
+        assert_eq!(count, N);
+
+        // SAFETY: The accounts were initialized by `deserialize`.
+        (buffer, infos.map(|info| unsafe { info.assume_init() }))
+    }
+
+    /// A cross-program invocation recorded by [`sol_invoke_signed_c`].
+    #[derive(Clone, Debug)]
+    pub struct Invocation {
+        /// The invoked instruction.
+        pub instruction: OwnedInstruction,
+
+        /// Seeds of each of the PDA signers of the invocation.
+        pub signers_seeds: Vec<Vec<Vec<u8>>>,
+
+        /// Keys and data lengths of the accounts passed to the invocation.
+        pub accounts: Vec<(Pubkey, u64)>,
+    }
+
+    /// Values returned by the syscalls.
+    #[derive(Default)]
+    struct Registry {
+        clock: Option<Clock>,
+        clock_reads: usize,
+        rent: Option<Rent>,
+        sysvars: Vec<(Pubkey, Vec<u8>)>,
+        program_id: Pubkey,
+        return_data: Option<(Pubkey, Vec<u8>)>,
+        invocations: Vec<Invocation>,
+        stack_height: Option<usize>,
+        epoch_stakes: Vec<(Pubkey, u64)>,
+        total_epoch_stake: u64,
+        logged_pubkeys: Vec<(*const u8, Pubkey)>,
+    }
+
+    thread_local! {
+        static REGISTRY: RefCell<Registry> = RefCell::new(Registry::default());
+    }
+
+    /// Set the `Clock` returned by [`sol_get_clock_sysvar`].
+    ///
+    /// The clock is also set as the data of the clock sysvar read by
+    /// [`sol_get_sysvar`].
+    pub fn set_clock(clock: Clock) {
+        // SAFETY: `Clock` is `Pod`, so it has no padding bytes.
+        let data =
+            unsafe { from_raw_parts(&clock as *const Clock as *const u8, size_of::<Clock>()) };
+        set_sysvar(&CLOCK_ID, data);
+
+        REGISTRY.with_borrow_mut(|registry| registry.clock = Some(clock));
+    }
+
+    /// Set the `Rent` returned by [`sol_get_rent_sysvar`].
+    pub fn set_rent(rent: Rent) {
+        REGISTRY.with_borrow_mut(|registry| registry.rent = Some(rent));
+    }
+
+    /// Set the data of the sysvar `sysvar_id` read by [`sol_get_sysvar`],
+    /// replacing any previous data.
+    pub fn set_sysvar(sysvar_id: &Pubkey, data: &[u8]) {
+        REGISTRY.with_borrow_mut(|registry| {
+            registry.sysvars.retain(|(id, _)| id != sysvar_id);
+            registry.sysvars.push((*sysvar_id, data.to_vec()));
+        });
+    }
+
+    /// Set the ID of the program running, which owns the return data set with
+    /// [`sol_set_return_data`].
+    pub fn set_program_id(program_id: &Pubkey) {
+        REGISTRY.with_borrow_mut(|registry| registry.program_id = *program_id);
+    }
+
+    /// Set the return data read by [`sol_get_return_data`], as if it was set
+    /// by the program `program_id`.
+    ///
+    /// This is useful to simulate the return data of an invoked program.
+    pub fn set_return_data(program_id: &Pubkey, data: &[u8]) {
+        REGISTRY.with_borrow_mut(|registry| {
+            registry.return_data = Some((*program_id, data.to_vec()));
+        });
+    }
+
+    /// Set the stack height returned by [`sol_get_stack_height`].
+    ///
+    /// The stack height defaults to [`TRANSACTION_LEVEL_STACK_HEIGHT`].
+    pub fn set_stack_height(stack_height: usize) {
+        REGISTRY.with_borrow_mut(|registry| registry.stack_height = Some(stack_height));
+    }
+
+    /// Set the stake of the vote account `vote_account` returned by
+    /// [`sol_get_epoch_stake`], replacing any previous stake.
+    pub fn set_epoch_stake(vote_account: &Pubkey, stake: u64) {
+        REGISTRY.with_borrow_mut(|registry| {
+            registry.epoch_stakes.retain(|(id, _)| id != vote_account);
+            registry.epoch_stakes.push((*vote_account, stake));
+        });
+    }
+
+    /// Set the total stake returned by [`sol_get_epoch_stake`] for a null vote
+    /// address.
+    pub fn set_total_epoch_stake(stake: u64) {
+        REGISTRY.with_borrow_mut(|registry| registry.total_epoch_stake = stake);
+    }
+
+    /// Return the return data set, together with the program that set it.
+    pub fn return_data() -> Option<(Pubkey, Vec<u8>)> {
+        REGISTRY.with_borrow(|registry| registry.return_data.clone())
+    }
+
+    /// Return the cross-program invocations recorded, in the order they were
+    /// made.
+    pub fn invocations() -> Vec<Invocation> {
+        REGISTRY.with_borrow(|registry| registry.invocations.clone())
+    }
+
+    /// Return the cross-program invocations recorded and clear them.
+    pub fn take_invocations() -> Vec<Invocation> {
+        REGISTRY.with_borrow_mut(|registry| core::mem::take(&mut registry.invocations))
+    }
+
+    /// Return the pubkeys logged with [`sol_log_pubkey`], in the order they
+    /// were logged, together with the address they were passed at.
+    pub fn logged_pubkeys() -> Vec<(*const u8, Pubkey)> {
+        REGISTRY.with_borrow(|registry| registry.logged_pubkeys.clone())
+    }
+
+    /// Return the number of times the clock was read with
+    /// [`sol_get_clock_sysvar`].
+    pub fn clock_reads() -> usize {
+        REGISTRY.with_borrow(|registry| registry.clock_reads)
+    }
+
+    /// Clear all values of the registry.
+    pub fn reset() {
+        REGISTRY.with_borrow_mut(|registry| *registry = Registry::default());
+    }
+
+    /// Write the `Clock` set with [`set_clock`] to `addr`, counting the reads
+    /// returned by [`clock_reads`].
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be valid for writes of a `Clock`.
+    pub unsafe fn sol_get_clock_sysvar(addr: *mut u8) -> u64 {
+        REGISTRY.with_borrow_mut(|registry| {
+            registry.clock_reads += 1;
+            match registry.clock {
+                Some(clock) => {
+                    (addr as *mut Clock).write_unaligned(clock);
+                    SUCCESS
+                }
+                None => UNSUPPORTED_SYSVAR,
+            }
+        })
+    }
+
+    /// Write the `Rent` set with [`set_rent`] to `addr`.
+    ///
+    /// # Safety
+    ///
+    /// `addr` must be valid for writes of a `Rent`.
+    pub unsafe fn sol_get_rent_sysvar(addr: *mut u8) -> u64 {
+        REGISTRY.with_borrow(|registry| match &registry.rent {
+            Some(rent) => {
+                (addr as *mut Rent).write_unaligned(rent.clone());
+                SUCCESS
+            }
+            None => UNSUPPORTED_SYSVAR,
+        })
+    }
+
+    /// The `Fees` sysvar is deprecated and not supported by the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_fees_sysvar(_addr: *mut u8) -> u64 {
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Copy `length` bytes of the sysvar `sysvar_id_addr` set with
+    /// [`set_sysvar`], starting at `offset`, to `result`.
+    ///
+    /// # Safety
+    ///
+    /// `sysvar_id_addr` must point to a `Pubkey` and `result` must be valid
+    /// for writes of `length` bytes.
+    pub unsafe fn sol_get_sysvar(
+        sysvar_id_addr: *const u8,
+        result: *mut u8,
+        offset: u64,
+        length: u64,
+    ) -> u64 {
+        let sysvar_id = &*(sysvar_id_addr as *const Pubkey);
+
+        REGISTRY.with_borrow(|registry| {
+            let Some((_, data)) = registry.sysvars.iter().find(|(id, _)| id == sysvar_id) else {
+                return SYSVAR_NOT_FOUND;
+            };
+
+            let range = usize::try_from(offset)
+                .ok()
+                .and_then(|start| Some(start..start.checked_add(usize::try_from(length).ok()?)?));
+
+            match range.and_then(|range| data.get(range)) {
+                Some(bytes) => {
+                    core::ptr::copy_nonoverlapping(bytes.as_ptr(), result, bytes.len());
+                    SUCCESS
+                }
+                None => OFFSET_LENGTH_EXCEEDS_SYSVAR,
+            }
+        })
+    }
+
+    /// Record the cross-program invocation, which can be inspected with
+    /// [`invocations`].
+    ///
+    /// The account infos are not used.
+    ///
+    /// # Safety
+    ///
+    /// The arguments must have the layout expected by the `sol_invoke_signed_c`
+    /// syscall.
+    pub unsafe fn sol_invoke_signed_c(
+        instruction_addr: *const u8,
+        account_infos_addr: *const u8,
+        account_infos_len: u64,
+        signers_seeds_addr: *const u8,
+        signers_seeds_len: u64,
+    ) -> u64 {
+        let instruction = &*(instruction_addr as *const CInstruction);
+        let account_infos = from_raw_parts(
+            account_infos_addr as *const Account,
+            account_infos_len as usize,
+        );
+        let accounts = from_raw_parts(instruction.accounts, instruction.accounts_len as usize);
+        let signers = from_raw_parts(
+            signers_seeds_addr as *const Signer,
+            signers_seeds_len as usize,
+        );
+
+        let invocation = Invocation {
+            instruction: OwnedInstruction {
+                program_id: *instruction.program_id,
+                accounts: accounts
+                    .iter()
+                    .map(|meta| {
+                        (
+                            *meta.pubkey,
+                            MetaFlags::new(meta.is_writable, meta.is_signer),
+                        )
+                    })
+                    .collect(),
+                data: from_raw_parts(instruction.data, instruction.data_len as usize).to_vec(),
+            },
+            signers_seeds: signers
+                .iter()
+                .map(|signer| {
+                    from_raw_parts(signer.seeds, signer.len as usize)
+                        .iter()
+                        .map(|seed| seed.to_vec())
+                        .collect()
+                })
+                .collect(),
+            accounts: account_infos
+                .iter()
+                .map(|account| (*account.key(), account.data_len()))
+                .collect(),
+        };
+
+        REGISTRY.with_borrow_mut(|registry| registry.invocations.push(invocation));
+        SUCCESS
+    }
+
+    /// Set the return data of the program set with [`set_program_id`].
+    ///
+    /// # Safety
+    ///
+    /// `data` must be valid for reads of `length` bytes.
+    pub unsafe fn sol_set_return_data(data: *const u8, length: u64) {
+        let data = from_raw_parts(data, length as usize);
+
+        REGISTRY.with_borrow_mut(|registry| {
+            registry.return_data = Some((registry.program_id, data.to_vec()));
+        });
+    }
+
+    /// Copy up to `length` bytes of the return data to `data` and the program
+    /// that set it to `program_id`, returning the length of the return data.
+    ///
+    /// # Safety
+    ///
+    /// `data` must be valid for writes of `length` bytes and `program_id` for
+    /// writes of a `Pubkey`.
+    pub unsafe fn sol_get_return_data(data: *mut u8, length: u64, program_id: *mut Pubkey) -> u64 {
+        REGISTRY.with_borrow(|registry| match &registry.return_data {
+            Some((id, return_data)) if !return_data.is_empty() => {
+                let size = core::cmp::min(length as usize, return_data.len());
+                core::ptr::copy_nonoverlapping(return_data.as_ptr(), data, size);
+                *program_id = *id;
+                return_data.len() as u64
+            }
+            _ => 0,
+        })
+    }
+
+    /// Record the pubkey at `pubkey_addr`, which can be inspected with
+    /// [`logged_pubkeys`].
+    ///
+    /// # Safety
+    ///
+    /// `pubkey_addr` must point to a `Pubkey`.
+    pub unsafe fn sol_log_pubkey(pubkey_addr: *const u8) {
+        let pubkey = *(pubkey_addr as *const Pubkey);
+
+        REGISTRY.with_borrow_mut(|registry| registry.logged_pubkeys.push((pubkey_addr, pubkey)));
+    }
+
+    /// Return the stake of the vote account at `vote_address` set with
+    /// [`set_epoch_stake`], or the total stake set with
+    /// [`set_total_epoch_stake`] if `vote_address` is null.
+    ///
+    /// Vote accounts without a stake set have no stake.
+    ///
+    /// # Safety
+    ///
+    /// `vote_address` must be null or point to a `Pubkey`.
+    pub unsafe fn sol_get_epoch_stake(vote_address: *const u8) -> u64 {
+        REGISTRY.with_borrow(|registry| {
+            if vote_address.is_null() {
+                return registry.total_epoch_stake;
+            }
+
+            let vote_account = &*(vote_address as *const Pubkey);
+            registry
+                .epoch_stakes
+                .iter()
+                .find(|(id, _)| id == vote_account)
+                .map_or(0, |(_, stake)| *stake)
+        })
+    }
+
+    /// Return the stack height set with [`set_stack_height`].
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_stack_height() -> u64 {
+        REGISTRY.with_borrow(|registry| {
+            registry
+                .stack_height
+                .unwrap_or(TRANSACTION_LEVEL_STACK_HEIGHT) as u64
+        })
+    }
+}
+
+/// Syscalls used when the registry is not available.
+#[cfg(not(any(test, feature = "std")))]
+mod stubs {
+    use core::hint::black_box;
+
+    use crate::{
+        cpi::TRANSACTION_LEVEL_STACK_HEIGHT, program_error::UNSUPPORTED_SYSVAR, pubkey::Pubkey,
+        sysvars::SYSVAR_NOT_FOUND, SUCCESS,
+    };
+
+    /// Sysvars are not available without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_clock_sysvar(addr: *mut u8) -> u64 {
+        black_box(addr);
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Sysvars are not available without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_rent_sysvar(addr: *mut u8) -> u64 {
+        black_box(addr);
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Sysvars are not available without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_fees_sysvar(addr: *mut u8) -> u64 {
+        black_box(addr);
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Sysvars are not available without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_sysvar(
+        sysvar_id_addr: *const u8,
+        result: *mut u8,
+        offset: u64,
+        length: u64,
+    ) -> u64 {
+        black_box((sysvar_id_addr, result, offset, length));
+        SYSVAR_NOT_FOUND
+    }
+
+    /// Cross-program invocations are ignored without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_invoke_signed_c(
+        instruction_addr: *const u8,
+        account_infos_addr: *const u8,
+        account_infos_len: u64,
+        signers_seeds_addr: *const u8,
+        signers_seeds_len: u64,
+    ) -> u64 {
+        black_box((
+            instruction_addr,
+            account_infos_addr,
+            account_infos_len,
+            signers_seeds_addr,
+            signers_seeds_len,
+        ));
+        SUCCESS
+    }
+
+    /// Return data is ignored without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_set_return_data(data: *const u8, length: u64) {
+        black_box((data, length));
+    }
+
+    /// Return data is not available without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_return_data(data: *mut u8, length: u64, program_id: *mut Pubkey) -> u64 {
+        black_box((data, length, program_id));
+        0
+    }
+
+    /// Logs are ignored without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_log_pubkey(pubkey_addr: *const u8) {
+        black_box(pubkey_addr);
+    }
+
+    /// Stakes are not available without the registry.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_epoch_stake(vote_address: *const u8) -> u64 {
+        black_box(vote_address);
+        0
+    }
+
+    /// Without the registry, programs run as transaction-level instructions.
+    ///
+    /// # Safety
+    ///
+    /// This function is always safe to call; it is `unsafe` to match the
+    /// signature of the syscall.
+    pub unsafe fn sol_get_stack_height() -> u64 {
+        TRANSACTION_LEVEL_STACK_HEIGHT as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        account_info::tests::account_info,
+        cpi::{get_return_data, invoke_signed},
+        instruction::{AccountMeta, Instruction, Seed, Signer},
+        program_error::ProgramError,
+        seeds,
+        sysvars::{clock::Clock, rent::Rent, Sysvar},
+    };
+
+    #[test]
+    fn test_is_on_curve() {
+        let mut point = [0; 32];
+        // y = 0, 1 and 3 are on the curve, y = 2 is not
+        for (y, expected) in [(0, true), (1, true), (2, false), (3, true)] {
+            point[0] = y;
+            assert_eq!(edwards::is_on_curve(&point), expected, "y = {y}");
+        }
+
+        // the base point
+        let mut point = [0x66; 32];
+        point[0] = 0x58;
+        assert!(edwards::is_on_curve(&point));
+    }
+
+    #[test]
+    fn test_program_address() {
+        let program_id = [9; 32];
+        let mut address = [0; 32];
+        let mut bump = 0u8;
+
+        let seeds: [&[u8]; 2] = [b"seed", &[0]];
+        let result = unsafe {
+            sol_try_find_program_address(
+                seeds.as_ptr() as *const u8,
+                seeds.len() as u64,
+                program_id.as_ptr(),
+                address.as_mut_ptr(),
+                &mut bump as *mut u8,
+            )
+        };
+
+        // bumps 255 and 254 derive addresses on the curve
+        assert_eq!(result, SUCCESS);
+        assert_eq!(bump, 253);
+        assert_eq!(
+            address,
+            [
+                153, 121, 175, 6, 197, 99, 34, 251, 218, 100, 54, 240, 106, 175, 143, 46, 230, 170,
+                11, 124, 166, 28, 68, 207, 202, 116, 126, 146, 217, 141, 72, 63
+            ]
+        );
+
+        let seeds: [&[u8]; 3] = [b"seed", &[0], &[255]];
+        let result = unsafe {
+            sol_create_program_address(
+                seeds.as_ptr() as *const u8,
+                seeds.len() as u64,
+                program_id.as_ptr(),
+                address.as_mut_ptr(),
+            )
+        };
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    fn test_clock() {
+        reset();
+        assert_eq!(Clock::get().err(), Some(ProgramError::UnsupportedSysvar));
+
+        set_clock(Clock {
+            slot: 42,
+            epoch: 3,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        });
+
+        let clock = Clock::get().unwrap();
+        assert_eq!(clock.slot, 42);
+        assert_eq!(clock.epoch, 3);
+        assert_eq!(clock.unix_timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_rent() {
+        reset();
+        assert_eq!(Rent::get().err(), Some(ProgramError::UnsupportedSysvar));
+
+        set_rent(Rent::default());
+        assert_eq!(
+            Rent::get().unwrap().minimum_balance(0),
+            Rent::default().minimum_balance(0)
+        );
+    }
+
+    #[test]
+    fn test_invoke() {
+        reset();
+
+        let mut buffer = [0u64; 32];
+        let account = account_info(&mut buffer, &[]);
+
+        let program_id = [7; 32];
+        let instruction = Instruction {
+            program_id: &program_id,
+            accounts: &[AccountMeta::writable_signer(account.key())],
+            data: &[1, 2, 3],
+        };
+
+        let bump = [255];
+        let seeds: [Seed; 2] = seeds!(b"vault", &bump);
+        let signer = Signer::from(&seeds);
+
+        invoke_signed(&instruction, &[&account], &[signer]).unwrap();
+
+        let recorded = take_invocations();
+        assert_eq!(recorded.len(), 1);
+
+        let invocation = &recorded[0];
+        assert_eq!(invocation.instruction.program_id, program_id);
+        assert_eq!(invocation.instruction.data, [1, 2, 3]);
+        assert_eq!(invocation.instruction.accounts.len(), 1);
+
+        let (key, flags) = invocation.instruction.accounts[0];
+        assert_eq!(&key, account.key());
+        assert!(flags.writable() && flags.signer());
+
+        assert_eq!(
+            invocation.signers_seeds,
+            [[b"vault".to_vec(), bump.to_vec()]]
+        );
+
+        assert!(invocations().is_empty());
+    }
+
+    #[test]
+    fn test_return_data() {
+        reset();
+        assert!(get_return_data().is_none());
+
+        set_return_data(&[5; 32], &[9, 8, 7]);
+        let data = get_return_data().unwrap();
+        assert_eq!(data.program_id(), &[5; 32]);
+        assert_eq!(data.as_slice(), [9, 8, 7]);
+
+        set_program_id(&[6; 32]);
+        crate::cpi::set_return_data(&[1]);
+        assert_eq!(return_data(), Some(([6; 32], [1].to_vec())));
+    }
+}
@@ -0,0 +1,122 @@
+//! Marker traits for plain-old-data types and checked casts from bytes.
+
+use core::mem::{align_of, size_of};
+
+/// Marker trait for types for which an all-zeroes bit pattern is a valid value.
+///
+/// # Safety
+///
+/// Implementors must guarantee that a value with all bytes set to zero is a
+/// valid value of the type.
+pub unsafe trait Zeroable: Sized {
+    /// Return a value with all bytes set to zero.
+    #[inline(always)]
+    fn zeroed() -> Self {
+        // SAFETY: The implementor guarantees that all-zeroes is a valid value.
+        unsafe { core::mem::zeroed() }
+    }
+}
+
+/// Marker trait for types that can be safely reinterpreted from raw bytes.
+///
+/// This is used by the zero-copy readers to obtain a typed reference to
+/// account data without copying it.
+///
+/// # Safety
+///
+/// Implementors must guarantee that the type:
+///   * has a stable layout (e.g., `#[repr(C)]` or `#[repr(transparent)]`);
+///   * does not contain any padding bytes;
+///   * accepts any bit pattern as a valid value.
+pub unsafe trait Pod: Zeroable + Copy + 'static {}
+
+macro_rules! impl_pod {
+    ( $($t:ty),* ) => {
+        $(
+            unsafe impl Zeroable for $t {}
+            unsafe impl Pod for $t {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Reinterpret `bytes` as a reference to `T`.
+///
+/// Returns `None` if the length of `bytes` is not the size of `T` or if
+/// `bytes` is not suitably aligned for `T`.
+#[inline]
+pub fn cast_ref<T: Pod>(bytes: &[u8]) -> Option<&T> {
+    if bytes.len() != size_of::<T>() || bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+        return None;
+    }
+    // SAFETY: `bytes` has the size and alignment of `T`, and `T` is `Pod`.
+    Some(unsafe { &*(bytes.as_ptr() as *const T) })
+}
+
+/// Reinterpret `bytes` as a slice of `T`.
+///
+/// Returns `None` if `T` is zero-sized, if the length of `bytes` is not a
+/// multiple of the size of `T` or if `bytes` is not suitably aligned for `T`.
+#[inline]
+pub fn cast_slice<T: Pod>(bytes: &[u8]) -> Option<&[T]> {
+    let size = size_of::<T>();
+    if size == 0 {
+        return None;
+    }
+
+    let len = bytes.len() / size;
+    if len * size != bytes.len() || bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+        return None;
+    }
+    // SAFETY: `bytes` holds `len` values of `T`, is aligned for
+    // `T`, and `T` is `Pod`.
+    Some(unsafe { core::slice::from_raw_parts(bytes.as_ptr() as *const T, len) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cast_ref() {
+        let bytes = [1u8, 2, 3, 4];
+
+        assert_eq!(cast_ref::<[u8; 4]>(&bytes), Some(&[1, 2, 3, 4]));
+        assert_eq!(cast_ref::<[u8; 2]>(&bytes[1..3]), Some(&[2, 3]));
+
+        // Wrong size.
+        assert!(cast_ref::<[u8; 4]>(&bytes[..3]).is_none());
+        assert!(cast_ref::<[u8; 2]>(&bytes).is_none());
+
+        // Wrong alignment.
+        let words = [1u64, 2];
+        let bytes = unsafe { core::slice::from_raw_parts(words.as_ptr() as *const u8, 16) };
+        assert_eq!(cast_ref::<u64>(&bytes[..8]), Some(&1));
+        assert!(cast_ref::<u64>(&bytes[1..9]).is_none());
+    }
+
+    #[test]
+    fn test_cast_slice() {
+        let words = [1u32, 2, 3];
+        let bytes = unsafe { core::slice::from_raw_parts(words.as_ptr() as *const u8, 12) };
+
+        assert_eq!(cast_slice::<u32>(bytes), Some(&[1, 2, 3][..]));
+        assert_eq!(cast_slice::<u32>(&bytes[..0]), Some(&[][..]));
+
+        // Length not a multiple of the size.
+        assert!(cast_slice::<u32>(&bytes[..10]).is_none());
+        // Wrong alignment.
+        assert!(cast_slice::<u32>(&bytes[1..9]).is_none());
+        // Zero-sized type.
+        assert!(cast_slice::<[u8; 0]>(bytes).is_none());
+    }
+
+    #[test]
+    fn test_zeroed() {
+        assert_eq!(<[u64; 2]>::zeroed(), [0, 0]);
+    }
+}
@@ -0,0 +1,46 @@
+//! Common imports for programs.
+//!
+//! The prelude re-exports the account, error, CPI and sysvar types most
+//! programs need, so they can be brought into scope with a single import:
+//!
+//! ```
+//! use pinocchio::prelude::*;
+//!
+//! fn process_instruction(
+//!     _program_id: &Pubkey,
+//!     accounts: &[AccountInfo],
+//!     _instruction_data: &[u8],
+//! ) -> ProgramResult {
+//!     let [payer, instructions_sysvar, ..] = accounts else {
+//!         return Err(ProgramError::NotEnoughAccountKeys);
+//!     };
+//!
+//!     let clock = Clock::get()?;
+//!     let rent = Rent::get()?;
+//!     if !rent.is_exempt(payer.lamports(), payer.data_len()) || clock.slot == 0 {
+//!         return Err(ProgramError::InsufficientFunds);
+//!     }
+//!
+//!     let instructions = Instructions::try_from(instructions_sysvar)?;
+//!     let _current = instructions.load_current_index();
+//!
+//!     let accounts = [AccountMeta::writable_signer(payer.key())];
+//!     let instruction = Instruction::new(&[0; 32], &accounts, &[]);
+//!     invoke(&instruction, &[payer])
+//! }
+//! ```
+
+pub use crate::{
+    account_info::AccountInfo,
+    cpi::{invoke, invoke_signed},
+    instruction::{AccountMeta, Instruction, Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{
+        clock::Clock,
+        instructions::{Instructions, IntrospectedInstruction, INSTRUCTIONS_ID},
+        rent::Rent,
+        Sysvar,
+    },
+    ProgramResult,
+};
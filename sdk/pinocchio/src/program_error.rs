@@ -6,7 +6,10 @@
 //! <https://github.com/anza-xyz/solana-sdk/blob/master/program-error/src/lib.rs>
 
 /// Reasons the program may fail.
+///
+/// The enum is non-exhaustive since the runtime may define new builtin errors.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum ProgramError {
     /// Allows on-chain programs to implement program-specific error types and see them returned
     /// by the Solana runtime. A program-specific error may be any type that is represented as
@@ -89,6 +92,12 @@ pub enum ProgramError {
 
     /// Incorrect authority provided
     IncorrectAuthority,
+
+    /// Builtin error not known to this version of the crate
+    ///
+    /// Holds the raw value returned by the runtime, so the error is encoded
+    /// back to the same value.
+    Unknown(u64),
 }
 
 /// Builtin return values occupy the upper 32 bits
@@ -183,11 +192,21 @@ impl From<u64> for ProgramError {
             ARITHMETIC_OVERFLOW => Self::ArithmeticOverflow,
             IMMUTABLE => Self::Immutable,
             INCORRECT_AUTHORITY => Self::IncorrectAuthority,
-            _ => Self::Custom(error as u32),
+            _ if error >> BUILTIN_BIT_SHIFT == 0 => Self::Custom(error as u32),
+            _ => Self::Unknown(error),
         }
     }
 }
 
+/// Converting a slice of the wrong length to an array usually means that the
+/// account data is shorter than expected, so `?` can be used on `try_into`
+/// when reading account data.
+impl From<core::array::TryFromSliceError> for ProgramError {
+    fn from(_: core::array::TryFromSliceError) -> Self {
+        ProgramError::InvalidAccountData
+    }
+}
+
 impl From<ProgramError> for u64 {
     fn from(error: ProgramError) -> Self {
         match error {
@@ -222,6 +241,7 @@ impl From<ProgramError> for u64 {
             ProgramError::ArithmeticOverflow => ARITHMETIC_OVERFLOW,
             ProgramError::Immutable => IMMUTABLE,
             ProgramError::IncorrectAuthority => INCORRECT_AUTHORITY,
+            ProgramError::Unknown(error) => error,
             ProgramError::Custom(error) => {
                 if error == 0 {
                     CUSTOM_ZERO
@@ -233,6 +253,64 @@ impl From<ProgramError> for u64 {
     }
 }
 
+#[cfg(any(test, feature = "std"))]
+impl core::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Custom(error) => write!(f, "Custom program error: {error:#x}"),
+            Self::InvalidArgument => {
+                f.write_str("The arguments provided to a program instruction were invalid")
+            }
+            Self::InvalidInstructionData => f.write_str("An instruction's data contents was invalid"),
+            Self::InvalidAccountData => f.write_str("An account's data contents was invalid"),
+            Self::AccountDataTooSmall => f.write_str("An account's data was too small"),
+            Self::InsufficientFunds => {
+                f.write_str("An account's balance was too small to complete the instruction")
+            }
+            Self::IncorrectProgramId => f.write_str("The account did not have the expected program id"),
+            Self::MissingRequiredSignature => f.write_str("A signature was required but not found"),
+            Self::AccountAlreadyInitialized => f.write_str(
+                "An initialize instruction was sent to an account that has already been initialized",
+            ),
+            Self::UninitializedAccount => {
+                f.write_str("An attempt to operate on an account that hasn't been initialized")
+            }
+            Self::NotEnoughAccountKeys => f.write_str("The instruction expected additional account keys"),
+            Self::AccountBorrowFailed => {
+                f.write_str("Failed to borrow a reference to account data, already borrowed")
+            }
+            Self::MaxSeedLengthExceeded => {
+                f.write_str("Length of the seed is too long for address generation")
+            }
+            Self::InvalidSeeds => f.write_str("Provided seeds do not result in a valid address"),
+            Self::BorshIoError => f.write_str("IO Error"),
+            Self::AccountNotRentExempt => {
+                f.write_str("An account does not have enough lamports to be rent-exempt")
+            }
+            Self::UnsupportedSysvar => f.write_str("Unsupported sysvar"),
+            Self::IllegalOwner => f.write_str("Provided owner is not allowed"),
+            Self::MaxAccountsDataAllocationsExceeded => f.write_str(
+                "Accounts data allocations exceeded the maximum allowed per transaction",
+            ),
+            Self::InvalidRealloc => f.write_str("Account data reallocation was invalid"),
+            Self::MaxInstructionTraceLengthExceeded => f.write_str(
+                "Instruction trace length exceeded the maximum allowed per transaction",
+            ),
+            Self::BuiltinProgramsMustConsumeComputeUnits => {
+                f.write_str("Builtin programs must consume compute units")
+            }
+            Self::InvalidAccountOwner => f.write_str("Invalid account owner"),
+            Self::ArithmeticOverflow => f.write_str("Program arithmetic overflowed"),
+            Self::Immutable => f.write_str("Account is immutable"),
+            Self::IncorrectAuthority => f.write_str("Incorrect authority provided"),
+            Self::Unknown(error) => write!(f, "Unknown builtin error: {error:#x}"),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl std::error::Error for ProgramError {}
+
 /// A trait for converting a program error to a `&str`.
 pub trait ToStr {
     fn to_str<E>(&self) -> &'static str
@@ -280,6 +358,167 @@ impl ToStr for ProgramError {
             Self::ArithmeticOverflow => "Error: ArithmeticOverflow",
             Self::Immutable => "Error: Immutable",
             Self::IncorrectAuthority => "Error: IncorrectAuthority",
+            Self::Unknown(_) => "Error: Unknown",
+        }
+    }
+}
+
+/// Returns early from the enclosing function with `$error` if `$condition`
+/// is false.
+///
+/// Unlike `assert!`, a failed check does not panic, so the program fails
+/// with the given error instead of an opaque abort.
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::{program_error::ProgramError, require, ProgramResult};
+///
+/// fn process(amount: u64) -> ProgramResult {
+///     require!(amount > 0, ProgramError::InvalidArgument);
+///     Ok(())
+/// }
+///
+/// assert_eq!(process(0), Err(ProgramError::InvalidArgument));
+/// ```
+#[macro_export]
+macro_rules! require {
+    ( $condition:expr, $error:expr $(,)? ) => {
+        if !$condition {
+            return Err($error.into());
+        }
+    };
+}
+
+/// Returns early from the enclosing function with `$error` if `$left` is not
+/// equal to `$right`.
+///
+/// Unlike `assert_eq!`, a failed check does not panic, so the program fails
+/// with the given error instead of an opaque abort.
+#[macro_export]
+macro_rules! require_eq {
+    ( $left:expr, $right:expr, $error:expr $(,)? ) => {
+        if $left != $right {
+            return Err($error.into());
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProgramResult;
+
+    #[test]
+    fn test_display() {
+        use std::format;
+
+        assert_eq!(
+            format!("{}", ProgramError::InvalidArgument),
+            "The arguments provided to a program instruction were invalid"
+        );
+        assert_eq!(
+            format!("{}", ProgramError::Custom(42)),
+            "Custom program error: 0x2a"
+        );
+        assert_eq!(
+            format!("{}", ProgramError::Unknown(1 << 40)),
+            "Unknown builtin error: 0x10000000000"
+        );
+
+        let error: std::boxed::Box<dyn std::error::Error> = ProgramError::Immutable.into();
+        assert_eq!(format!("{error}"), "Account is immutable");
+    }
+
+    fn read_amount(data: &[u8]) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(data[..data.len().min(8)].try_into()?))
+    }
+
+    #[test]
+    fn test_from_try_from_slice_error() {
+        assert_eq!(read_amount(&42u64.to_le_bytes()), Ok(42));
+        assert_eq!(read_amount(&[1; 10]), Ok(u64::from_le_bytes([1; 8])));
+
+        // an undersized slice
+        assert_eq!(read_amount(&[1; 7]), Err(ProgramError::InvalidAccountData));
+        assert_eq!(read_amount(&[]), Err(ProgramError::InvalidAccountData));
+    }
+
+    fn check(amount: u64, decimals: u8) -> ProgramResult {
+        require!(amount > 0, ProgramError::InvalidArgument);
+        require_eq!(decimals, 6, ProgramError::Custom(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_require() {
+        assert_eq!(check(1, 6), Ok(()));
+        assert_eq!(check(0, 6), Err(ProgramError::InvalidArgument));
+        assert_eq!(check(1, 9), Err(ProgramError::Custom(1)));
+        // the first failed check returns
+        assert_eq!(check(0, 9), Err(ProgramError::InvalidArgument));
+    }
+
+    /// Builtin errors and their numeric codes, as defined by the runtime.
+    const BUILTIN_ERRORS: [(ProgramError, u64); 26] = [
+        (ProgramError::Custom(0), 1),
+        (ProgramError::InvalidArgument, 2),
+        (ProgramError::InvalidInstructionData, 3),
+        (ProgramError::InvalidAccountData, 4),
+        (ProgramError::AccountDataTooSmall, 5),
+        (ProgramError::InsufficientFunds, 6),
+        (ProgramError::IncorrectProgramId, 7),
+        (ProgramError::MissingRequiredSignature, 8),
+        (ProgramError::AccountAlreadyInitialized, 9),
+        (ProgramError::UninitializedAccount, 10),
+        (ProgramError::NotEnoughAccountKeys, 11),
+        (ProgramError::AccountBorrowFailed, 12),
+        (ProgramError::MaxSeedLengthExceeded, 13),
+        (ProgramError::InvalidSeeds, 14),
+        (ProgramError::BorshIoError, 15),
+        (ProgramError::AccountNotRentExempt, 16),
+        (ProgramError::UnsupportedSysvar, 17),
+        (ProgramError::IllegalOwner, 18),
+        (ProgramError::MaxAccountsDataAllocationsExceeded, 19),
+        (ProgramError::InvalidRealloc, 20),
+        (ProgramError::MaxInstructionTraceLengthExceeded, 21),
+        (ProgramError::BuiltinProgramsMustConsumeComputeUnits, 22),
+        (ProgramError::InvalidAccountOwner, 23),
+        (ProgramError::ArithmeticOverflow, 24),
+        (ProgramError::Immutable, 25),
+        (ProgramError::IncorrectAuthority, 26),
+    ];
+
+    #[test]
+    fn test_builtin_round_trip() {
+        for (error, code) in BUILTIN_ERRORS {
+            let value = code << BUILTIN_BIT_SHIFT;
+            assert_eq!(u64::from(error.clone()), value, "{error:?}");
+            assert_eq!(ProgramError::from(value), error);
+        }
+    }
+
+    #[test]
+    fn test_custom_round_trip() {
+        for code in [1, 2, 42, u32::MAX] {
+            let error = ProgramError::Custom(code);
+            assert_eq!(u64::from(error.clone()), code as u64);
+            assert_eq!(ProgramError::from(code as u64), error);
+        }
+
+        // `Custom(0)` would be indistinguishable from success, so the runtime
+        // encodes it as a builtin value.
+        assert_eq!(u64::from(ProgramError::Custom(0)), CUSTOM_ZERO);
+    }
+
+    #[test]
+    fn test_unknown_round_trip() {
+        // builtin codes not (yet) known to the crate, including values with
+        // bits set in both halves
+        for value in [to_builtin!(27), to_builtin!(u32::MAX), u64::MAX] {
+            let error = ProgramError::from(value);
+            assert_eq!(error, ProgramError::Unknown(value));
+            assert_eq!(u64::from(error), value);
         }
     }
 }
@@ -1,31 +1,80 @@
 //! Public key type and functions.
 
-use crate::program_error::ProgramError;
+#[cfg(not(target_os = "solana"))]
+use crate::mock as syscalls;
+#[cfg(target_os = "solana")]
+use crate::syscalls;
+use crate::{hash::hashv, program_error::ProgramError};
 
 /// Number of bytes in a pubkey.
 pub const PUBKEY_BYTES: usize = 32;
 
+/// Identifier of the ed25519 curve for the curve syscalls.
+pub(crate) const CURVE25519_EDWARDS: u64 = 0;
+
 /// maximum length of derived `Pubkey` seed.
 pub const MAX_SEED_LEN: usize = 32;
 
 /// Maximum number of seeds.
 pub const MAX_SEEDS: usize = 16;
 
+/// Marker appended to the seeds of program derived addresses.
+pub(crate) const PDA_MARKER: &[u8; 21] = b"ProgramDerivedAddress";
+
 /// The address of a [Solana account][account].
 ///
 /// [account]: https://solana.com/docs/core/accounts
 pub type Pubkey = [u8; PUBKEY_BYTES];
 
+/// Return the bytes of a `Pubkey`.
+///
+/// Since [`Pubkey`] is a type alias for a byte array, this is the identity;
+/// using it makes the intent explicit at call sites that need the raw bytes,
+/// which keeps them working if `Pubkey` becomes a distinct type.
+#[inline(always)]
+pub const fn as_array(pubkey: &Pubkey) -> &[u8; PUBKEY_BYTES] {
+    pubkey
+}
+
+/// Create a `Pubkey` from its bytes.
+///
+/// Like [`as_array`], this is the identity while [`Pubkey`] is a type alias.
+#[inline(always)]
+pub const fn from_array(bytes: [u8; PUBKEY_BYTES]) -> Pubkey {
+    bytes
+}
+
 /// Log a `Pubkey` from a program.
+///
+/// The pubkey is passed to the `sol_log_pubkey` syscall, which encodes it as
+/// base58 in the runtime. This is cheaper than encoding it in the program and
+/// does not require allocations.
+///
+/// Since [`Pubkey`] is a type alias for a byte array, this is a free function
+/// rather than a method: `pubkey::log(&key)`.
 #[inline(always)]
 pub fn log(pubkey: &Pubkey) {
-    #[cfg(target_os = "solana")]
-    unsafe {
-        crate::syscalls::sol_log_pubkey(pubkey as *const _ as *const u8)
-    };
+    // SAFETY: `pubkey` points to a `Pubkey`.
+    unsafe { syscalls::sol_log_pubkey(pubkey.as_ptr()) };
+}
+
+/// Compare two `Pubkey`s for equality.
+///
+/// The pubkeys are compared as four `u64` chunks, which takes fewer compute
+/// units than the byte-wise slice comparison of `==`.
+#[inline(always)]
+pub fn pubkey_eq(p1: &Pubkey, p2: &Pubkey) -> bool {
+    let p1 = p1.as_ptr() as *const u64;
+    let p2 = p2.as_ptr() as *const u64;
 
-    #[cfg(not(target_os = "solana"))]
-    core::hint::black_box(pubkey);
+    // SAFETY: A pubkey is 32 bytes long, so it is valid to read four
+    // (unaligned) `u64` values from it.
+    unsafe {
+        core::ptr::read_unaligned(p1) == core::ptr::read_unaligned(p2)
+            && core::ptr::read_unaligned(p1.add(1)) == core::ptr::read_unaligned(p2.add(1))
+            && core::ptr::read_unaligned(p1.add(2)) == core::ptr::read_unaligned(p2.add(2))
+            && core::ptr::read_unaligned(p1.add(3)) == core::ptr::read_unaligned(p2.add(3))
+    }
 }
 
 /// Find a valid [program derived address][pda] and its corresponding bump seed.
@@ -118,32 +167,75 @@ pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8
 /// [`find_program_address`]: #find_program_address
 #[inline]
 pub fn try_find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
-    #[cfg(target_os = "solana")]
-    {
-        let mut bytes = core::mem::MaybeUninit::<[u8; PUBKEY_BYTES]>::uninit();
-        let mut bump_seed = u8::MAX;
-
-        let result = unsafe {
-            crate::syscalls::sol_try_find_program_address(
-                seeds as *const _ as *const u8,
-                seeds.len() as u64,
-                program_id as *const _,
-                bytes.as_mut_ptr() as *mut _,
-                &mut bump_seed as *mut _,
-            )
-        };
-        match result {
-            // SAFETY: The syscall has initialized the bytes.
-            crate::SUCCESS => Some((unsafe { bytes.assume_init() }, bump_seed)),
-            _ => None,
-        }
+    let mut bytes = core::mem::MaybeUninit::<[u8; PUBKEY_BYTES]>::uninit();
+    let mut bump_seed = u8::MAX;
+
+    let result = unsafe {
+        syscalls::sol_try_find_program_address(
+            seeds as *const _ as *const u8,
+            seeds.len() as u64,
+            program_id as *const _ as *const u8,
+            bytes.as_mut_ptr() as *mut _,
+            &mut bump_seed as *mut _,
+        )
+    };
+    match result {
+        // SAFETY: The syscall has initialized the bytes.
+        crate::SUCCESS => Some((unsafe { bytes.assume_init() }, bump_seed)),
+        _ => None,
     }
+}
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        core::hint::black_box((seeds, program_id));
-        None
+/// Bump seeds indexed by their value, so that the bump seed of a signer can be
+/// borrowed for any lifetime.
+static BUMP_SEEDS: [u8; 256] = {
+    let mut seeds = [0; 256];
+    let mut i = 0;
+    while i < seeds.len() {
+        seeds[i] = i as u8;
+        i += 1;
     }
+    seeds
+};
+
+/// Find a valid [program derived address][pda] and return it together with
+/// its signer seeds and bump seed.
+///
+/// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
+///
+/// The signer seeds are `seeds` followed by the bump seed, ready to be used
+/// to sign for the address with [`invoke_signed`]. `N` is the number of signer
+/// seeds, which is one more than the number of `seeds`.
+///
+/// ```ignore
+/// let (vault, signer_seeds, _) = derive_signer::<3>(&[b"vault", owner.as_ref()], program_id)?;
+/// let signer_seeds = signer_seeds.map(Seed::from);
+/// invoke_signed(&instruction, &[vault_info], &[Signer::from(&signer_seeds)])?;
+/// ```
+///
+/// [`invoke_signed`]: crate::cpi::invoke_signed
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidSeeds`] if `N` is not one more than the
+/// number of `seeds`, or if no bump seed derives a valid address.
+#[inline]
+pub fn derive_signer<'a, const N: usize>(
+    seeds: &[&'a [u8]],
+    program_id: &Pubkey,
+) -> Result<(Pubkey, [&'a [u8]; N], u8), ProgramError> {
+    if seeds.len() + 1 != N {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (address, bump) =
+        try_find_program_address(seeds, program_id).ok_or(ProgramError::InvalidSeeds)?;
+
+    let mut signer_seeds: [&'a [u8]; N] = [&[]; N];
+    signer_seeds[..seeds.len()].copy_from_slice(seeds);
+    signer_seeds[seeds.len()] = core::slice::from_ref(&BUMP_SEEDS[bump as usize]);
+
+    Ok((address, signer_seeds, bump))
 }
 
 /// Create a valid [program derived address][pda] without searching for a bump seed.
@@ -172,30 +264,21 @@ pub fn create_program_address(
     program_id: &Pubkey,
 ) -> Result<Pubkey, ProgramError> {
     // Call via a system call to perform the calculation
-    #[cfg(target_os = "solana")]
-    {
-        let mut bytes = core::mem::MaybeUninit::<[u8; PUBKEY_BYTES]>::uninit();
-
-        let result = unsafe {
-            crate::syscalls::sol_create_program_address(
-                seeds as *const _ as *const u8,
-                seeds.len() as u64,
-                program_id as *const _ as *const u8,
-                bytes.as_mut_ptr() as *mut u8,
-            )
-        };
+    let mut bytes = core::mem::MaybeUninit::<[u8; PUBKEY_BYTES]>::uninit();
 
-        match result {
-            // SAFETY: The syscall has initialized the bytes.
-            crate::SUCCESS => Ok(unsafe { bytes.assume_init() }),
-            _ => Err(result.into()),
-        }
-    }
+    let result = unsafe {
+        syscalls::sol_create_program_address(
+            seeds as *const _ as *const u8,
+            seeds.len() as u64,
+            program_id as *const _ as *const u8,
+            bytes.as_mut_ptr() as *mut u8,
+        )
+    };
 
-    #[cfg(not(target_os = "solana"))]
-    {
-        core::hint::black_box((seeds, program_id));
-        panic!("create_program_address is only available on target `solana`")
+    match result {
+        // SAFETY: The syscall has initialized the bytes.
+        crate::SUCCESS => Ok(unsafe { bytes.assume_init() }),
+        _ => Err(result.into()),
     }
 }
 
@@ -232,3 +315,192 @@ pub fn checked_create_program_address(
 
     create_program_address(seeds, program_id)
 }
+
+/// Indicate whether a `Pubkey` is a valid ed25519 point.
+///
+/// Keys of wallets lie on the curve, while [program derived addresses][pda]
+/// never do, so this can be used to reject a PDA where a wallet is expected,
+/// or vice versa. Note that a key on the curve does not imply that its
+/// private key is known.
+///
+/// Like [`log`], this is a free function since [`Pubkey`] is a type alias.
+///
+/// [pda]: https://solana.com/docs/core/cpi#program-derived-addresses
+#[inline]
+pub fn is_on_curve(pubkey: &Pubkey) -> bool {
+    let mut result = 0u8;
+
+    // SAFETY: A pubkey is 32 bytes long. The result is not written for the
+    // ed25519 curve.
+    let status = unsafe {
+        syscalls::sol_curve_validate_point(CURVE25519_EDWARDS, pubkey.as_ptr(), &mut result)
+    };
+
+    status == 0
+}
+
+/// Create an address derived from a base public key and a seed.
+///
+/// The address is the SHA-256 hash of `base || seed || owner`. Unlike program
+/// derived addresses, seeded addresses may lie on the ed25519 curve and the
+/// `base` key must sign for them.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::MaxSeedLengthExceeded`] if `seed` is longer than
+/// [`MAX_SEED_LEN`], or [`ProgramError::IllegalOwner`] if `owner` ends with the
+/// program derived address marker.
+#[inline]
+pub fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Result<Pubkey, ProgramError> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(ProgramError::MaxSeedLengthExceeded);
+    }
+    if owner.ends_with(PDA_MARKER) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    Ok(hashv(&[base, seed.as_bytes(), owner]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpi::invoke_signed,
+        instruction::{Instruction, Seed, Signer},
+    };
+
+    #[test]
+    fn test_array_conversions() {
+        let bytes: [u8; PUBKEY_BYTES] = core::array::from_fn(|i| i as u8);
+
+        let pubkey = from_array(bytes);
+        assert_eq!(as_array(&pubkey), &bytes);
+
+        // the conversions do not copy or change the layout
+        assert!(core::ptr::eq(as_array(&pubkey), &pubkey));
+        assert_eq!(core::mem::size_of::<Pubkey>(), PUBKEY_BYTES);
+        assert_eq!(core::mem::align_of::<Pubkey>(), 1);
+
+        const KEY: Pubkey = from_array([7; PUBKEY_BYTES]);
+        assert_eq!(as_array(&KEY), &[7; PUBKEY_BYTES]);
+    }
+
+    #[test]
+    fn test_log() {
+        crate::mock::reset();
+
+        let first = [7; PUBKEY_BYTES];
+        let second = [9; PUBKEY_BYTES];
+        log(&first);
+        log(&second);
+
+        // the pubkey is passed to the syscall by reference, without a copy
+        assert_eq!(
+            crate::mock::logged_pubkeys(),
+            [(first.as_ptr(), first), (second.as_ptr(), second)]
+        );
+    }
+
+    #[test]
+    fn test_derive_signer() {
+        let owner = [3; 32];
+        let program_id = [7; 32];
+
+        let (vault, signer_seeds, bump) =
+            derive_signer::<3>(&[b"vault", &owner], &program_id).unwrap();
+
+        assert_eq!(
+            (vault, bump),
+            find_program_address(&[b"vault", &owner], &program_id)
+        );
+        assert_eq!(signer_seeds, [&b"vault"[..], &owner, &[bump]]);
+        assert_eq!(
+            create_program_address(&signer_seeds, &program_id),
+            Ok(vault)
+        );
+
+        // The signer seeds sign for the vault.
+        let seeds = signer_seeds.map(Seed::from);
+        let instruction = Instruction {
+            program_id: &program_id,
+            accounts: &[],
+            data: &[],
+        };
+        crate::mock::reset();
+        invoke_signed::<0>(&instruction, &[], &[Signer::from(&seeds)]).unwrap();
+
+        let invocations = crate::mock::take_invocations();
+        assert_eq!(
+            invocations[0].signers_seeds,
+            [[b"vault".to_vec(), owner.to_vec(), [bump].to_vec()]]
+        );
+
+        assert_eq!(
+            derive_signer::<2>(&[b"vault", &owner], &program_id),
+            Err(ProgramError::InvalidSeeds)
+        );
+    }
+
+    #[test]
+    fn test_is_on_curve() {
+        // the ed25519 base point
+        let mut base_point = [0x66; 32];
+        base_point[0] = 0x58;
+        assert!(is_on_curve(&base_point));
+
+        // y = 2 is not on the curve
+        let mut point = [0; 32];
+        point[0] = 2;
+        assert!(!is_on_curve(&point));
+
+        let (pda, _) = find_program_address(&[b"vault"], &[7; 32]);
+        assert!(!is_on_curve(&pda));
+    }
+
+    #[test]
+    fn test_pubkey_eq() {
+        let key: Pubkey = core::array::from_fn(|i| i as u8);
+        assert!(pubkey_eq(&key, &{ key }));
+
+        // a difference in any of the chunks is detected
+        for index in [0, 8, 17, 31] {
+            let mut other = key;
+            other[index] ^= 1;
+            assert!(!pubkey_eq(&key, &other));
+        }
+    }
+
+    #[test]
+    fn test_create_with_seed() {
+        let base: Pubkey = core::array::from_fn(|i| i as u8);
+        let owner = [6; 32];
+
+        assert_eq!(
+            create_with_seed(&base, "vault", &owner),
+            Ok([
+                145, 146, 125, 81, 245, 51, 89, 164, 87, 165, 33, 188, 224, 45, 34, 206, 93, 141,
+                138, 107, 246, 241, 146, 48, 200, 147, 25, 204, 98, 220, 208, 47
+            ])
+        );
+    }
+
+    #[test]
+    fn test_create_with_seed_invalid() {
+        let base = [1; 32];
+        let owner = [6; 32];
+
+        assert!(create_with_seed(&base, "seed seed seed seed seed seed se", &owner).is_ok());
+        assert_eq!(
+            create_with_seed(&base, "seed seed seed seed seed seed sed", &owner),
+            Err(ProgramError::MaxSeedLengthExceeded)
+        );
+
+        let mut pda_owner = [0; 32];
+        pda_owner[32 - PDA_MARKER.len()..].copy_from_slice(PDA_MARKER);
+        assert_eq!(
+            create_with_seed(&base, "seed", &pda_owner),
+            Err(ProgramError::IllegalOwner)
+        );
+    }
+}
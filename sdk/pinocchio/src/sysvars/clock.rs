@@ -1,7 +1,13 @@
 //! Information about the network's clock, ticks, slots, etc.
 
-use super::Sysvar;
-use crate::impl_sysvar_get;
+use super::{get_sysvar, Sysvar};
+use crate::{
+    impl_sysvar_get,
+    pod::{Pod, Zeroable},
+    program_error::{ProgramError, ToStr},
+};
+
+pub use super::ids::CLOCK_ID;
 
 /// The unit of time given to a leader for encoding a block.
 ///
@@ -62,6 +68,362 @@ pub const DEFAULT_TICKS_PER_SECOND: u64 = 160;
 // Actually calculation is supposed to be derived DEFAULT_TICKS_PER_SLOT / DEFAULT_TICKS_PER_SECOND
 pub const DEFAULT_MS_PER_SLOT: u64 = 1_000 * DEFAULT_TICKS_PER_SLOT / DEFAULT_TICKS_PER_SECOND;
 
+// SAFETY: `Clock` is `#[repr(C)]` with only 8-byte integer fields, so it has
+// no padding and any bit pattern is valid.
+unsafe impl Zeroable for Clock {}
+unsafe impl Pod for Clock {}
+
 impl Sysvar for Clock {
     impl_sysvar_get!(sol_get_clock_sysvar);
 }
+
+/// A `Clock` read from the runtime at most once.
+///
+/// Each call to [`Clock::get`] makes a syscall. A handler that needs the clock
+/// in several places can create a `ClockCache` and pass it around instead: the
+/// clock is read the first time it is needed and reused afterwards.
+///
+/// The clock is the same for all the instructions of a transaction, but the
+/// cache should still be created by the handler of each instruction and not
+/// stored beyond it, so a value is never reused across invocations of the
+/// program.
+#[derive(Clone, Default)]
+pub struct ClockCache {
+    /// The clock, once it was read.
+    clock: Option<Clock>,
+}
+
+impl ClockCache {
+    /// Creates a new `ClockCache` that has not read the clock yet.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { clock: None }
+    }
+
+    /// Return the `Clock`, reading it from the runtime on the first call.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`Clock::get`] if the clock cannot be read; the
+    /// next call then tries to read it again.
+    #[inline]
+    pub fn get(&mut self) -> Result<&Clock, ProgramError> {
+        let clock = match self.clock {
+            Some(clock) => clock,
+            None => Clock::get()?,
+        };
+
+        Ok(self.clock.insert(clock))
+    }
+}
+
+/// Return the current slot.
+///
+/// Only the `slot` field of the clock sysvar is read, which is cheaper than
+/// loading the whole `Clock` when the program only needs the slot.
+#[inline]
+pub fn current_slot() -> Result<Slot, ProgramError> {
+    let mut slot = [0u8; core::mem::size_of::<Slot>()];
+    // The slot is the first field of the clock sysvar data.
+    get_sysvar(&CLOCK_ID, 0, &mut slot)?;
+    Ok(Slot::from_le_bytes(slot))
+}
+
+/// Errors returned by the clock assertions.
+///
+/// The errors are converted to [`ProgramError::Custom`] using their
+/// discriminant as the error code.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockError {
+    /// The deadline has passed.
+    Expired,
+
+    /// The target slot has not been reached yet.
+    SlotNotReached,
+}
+
+impl From<ClockError> for ProgramError {
+    fn from(error: ClockError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
+
+impl TryFrom<u32> for ClockError {
+    type Error = ProgramError;
+
+    fn try_from(error: u32) -> Result<Self, Self::Error> {
+        match error {
+            0 => Ok(ClockError::Expired),
+            1 => Ok(ClockError::SlotNotReached),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+impl ToStr for ClockError {
+    fn to_str<E>(&self) -> &'static str
+    where
+        E: 'static + ToStr + TryFrom<u32>,
+    {
+        match self {
+            ClockError::Expired => "Error: Expired",
+            ClockError::SlotNotReached => "Error: SlotNotReached",
+        }
+    }
+}
+
+/// Asserts that the deadline `expiry_unix_ts` has not passed.
+///
+/// The deadline is inclusive: the assertion succeeds while the clock's
+/// `unix_timestamp` is less than or equal to `expiry_unix_ts`.
+///
+/// # Errors
+///
+/// Returns [`ClockError::Expired`], as `ProgramError::Custom(0)`, if the
+/// deadline has passed.
+#[inline]
+pub fn assert_not_expired(
+    clock: &Clock,
+    expiry_unix_ts: UnixTimestamp,
+) -> Result<(), ProgramError> {
+    if clock.unix_timestamp > expiry_unix_ts {
+        return Err(ClockError::Expired.into());
+    }
+    Ok(())
+}
+
+/// Asserts that the clock has reached the `target_slot`.
+///
+/// The assertion succeeds when the clock's `slot` is greater than or equal to
+/// `target_slot`.
+///
+/// # Errors
+///
+/// Returns [`ClockError::SlotNotReached`], as `ProgramError::Custom(1)`, if
+/// the target slot has not been reached yet.
+#[inline]
+pub fn assert_slot_reached(clock: &Clock, target_slot: Slot) -> Result<(), ProgramError> {
+    if clock.slot < target_slot {
+        return Err(ClockError::SlotNotReached.into());
+    }
+    Ok(())
+}
+
+/// Return the amount of a linear vesting schedule of `total` that is vested
+/// at the timestamp `now`.
+///
+/// The schedule vests linearly from `start` over `duration` seconds, but
+/// nothing is vested before the `cliff` timestamp:
+///   * before `cliff`, the vested amount is `0`;
+///   * between `cliff` and `start + duration`, it is
+///     `total * (now - start) / duration`, rounded down, which is `0` until
+///     `start` if the cliff is earlier;
+///   * from `start + duration` on, it is `total`.
+///
+/// The computation uses checked integer math, so it does not overflow for any
+/// timestamps. A `duration` that is not positive vests `total` at the cliff,
+/// regardless of `start`.
+#[inline]
+pub fn vested_amount(
+    now: UnixTimestamp,
+    start: UnixTimestamp,
+    cliff: UnixTimestamp,
+    duration: i64,
+    total: u64,
+) -> u64 {
+    if now < cliff {
+        return 0;
+    }
+
+    if duration <= 0 {
+        return total;
+    }
+
+    if now <= start {
+        return 0;
+    }
+    // `now > start`, so the difference is the elapsed time even when it does
+    // not fit in an `i64`.
+    let elapsed = now.abs_diff(start);
+
+    if elapsed >= duration as u64 {
+        return total;
+    }
+
+    // `elapsed < duration`, so the result is less than `total` and fits in a `u64`.
+    ((total as u128 * elapsed as u128) / duration as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::cast_ref;
+
+    #[test]
+    fn test_cast_ref() {
+        let data = [7u64, 0, 0, 0, 1_700_000_000];
+        let bytes = unsafe { core::slice::from_raw_parts(data.as_ptr() as *const u8, 40) };
+
+        let clock = cast_ref::<Clock>(bytes).unwrap();
+        assert_eq!(clock.slot, 7);
+        assert_eq!(clock.unix_timestamp, 1_700_000_000);
+
+        assert!(cast_ref::<Clock>(&bytes[..39]).is_none());
+    }
+
+    #[test]
+    fn test_current_slot() {
+        crate::mock::reset();
+        assert_eq!(current_slot(), Err(ProgramError::UnsupportedSysvar));
+
+        crate::mock::set_clock(Clock {
+            slot: 1_234,
+            epoch: 5,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        });
+        assert_eq!(current_slot(), Ok(Clock::get().unwrap().slot));
+        assert_eq!(current_slot(), Ok(1_234));
+    }
+
+    #[test]
+    fn test_clock_cache() {
+        crate::mock::reset();
+
+        let mut cache = ClockCache::new();
+        assert_eq!(cache.get().err(), Some(ProgramError::UnsupportedSysvar));
+        assert_eq!(crate::mock::clock_reads(), 1);
+
+        crate::mock::set_clock(Clock {
+            slot: 1_234,
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        });
+
+        // a failed read is retried, then the clock is read only once
+        assert_eq!(cache.get().unwrap().slot, 1_234);
+        assert_eq!(cache.get().unwrap().unix_timestamp, 1_700_000_000);
+        assert_eq!(crate::mock::clock_reads(), 2);
+
+        // a new cache reads the clock again
+        assert_eq!(ClockCache::new().get().unwrap().slot, 1_234);
+        assert_eq!(crate::mock::clock_reads(), 3);
+
+        crate::mock::reset();
+    }
+
+    #[test]
+    fn test_assert_not_expired() {
+        let clock = Clock {
+            unix_timestamp: 1_700_000_000,
+            ..Clock::default()
+        };
+
+        assert!(assert_not_expired(&clock, 1_700_000_001).is_ok());
+        assert!(assert_not_expired(&clock, 1_700_000_000).is_ok());
+        assert_eq!(
+            assert_not_expired(&clock, 1_699_999_999),
+            Err(ProgramError::Custom(0))
+        );
+        assert_eq!(ClockError::try_from(0), Ok(ClockError::Expired));
+    }
+
+    #[test]
+    fn test_assert_slot_reached() {
+        let clock = Clock {
+            slot: 100,
+            ..Clock::default()
+        };
+
+        assert!(assert_slot_reached(&clock, 99).is_ok());
+        assert!(assert_slot_reached(&clock, 100).is_ok());
+        assert_eq!(
+            assert_slot_reached(&clock, 101),
+            Err(ProgramError::Custom(1))
+        );
+        assert_eq!(ClockError::try_from(1), Ok(ClockError::SlotNotReached));
+        assert_ne!(
+            ProgramError::from(ClockError::Expired),
+            ProgramError::from(ClockError::SlotNotReached)
+        );
+    }
+
+    #[test]
+    fn test_vested_amount() {
+        let start = 1_700_000_000;
+        let cliff = start + 100;
+        let duration = 1_000;
+
+        // before the cliff
+        assert_eq!(
+            vested_amount(start - 1, start, cliff, duration, 1_000_000),
+            0
+        );
+        assert_eq!(vested_amount(start, start, cliff, duration, 1_000_000), 0);
+        assert_eq!(
+            vested_amount(cliff - 1, start, cliff, duration, 1_000_000),
+            0
+        );
+
+        // at the cliff, the amount vested since the start is released
+        assert_eq!(
+            vested_amount(cliff, start, cliff, duration, 1_000_000),
+            100_000
+        );
+
+        // mid-schedule
+        assert_eq!(
+            vested_amount(start + 500, start, cliff, duration, 1_000_000),
+            500_000
+        );
+        assert_eq!(
+            vested_amount(start + 999, start, cliff, duration, 1_000),
+            999
+        );
+        assert_eq!(vested_amount(start + 333, start, cliff, duration, 10), 3);
+
+        // after completion
+        assert_eq!(
+            vested_amount(start + 1_000, start, cliff, duration, 1_000_000),
+            1_000_000
+        );
+        assert_eq!(
+            vested_amount(i64::MAX, start, cliff, duration, 1_000_000),
+            1_000_000
+        );
+
+        // large amounts and timestamps do not overflow
+        assert_eq!(
+            vested_amount(0, i64::MIN / 2, i64::MIN, i64::MAX, u64::MAX),
+            1 << 63
+        );
+        assert_eq!(
+            vested_amount(i64::MAX, i64::MIN, i64::MIN, 1, u64::MAX),
+            u64::MAX
+        );
+        assert_eq!(vested_amount(i64::MIN, i64::MAX, i64::MIN, 1, u64::MAX), 0);
+
+        // a schedule without a duration vests everything at the cliff
+        assert_eq!(vested_amount(cliff - 1, start, cliff, 0, 1_000), 0);
+        assert_eq!(vested_amount(cliff, start, cliff, 0, 1_000), 1_000);
+        assert_eq!(vested_amount(cliff, start, cliff, -1, 1_000), 1_000);
+
+        // ... including when the cliff is the start
+        assert_eq!(vested_amount(start, start, start, 0, 1_000), 1_000);
+        assert_eq!(vested_amount(start - 1, start, start, 0, 1_000), 0);
+
+        // ... or earlier than the start
+        assert_eq!(
+            vested_amount(start - 10, start, start - 10, 0, 1_000),
+            1_000
+        );
+        assert_eq!(vested_amount(start - 11, start, start - 10, 0, 1_000), 0);
+
+        // with a cliff earlier than the start, vesting begins at the start
+        let cliff = start - 10;
+        assert_eq!(vested_amount(cliff, start, cliff, duration, 1_000), 0);
+        assert_eq!(vested_amount(start, start, cliff, duration, 1_000), 0);
+        assert_eq!(vested_amount(start + 1, start, cliff, duration, 1_000), 1);
+    }
+}
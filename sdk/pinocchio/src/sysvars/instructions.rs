@@ -1,17 +1,41 @@
+#[cfg(any(test, feature = "alloc"))]
+use {crate::instruction::Instruction, alloc::vec::Vec};
+
 use crate::{
     account_info::{AccountInfo, Ref},
-    instruction::AccountMeta,
+    instruction::{AccountMeta, MetaFlags},
     program_error::ProgramError,
-    pubkey::{Pubkey, PUBKEY_BYTES},
+    pubkey::{pubkey_eq, Pubkey, PUBKEY_BYTES},
 };
 
 use core::{marker::PhantomData, mem::size_of, ops::Deref};
 
-/// Sysvar1nstructions1111111111111111111111111
-pub const INSTRUCTIONS_ID: Pubkey = [
-    0x06, 0xa7, 0xd5, 0x17, 0x18, 0x7b, 0xd1, 0x66, 0x35, 0xda, 0xd4, 0x04, 0x55, 0xfd, 0xc2, 0xc0,
-    0xc1, 0x24, 0xc6, 0x8f, 0x21, 0x56, 0x75, 0xa5, 0xdb, 0xba, 0xcb, 0x5f, 0x08, 0x00, 0x00, 0x00,
-];
+pub use super::ids::INSTRUCTIONS_ID;
+
+/// Indicates whether the bounds-checked readers of an `IntrospectedInstruction`
+/// trust that the sysvar data is well-formed, which is the case when the
+/// `trust-input` feature is enabled.
+///
+/// Only instructions loaded through an [`Instructions`] are trusted, since its
+/// data is either borrowed from the instructions sysvar account or provided by
+/// the caller of the `unsafe` [`Instructions::new_unchecked`]. Their length
+/// checks are then only performed as debug assertions, so malformed data leads
+/// to undefined behavior in release builds. Instructions loaded from any slice
+/// with [`get_instruction_relative_ref`] are always validated.
+pub const TRUST_INPUT: bool = cfg!(feature = "trust-input");
+
+/// Error returned when the instructions sysvar data is malformed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SanitizeError {
+    /// An offset or length points outside of the sysvar data.
+    IndexOutOfBounds,
+}
+
+impl From<SanitizeError> for ProgramError {
+    fn from(_: SanitizeError) -> Self {
+        ProgramError::InvalidArgument
+    }
+}
 
 pub struct Instructions<T>
 where
@@ -31,6 +55,7 @@ where
     /// # Safety
     ///
     /// This function is unsafe because it does not check if the provided data is from the Sysvar Account.
+    /// When the `trust-input` feature is enabled, the data must also be well-formed (see [`TRUST_INPUT`]).
     #[inline(always)]
     pub unsafe fn new_unchecked(data: T) -> Self {
         Instructions { data }
@@ -65,6 +90,8 @@ where
 
         IntrospectedInstruction {
             raw: self.data.as_ptr().add(offset as usize),
+            len: self.data.len().saturating_sub(offset as usize),
+            trusted: true,
             marker: PhantomData,
         }
     }
@@ -87,6 +114,25 @@ where
         Ok(unsafe { self.deserialize_instruction_unchecked(index) })
     }
 
+    /// Creates and returns an `IntrospectedInstruction` for the instruction at the specified index,
+    /// validating the offset table of the sysvar data.
+    ///
+    /// Unlike [`Instructions::load_instruction_at`], the number of instructions, the entry of the
+    /// offset table and the offset it holds are checked to be within the sysvar data, and the offset
+    /// must point past the offset table.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidInstructionData`] if the index is out of bounds and
+    /// [`ProgramError::InvalidArgument`] if the data is malformed.
+    #[inline]
+    pub fn load_instruction_at_checked(
+        &self,
+        index: usize,
+    ) -> Result<IntrospectedInstruction, ProgramError> {
+        instruction_at(&self.data, index, true)
+    }
+
     /// Creates and returns an `IntrospectedInstruction` relative to the current `Instruction` in the
     /// currently executing `Transaction.
     #[inline(always)]
@@ -103,6 +149,37 @@ where
 
         self.load_instruction_at(index as usize)
     }
+
+    /// Find the first instruction of the currently executing `Transaction` that
+    /// targets the program `program_id`.
+    ///
+    /// Returns the index and the `IntrospectedInstruction` of the instruction, or
+    /// `None` if no instruction targets the program. The instruction borrows the
+    /// sysvar data, so this takes the `Instructions` created from the sysvar
+    /// account rather than the account itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if the sysvar data is malformed.
+    #[inline]
+    pub fn find_first(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<Option<(usize, IntrospectedInstruction)>, ProgramError> {
+        // SAFETY: The first 2 bytes of the Instructions sysvar data represents the
+        // number of instructions.
+        let num_instructions = unsafe { *(self.data.as_ptr() as *const u16) };
+
+        for index in 0..num_instructions as usize {
+            let instruction = self.load_instruction_at(index)?;
+
+            if pubkey_eq(instruction.try_get_program_id()?, program_id) {
+                return Ok(Some((index, instruction)));
+            }
+        }
+
+        Ok(None)
+    }
 }
 
 impl<'a> TryFrom<&'a AccountInfo> for Instructions<Ref<'a, [u8]>> {
@@ -110,7 +187,7 @@ impl<'a> TryFrom<&'a AccountInfo> for Instructions<Ref<'a, [u8]>> {
 
     #[inline(always)]
     fn try_from(account_info: &'a AccountInfo) -> Result<Self, Self::Error> {
-        if account_info.key() != &INSTRUCTIONS_ID {
+        if !account_info.key_eq(&INSTRUCTIONS_ID) {
             return Err(ProgramError::UnsupportedSysvar);
         }
 
@@ -120,22 +197,134 @@ impl<'a> TryFrom<&'a AccountInfo> for Instructions<Ref<'a, [u8]>> {
     }
 }
 
+/// Creates and returns an `IntrospectedInstruction` relative to the current
+/// `Instruction`, reading the instructions sysvar `data` borrowed by the caller.
+///
+/// Unlike [`Instructions::get_instruction_relative`], the returned instruction
+/// borrows `data` rather than an `Instructions`, so callers that hold the borrow
+/// of the sysvar account can load several instructions from the same data.
+///
+/// The instruction is returned by value rather than as a
+/// `&'a IntrospectedInstruction`: it is only a pointer into `data` and its
+/// length, which is not stored in `data` and so cannot be borrowed from it.
+/// Its lifetime is tied to `data`, and neither `data` nor the instruction is
+/// cloned.
+///
+/// `data` is not known to come from the sysvar account, so the whole record of
+/// the instruction &mdash; its account metas, program ID and data &mdash; is
+/// validated to be within `data`. This makes the unchecked getters, such as
+/// [`IntrospectedInstruction::get_program_id`], safe to use on the returned
+/// instruction, and its readers keep validating the data even when the
+/// `trust-input` feature is enabled.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidInstructionData`] if the index is out of
+/// bounds and [`ProgramError::InvalidArgument`] if the data is malformed.
+#[inline]
+pub fn get_instruction_relative_ref<'a>(
+    index_relative_to_current: i64,
+    data: &'a [u8],
+) -> Result<IntrospectedInstruction<'a>, ProgramError> {
+    let current_index = read_u16(
+        data,
+        data.len()
+            .checked_sub(size_of::<u16>())
+            .ok_or(ProgramError::InvalidArgument)?,
+    )? as i64;
+
+    let index = current_index.saturating_add(index_relative_to_current);
+
+    if index < 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let instruction = instruction_at(
+        data,
+        usize::try_from(index).map_err(|_| ProgramError::InvalidInstructionData)?,
+        false,
+    )?;
+
+    // The instruction data is the end of the record, so this validates that
+    // the account metas and the program ID are also within `data`.
+    instruction.try_get_instruction_data()?;
+
+    Ok(instruction)
+}
+
+/// Read the `u16` at `offset` of the instructions sysvar `data`.
+#[inline(always)]
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, ProgramError> {
+    data.get(offset..offset + size_of::<u16>())
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or(ProgramError::InvalidArgument)
+}
+
+/// Creates and returns an `IntrospectedInstruction` for the instruction at
+/// `index`, validating the offset table of the instructions sysvar `data`.
+///
+/// `trusted` indicates whether `data` comes from the instructions sysvar.
+#[inline]
+fn instruction_at(
+    data: &[u8],
+    index: usize,
+    trusted: bool,
+) -> Result<IntrospectedInstruction, ProgramError> {
+    let num_instructions = read_u16(data, 0)? as usize;
+
+    if index >= num_instructions {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    // The offset table follows the number of instructions.
+    let table_end = size_of::<u16>() * (1 + num_instructions);
+    let offset = read_u16(data, size_of::<u16>() * (1 + index))? as usize;
+
+    if offset < table_end || offset > data.len() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    Ok(IntrospectedInstruction {
+        // SAFETY: `offset` is within `data`.
+        raw: unsafe { data.as_ptr().add(offset) },
+        len: data.len() - offset,
+        trusted,
+        marker: PhantomData,
+    })
+}
+
 #[repr(C)]
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct IntrospectedInstruction<'a> {
     pub raw: *const u8,
+    /// Number of bytes of the sysvar data available from `raw`.
+    len: usize,
+    /// Whether the data comes from the instructions sysvar, so its length
+    /// checks can be skipped with the `trust-input` feature.
+    trusted: bool,
     pub marker: PhantomData<&'a [u8]>,
 }
 
+impl PartialEq for IntrospectedInstruction<'_> {
+    /// Instructions are equal when they point to the same bytes, whether or
+    /// not they are trusted.
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw && self.len == other.len
+    }
+}
+
+impl Eq for IntrospectedInstruction<'_> {}
+
 impl IntrospectedInstruction<'_> {
     /// Get the account meta at the specified index.
     ///
     /// # Safety
     ///
-    /// This function is unsafe because it does not verify if the index is out of bounds.
+    /// This function is unsafe because it does not verify if the index is out of bounds,
+    /// nor that the account meta is within the sysvar data.
     ///
     /// It is typically used internally within the `get_account_meta_at` function, which
-    /// performs the necessary index verification. However, to optimize performance for users
+    /// performs the necessary verification. However, to optimize performance for users
     /// who are sure that the index is in bounds, we have exposed it as an unsafe function.
     #[inline(always)]
     pub unsafe fn get_account_meta_at_unchecked(&self, index: usize) -> &IntrospectedAccountMeta {
@@ -147,23 +336,92 @@ impl IntrospectedInstruction<'_> {
     ///
     /// # Errors
     ///
-    /// Returns [`ProgramError::InvalidArgument`] if the index is out of bounds.
+    /// Returns [`ProgramError::InvalidArgument`] if the index is out of bounds or the
+    /// account meta is not within the sysvar data.
     #[inline(always)]
     pub fn get_account_meta_at(
         &self,
         index: usize,
     ) -> Result<&IntrospectedAccountMeta, ProgramError> {
-        // SAFETY: The first 2 bytes represent the number of accounts in the instruction.
-        let num_accounts = u16::from_le_bytes(unsafe { *(self.raw as *const [u8; 2]) });
+        if index >= self.read_u16(0)? as usize {
+            return Err(ProgramError::InvalidArgument);
+        }
 
-        if index >= num_accounts as usize {
+        if self.is_trusted() {
+            debug_assert!(index < self.available_account_metas());
+        } else if index >= self.available_account_metas() {
             return Err(ProgramError::InvalidArgument);
         }
 
-        // SAFETY: The index was checked to be in bounds.
+        // SAFETY: The index was checked to be in bounds and the account meta to be
+        // within the sysvar data.
         Ok(unsafe { self.get_account_meta_at_unchecked(index) })
     }
 
+    /// Get the number of accounts of the `Instruction`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanitizeError::IndexOutOfBounds`] if the number of accounts is
+    /// not within the sysvar data.
+    #[inline(always)]
+    pub fn num_accounts(&self) -> Result<u16, SanitizeError> {
+        // The first 2 bytes represent the number of accounts in the instruction.
+        self.read_u16(0)
+    }
+
+    /// Return an iterator over the account metas of the `Instruction`.
+    ///
+    /// The iterator stops at the end of the sysvar data if the account metas
+    /// region is truncated, and is empty if the number of accounts itself is
+    /// not within the sysvar data.
+    #[inline(always)]
+    pub fn account_metas(&self) -> impl Iterator<Item = &IntrospectedAccountMeta> {
+        let num_accounts = self.read_u16(0).unwrap_or(0) as usize;
+        let len = num_accounts.min(self.available_account_metas());
+        // SAFETY: The indices are within the number of accounts and the account
+        // metas are within the sysvar data.
+        (0..len).map(|index| unsafe { self.get_account_meta_at_unchecked(index) })
+    }
+
+    /// Return an iterator over the writable account metas of the `Instruction`.
+    #[inline(always)]
+    pub fn writable_accounts(&self) -> impl Iterator<Item = &IntrospectedAccountMeta> {
+        self.account_metas().filter(|meta| meta.is_writable())
+    }
+
+    /// Return an iterator over the keys of the signer accounts of the
+    /// `Instruction`.
+    #[inline(always)]
+    pub fn signers(&self) -> impl Iterator<Item = &Pubkey> {
+        self.account_metas()
+            .filter(|meta| meta.is_signer())
+            .map(|meta| &meta.key)
+    }
+
+    /// Indicate whether `key` is one of the accounts of the `Instruction`.
+    #[inline]
+    pub fn contains_account(&self, key: &Pubkey) -> bool {
+        self.account_metas().any(|meta| pubkey_eq(&meta.key, key))
+    }
+
+    /// Indicate whether the accounts of the `Instruction` are `metas`, in the
+    /// same order and with the same signer and writable flags.
+    ///
+    /// Returns `false` if the account metas are not within the sysvar data.
+    #[inline]
+    pub fn accounts_eq(&self, metas: &[AccountMeta]) -> bool {
+        match self.read_u16(0) {
+            Ok(num_accounts) if num_accounts as usize == metas.len() => (),
+            _ => return false,
+        }
+
+        metas.len() <= self.available_account_metas()
+            && self.account_metas().zip(metas).all(|(introspected, meta)| {
+                introspected.flags == meta.flags() && pubkey_eq(&introspected.key, meta.pubkey)
+            })
+    }
+
     /// Get the program ID of the `Instruction`.
     #[inline(always)]
     pub fn get_program_id(&self) -> &Pubkey {
@@ -178,6 +436,18 @@ impl IntrospectedInstruction<'_> {
         }
     }
 
+    /// Indicate whether the `Instruction` targets the program `id`.
+    ///
+    /// The program ID is compared using [`pubkey_eq`], which is cheaper than
+    /// comparing the result of [`Self::get_program_id`] with `==`.
+    ///
+    /// Returns `false` if the program ID is not within the sysvar data.
+    #[inline(always)]
+    pub fn program_id_is(&self, id: &Pubkey) -> bool {
+        self.try_get_program_id()
+            .is_ok_and(|program_id| pubkey_eq(program_id, id))
+    }
+
     /// Get the instruction data of the `Instruction`.
     #[inline(always)]
     pub fn get_instruction_data(&self) -> &[u8] {
@@ -199,13 +469,213 @@ impl IntrospectedInstruction<'_> {
             )
         }
     }
+
+    /// Indicate whether the instruction data of the `Instruction` is `data`.
+    ///
+    /// Returns `false` if the instruction data is not within the sysvar data.
+    #[inline]
+    pub fn data_eq(&self, data: &[u8]) -> bool {
+        self.try_get_instruction_data()
+            .is_ok_and(|instruction_data| instruction_data == data)
+    }
+
+    /// Get the program ID of the `Instruction`, validating that it is within
+    /// the sysvar data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanitizeError::IndexOutOfBounds`] if the program ID is not
+    /// within the sysvar data.
+    #[inline]
+    pub fn try_get_program_id(&self) -> Result<&Pubkey, SanitizeError> {
+        let offset = self.program_id_offset()?;
+        self.check_range(offset, PUBKEY_BYTES)?;
+
+        // SAFETY: The program ID was checked to be within the sysvar data.
+        Ok(unsafe { &*(self.raw.add(offset) as *const Pubkey) })
+    }
+
+    /// Get the instruction data of the `Instruction`, validating that it is
+    /// within the sysvar data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanitizeError::IndexOutOfBounds`] if the instruction data is
+    /// not within the sysvar data.
+    #[inline]
+    pub fn try_get_instruction_data(&self) -> Result<&[u8], SanitizeError> {
+        let data_len_offset = self
+            .program_id_offset()?
+            .checked_add(PUBKEY_BYTES)
+            .ok_or(SanitizeError::IndexOutOfBounds)?;
+        let data_len = self.read_u16(data_len_offset)? as usize;
+
+        let data_offset = data_len_offset + size_of::<u16>();
+        self.check_range(data_offset, data_len)?;
+
+        // SAFETY: The instruction data was checked to be within the sysvar data.
+        Ok(unsafe { core::slice::from_raw_parts(self.raw.add(data_offset), data_len) })
+    }
+
+    /// Copy the program ID, account metas and data of the `Instruction` into an
+    /// [`OwnedInstruction`] that outlives the borrow of the sysvar data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanitizeError::IndexOutOfBounds`] if the instruction is not
+    /// within the sysvar data.
+    #[cfg(any(test, feature = "alloc"))]
+    pub fn to_instruction_owned(&self) -> Result<OwnedInstruction, SanitizeError> {
+        // The program ID follows the account metas, so this also validates
+        // that the metas are within the sysvar data.
+        let program_id = *self.try_get_program_id()?;
+        let accounts = self
+            .account_metas()
+            .map(|meta| (meta.key, meta.flags))
+            .collect();
+
+        Ok(OwnedInstruction {
+            program_id,
+            accounts,
+            data: self.try_get_instruction_data()?.to_vec(),
+        })
+    }
+
+    /// Return the offset of the program ID, which is located after the account
+    /// metas.
+    #[inline(always)]
+    fn program_id_offset(&self) -> Result<usize, SanitizeError> {
+        let num_accounts = self.read_u16(0)? as usize;
+
+        num_accounts
+            .checked_mul(IntrospectedAccountMeta::LEN)
+            .and_then(|len| len.checked_add(size_of::<u16>()))
+            .ok_or(SanitizeError::IndexOutOfBounds)
+    }
+
+    /// Return the number of account metas that fit in the sysvar data.
+    #[inline(always)]
+    fn available_account_metas(&self) -> usize {
+        self.len.saturating_sub(size_of::<u16>()) / IntrospectedAccountMeta::LEN
+    }
+
+    /// Read a `u16` at `offset`, validating that it is within the sysvar data.
+    #[inline(always)]
+    fn read_u16(&self, offset: usize) -> Result<u16, SanitizeError> {
+        self.check_range(offset, size_of::<u16>())?;
+        // SAFETY: The bytes were checked to be within the sysvar data.
+        Ok(u16::from_le_bytes(unsafe {
+            *(self.raw.add(offset) as *const [u8; 2])
+        }))
+    }
+
+    /// Indicate whether the length checks are only debug assertions, which is
+    /// the case for trusted data when the `trust-input` feature is enabled.
+    #[inline(always)]
+    fn is_trusted(&self) -> bool {
+        TRUST_INPUT && self.trusted
+    }
+
+    /// Check that `len` bytes starting at `offset` are within the sysvar data.
+    #[inline(always)]
+    fn check_range(&self, offset: usize, len: usize) -> Result<(), SanitizeError> {
+        if self.is_trusted() {
+            debug_assert!(offset.checked_add(len).is_some_and(|end| end <= self.len));
+            return Ok(());
+        }
+
+        match offset.checked_add(len) {
+            Some(end) if end <= self.len => Ok(()),
+            _ => Err(SanitizeError::IndexOutOfBounds),
+        }
+    }
+}
+
+/// An instruction that owns its program ID, account metas and data.
+#[cfg(any(test, feature = "alloc"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnedInstruction {
+    /// Public key of the program.
+    pub program_id: Pubkey,
+
+    /// Account keys and their signer and writable flags.
+    pub accounts: Vec<(Pubkey, MetaFlags)>,
+
+    /// Data of the instruction.
+    pub data: Vec<u8>,
+}
+
+#[cfg(any(test, feature = "alloc"))]
+impl OwnedInstruction {
+    /// Return the account metas of the instruction, borrowing the account keys.
+    ///
+    /// The metas can be used to build an [`Instruction`] together with the
+    /// program ID and data.
+    pub fn account_metas(&self) -> Vec<AccountMeta> {
+        self.accounts
+            .iter()
+            .map(|(key, flags)| AccountMeta::with_flags(key, *flags))
+            .collect()
+    }
+}
+
+/// Store the current instruction `index` in the instructions sysvar `data`.
+///
+/// The index is stored in the last 2 bytes of the data.
+///
+/// # Errors
+///
+/// Returns [`SanitizeError::IndexOutOfBounds`] if `data` is shorter than
+/// 2 bytes.
+#[inline]
+pub fn store_current_index(data: &mut [u8], index: u16) -> Result<(), SanitizeError> {
+    let last_index = data
+        .len()
+        .checked_sub(size_of::<u16>())
+        .ok_or(SanitizeError::IndexOutOfBounds)?;
+
+    data[last_index..].copy_from_slice(&index.to_le_bytes());
+    Ok(())
 }
 
-/// The bit positions for the signer flags in the `AccountMeta`.
-const IS_SIGNER: u8 = 0b00000001;
+/// Serialize `instructions` using the layout of the instructions sysvar data.
+///
+/// The current instruction index at the end of the data is set to `0`.
+///
+/// # Panics
+///
+/// Panics if there are more than `u16::MAX` instructions, accounts per
+/// instruction or bytes of instruction data, or if the serialized data does
+/// not fit the `u16` offsets.
+#[cfg(any(test, feature = "alloc"))]
+pub fn pack_instructions(instructions: &[Instruction]) -> Vec<u8> {
+    let to_u16 = |value: usize| u16::try_from(value).expect("value exceeds u16::MAX");
 
-/// The bit positions for the writable flags in the `AccountMeta`.
-const IS_WRITABLE: u8 = 0b00000010;
+    let mut data = Vec::new();
+    data.extend_from_slice(&to_u16(instructions.len()).to_le_bytes());
+    // offsets table, filled as the instructions are written
+    data.resize(size_of::<u16>() * (1 + instructions.len()), 0);
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        let offset = to_u16(data.len()).to_le_bytes();
+        let start = size_of::<u16>() * (1 + index);
+        data[start..start + size_of::<u16>()].copy_from_slice(&offset);
+
+        data.extend_from_slice(&to_u16(instruction.accounts.len()).to_le_bytes());
+        for account in instruction.accounts {
+            data.push(account.flags().bits());
+            data.extend_from_slice(account.pubkey);
+        }
+
+        data.extend_from_slice(instruction.program_id);
+        data.extend_from_slice(&to_u16(instruction.data.len()).to_le_bytes());
+        data.extend_from_slice(instruction.data);
+    }
+
+    // current instruction index
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data
+}
 
 #[repr(C)]
 #[derive(Clone, PartialEq, Eq)]
@@ -213,7 +683,7 @@ pub struct IntrospectedAccountMeta {
     /// Account flags:
     ///   * bit `0`: signer
     ///   * bit `1`: writable
-    flags: u8,
+    flags: MetaFlags,
 
     /// The account key.
     pub key: Pubkey,
@@ -222,21 +692,803 @@ pub struct IntrospectedAccountMeta {
 impl IntrospectedAccountMeta {
     const LEN: usize = core::mem::size_of::<Self>();
 
+    /// Return the signer and writable flags of the account.
+    #[inline(always)]
+    pub fn flags(&self) -> MetaFlags {
+        self.flags
+    }
+
     /// Indicate whether the account is writable or not.
     #[inline(always)]
     pub fn is_writable(&self) -> bool {
-        (self.flags & IS_WRITABLE) != 0
+        self.flags.writable()
     }
 
     /// Indicate whether the account is a signer or not.
     #[inline(always)]
     pub fn is_signer(&self) -> bool {
-        (self.flags & IS_SIGNER) != 0
+        self.flags.signer()
     }
 
     /// Convert the `IntrospectedAccountMeta` to an `AccountMeta`.
     #[inline(always)]
     pub fn to_account_meta(&self) -> AccountMeta {
-        AccountMeta::new(&self.key, self.is_writable(), self.is_signer())
+        AccountMeta::with_flags(&self.key, self.flags)
+    }
+}
+
+impl From<&IntrospectedAccountMeta> for MetaFlags {
+    #[inline(always)]
+    fn from(meta: &IntrospectedAccountMeta) -> Self {
+        meta.flags
+    }
+}
+
+/// A fixed-capacity map from account keys to instruction indices.
+///
+/// This allows a program to scan the instructions of the transaction once and
+/// then look up which instruction touches an account, instead of scanning the
+/// instructions again for every account. Entries are kept in insertion order
+/// and looked up with a linear scan, which is cheaper than hashing for the
+/// small number of accounts of a transaction. No allocation is required.
+pub struct PubkeyIndexMap<const N: usize> {
+    /// Keys of the entries; only the first `len` are used.
+    keys: [Pubkey; N],
+
+    /// Instruction indices of the entries; only the first `len` are used.
+    indices: [usize; N],
+
+    /// Number of entries in the map.
+    len: usize,
+}
+
+impl<const N: usize> PubkeyIndexMap<N> {
+    /// Creates a new empty `PubkeyIndexMap`.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            keys: [[0; PUBKEY_BYTES]; N],
+            indices: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Inserts the instruction `index` for `key`.
+    ///
+    /// If the map already holds `key`, its index is replaced and the previous
+    /// index is returned; the entry keeps its position in the map.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::InvalidArgument`] if `key` is not in the map and
+    /// the map already holds `N` entries.
+    #[inline]
+    pub fn insert(&mut self, key: &Pubkey, index: usize) -> Result<Option<usize>, ProgramError> {
+        if let Some(position) = self.position(key) {
+            return Ok(Some(core::mem::replace(&mut self.indices[position], index)));
+        }
+
+        if self.len == N {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        self.keys[self.len] = *key;
+        self.indices[self.len] = index;
+        self.len += 1;
+
+        Ok(None)
+    }
+
+    /// Returns the instruction index of `key`, if the map holds it.
+    #[inline]
+    pub fn get(&self, key: &Pubkey) -> Option<usize> {
+        self.position(key).map(|position| self.indices[position])
+    }
+
+    /// Indicates whether the map holds `key`.
+    #[inline]
+    pub fn contains_key(&self, key: &Pubkey) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Return an iterator over the keys and instruction indices of the map, in
+    /// insertion order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&Pubkey, usize)> {
+        self.keys[..self.len]
+            .iter()
+            .zip(self.indices[..self.len].iter().copied())
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Indicates whether the map is empty.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all entries from the map.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Returns the position of `key` in the map.
+    #[inline(always)]
+    fn position(&self, key: &Pubkey) -> Option<usize> {
+        self.keys[..self.len]
+            .iter()
+            .position(|entry| pubkey_eq(entry, key))
+    }
+}
+
+impl<const N: usize> Default for PubkeyIndexMap<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    /// Serialize a single instruction using the instructions sysvar layout,
+    /// with `num_accounts` written as given.
+    fn sysvar_data(
+        num_accounts: u16,
+        metas: &[(u8, Pubkey)],
+        program_id: &Pubkey,
+        ix_data: &[u8],
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        // number of instructions and offset of the first one
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&4u16.to_le_bytes());
+
+        data.extend_from_slice(&num_accounts.to_le_bytes());
+        for (flags, key) in metas {
+            data.push(*flags);
+            data.extend_from_slice(key);
+        }
+        data.extend_from_slice(program_id);
+        data.extend_from_slice(&(ix_data.len() as u16).to_le_bytes());
+        data.extend_from_slice(ix_data);
+
+        // current instruction index
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_checked_accessors() {
+        let data = sysvar_data(1, &[(MetaFlags::SIGNER, [1; 32])], &[2; 32], &[3, 4, 5]);
+        let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = instructions.load_instruction_at(0).unwrap();
+
+        assert_eq!(instruction.try_get_program_id(), Ok(&[2; 32]));
+        assert_eq!(instruction.try_get_instruction_data(), Ok(&[3, 4, 5][..]));
+        assert_eq!(instruction.get_instruction_data(), &[3, 4, 5]);
+        assert!(instruction.get_account_meta_at(0).unwrap().is_signer());
+    }
+
+    #[test]
+    fn test_checked_readers_match_unchecked() {
+        // The checked readers return the same values as the unchecked ones on
+        // well-formed data, whether or not the `trust-input` feature is enabled.
+        let keys = [[1; 32], [2; 32], [3; 32]];
+        let account_metas = [
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+            AccountMeta::writable(&keys[2]),
+        ];
+        let data = pack_instructions(&[
+            Instruction {
+                program_id: &[7; 32],
+                accounts: &account_metas,
+                data: &[1, 2, 3],
+            },
+            Instruction {
+                program_id: &[8; 32],
+                accounts: &[],
+                data: &[],
+            },
+        ]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+
+        for index in 0..2 {
+            let instruction = sysvar.load_instruction_at_checked(index).unwrap();
+            let unchecked = unsafe { sysvar.deserialize_instruction_unchecked(index) };
+
+            assert_eq!(
+                instruction.try_get_program_id(),
+                Ok(unchecked.get_program_id())
+            );
+            assert_eq!(
+                instruction.try_get_instruction_data(),
+                Ok(unchecked.get_instruction_data())
+            );
+
+            let num_accounts = instruction.num_accounts().unwrap() as usize;
+            for meta_index in 0..num_accounts {
+                let meta = instruction.get_account_meta_at(meta_index).unwrap();
+                assert!(meta == unsafe { unchecked.get_account_meta_at_unchecked(meta_index) });
+            }
+            assert_eq!(instruction.account_metas().count(), num_accounts);
+            assert!(instruction.get_account_meta_at(num_accounts).is_err());
+        }
+
+        let first = sysvar.load_instruction_at_checked(0).unwrap();
+        assert_eq!(first.try_get_program_id(), Ok(&[7; 32]));
+        assert_eq!(first.try_get_instruction_data(), Ok(&[1, 2, 3][..]));
+        assert!(first.accounts_eq(&account_metas));
+    }
+
+    #[test]
+    fn test_get_instruction_relative_ref() {
+        let program_ids = [[7; 32], [8; 32], [9; 32]];
+        let instructions: std::vec::Vec<_> = program_ids
+            .iter()
+            .enumerate()
+            .map(|(index, program_id)| Instruction {
+                program_id,
+                accounts: &[],
+                data: &[0, 1, 2][index..=index],
+            })
+            .collect();
+
+        let mut data = pack_instructions(&instructions);
+        store_current_index(&mut data, 1).unwrap();
+        let data = data.as_slice();
+
+        let previous = get_instruction_relative_ref(-1, data).unwrap();
+        let current = get_instruction_relative_ref(0, data).unwrap();
+        let next = get_instruction_relative_ref(1, data).unwrap();
+
+        // the instructions borrow the data and can be used together
+        for (instruction, (index, program_id)) in [&previous, &current, &next]
+            .iter()
+            .zip(program_ids.iter().enumerate())
+        {
+            assert_eq!(instruction.try_get_program_id(), Ok(program_id));
+            assert_eq!(instruction.get_instruction_data(), [index as u8]);
+        }
+
+        // the same instruction as loaded through `Instructions`
+        let sysvar = unsafe { Instructions::new_unchecked(data) };
+        assert!(sysvar.get_instruction_relative(1).unwrap() == next);
+
+        for index in [-2, 2, i64::MIN, i64::MAX] {
+            assert_eq!(
+                get_instruction_relative_ref(index, data).err(),
+                Some(ProgramError::InvalidInstructionData)
+            );
+        }
+
+        // malformed data
+        assert_eq!(
+            get_instruction_relative_ref(0, &[]).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            get_instruction_relative_ref(0, &[2, 0, 1, 0]).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            get_instruction_relative_ref(0, &[1, 0, 9, 0, 0, 0]).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_get_instruction_relative_ref_malformed_instruction() {
+        // The data is not known to come from the sysvar account, so the whole
+        // record is validated whether or not the `trust-input` feature is
+        // enabled.
+
+        // one instruction with 5 accounts, truncated after its number of
+        // accounts, followed by the current index
+        assert_eq!(
+            get_instruction_relative_ref(0, &[1, 0, 4, 0, 5, 0, 0, 0]).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+
+        // the offset of the instruction is the end of the data
+        assert_eq!(
+            get_instruction_relative_ref(0, &[1, 0, 6, 0, 0, 0]).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+
+        let data = sysvar_data(1, &[(MetaFlags::SIGNER, [1; 32])], &[2; 32], &[3, 4, 5]);
+        let instruction = get_instruction_relative_ref(0, &data).unwrap();
+        assert_eq!(instruction.get_program_id(), &[2; 32]);
+        assert_eq!(instruction.get_instruction_data(), [3, 4, 5]);
+
+        // a record truncated in its account metas, program ID, data length and
+        // before its data, followed by the current index
+        for len in [
+            4 + 2 + 20,
+            4 + 2 + 33 + 16,
+            4 + 2 + 33 + 32 + 1,
+            4 + 2 + 33 + 32 + 2,
+        ] {
+            let truncated = [&data[..len], &[0, 0]].concat();
+            assert_eq!(
+                get_instruction_relative_ref(0, &truncated).err(),
+                Some(ProgramError::InvalidArgument),
+                "len = {len}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_instruction_at_checked() {
+        let accounts = [[1; 32], [2; 32]];
+        let metas = [
+            AccountMeta::writable(&accounts[0]),
+            AccountMeta::readonly_signer(&accounts[1]),
+        ];
+        let instructions = [
+            Instruction {
+                program_id: &[7; 32],
+                accounts: &metas,
+                data: &[1, 2, 3, 4, 5],
+            },
+            Instruction {
+                program_id: &[8; 32],
+                accounts: &metas[1..],
+                data: &[6],
+            },
+        ];
+
+        let mut data = pack_instructions(&instructions);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+
+        // the second instruction follows the accounts and data of the first
+        let first_offset = 2 + 2 * 2;
+        let second_offset = first_offset + 2 + 2 * 33 + 32 + 2 + 5;
+        assert_eq!(&data[2..6], [first_offset as u8, 0, second_offset as u8, 0]);
+
+        for (index, instruction) in instructions.iter().enumerate() {
+            let loaded = sysvar.load_instruction_at_checked(index).unwrap();
+            assert_eq!(loaded.try_get_program_id(), Ok(instruction.program_id));
+            assert_eq!(loaded.try_get_instruction_data(), Ok(instruction.data));
+            assert_eq!(
+                loaded.get_account_meta_at(0).unwrap().key,
+                *instruction.accounts[0].pubkey
+            );
+            assert!(sysvar.load_instruction_at(index).unwrap() == loaded);
+        }
+
+        assert_eq!(
+            sysvar.load_instruction_at_checked(2).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+
+        // offsets past the end of the data or into the offset table
+        let len = data.len() as u16;
+        for offset in [len + 1, u16::MAX, 0, 4] {
+            data[4..6].copy_from_slice(&offset.to_le_bytes());
+            let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+            assert_eq!(
+                sysvar.load_instruction_at_checked(1).err(),
+                Some(ProgramError::InvalidArgument)
+            );
+        }
+
+        // the offset table is truncated
+        let sysvar = unsafe { Instructions::new_unchecked(&[9, 0, 4, 0][..]) };
+        assert_eq!(
+            sysvar.load_instruction_at_checked(1).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "trust-input"))]
+    fn test_truncated_account_metas() {
+        let metas = [(MetaFlags::SIGNER, [1; 32]), (MetaFlags::WRITABLE, [2; 32])];
+        let mut data = sysvar_data(2, &metas, &[3; 32], &[]);
+        // keep the first account meta and half of the second one
+        data.truncate(4 + 2 + 33 + 16);
+
+        let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = instructions.load_instruction_at(0).unwrap();
+
+        assert_eq!(instruction.num_accounts(), Ok(2));
+        assert_eq!(instruction.get_account_meta_at(0).unwrap().key, [1; 32]);
+        assert_eq!(
+            instruction.get_account_meta_at(1).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+        assert_eq!(instruction.account_metas().count(), 1);
+        assert!(!instruction.contains_account(&[2; 32]));
+        assert_eq!(
+            instruction.try_get_program_id(),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_program_id_is() {
+        let data = sysvar_data(1, &[(MetaFlags::SIGNER, [1; 32])], &[2; 32], &[]);
+        let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = instructions.load_instruction_at(0).unwrap();
+
+        assert!(instruction.program_id_is(&[2; 32]));
+        assert!(!instruction.program_id_is(&[1; 32]));
+
+        let mut other = [2; 32];
+        other[31] = 3;
+        assert!(!instruction.program_id_is(&other));
+
+        // an instruction truncated in its program ID
+        let truncated = IntrospectedInstruction {
+            raw: instruction.raw,
+            len: size_of::<u16>() + IntrospectedAccountMeta::LEN + 16,
+            trusted: false,
+            marker: PhantomData,
+        };
+        assert!(!truncated.program_id_is(&[2; 32]));
+    }
+
+    #[test]
+    fn test_store_current_index() {
+        let mut data = pack_instructions(&[]);
+        store_current_index(&mut data, 513).unwrap();
+
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        assert_eq!(sysvar.load_current_index(), 513);
+
+        assert_eq!(
+            store_current_index(&mut [0], 1),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            store_current_index(&mut [], 1),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    fn test_find_first() {
+        let program_ids = [[7; 32], [8; 32], [9; 32]];
+        let instructions = [
+            Instruction {
+                program_id: &program_ids[0],
+                accounts: &[],
+                data: &[0],
+            },
+            Instruction {
+                program_id: &program_ids[1],
+                accounts: &[],
+                data: &[1],
+            },
+            Instruction {
+                program_id: &program_ids[2],
+                accounts: &[],
+                data: &[2],
+            },
+            Instruction {
+                program_id: &program_ids[1],
+                accounts: &[],
+                data: &[3],
+            },
+        ];
+
+        let data = pack_instructions(&instructions);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+
+        for (expected, program_id) in program_ids.iter().enumerate() {
+            let (index, instruction) = sysvar.find_first(program_id).unwrap().unwrap();
+            // the first of the matching instructions is returned
+            assert_eq!(index, expected);
+            assert_eq!(instruction.get_instruction_data(), [expected as u8]);
+        }
+
+        assert!(sysvar.find_first(&[1; 32]).unwrap().is_none());
+
+        let data = pack_instructions(&[]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        assert!(sysvar.find_first(&program_ids[0]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_to_instruction_owned() {
+        let keys = [[1; 32], [2; 32]];
+        let program_ids = [[7; 32], [8; 32]];
+        let account_metas = [
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+        ];
+        let instructions = [
+            Instruction {
+                program_id: &program_ids[0],
+                accounts: &account_metas,
+                data: &[1, 2, 3],
+            },
+            Instruction {
+                program_id: &program_ids[1],
+                accounts: &[],
+                data: &[],
+            },
+        ];
+
+        let data = pack_instructions(&instructions);
+        let owned = {
+            let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+            [
+                sysvar
+                    .load_instruction_at(0)
+                    .unwrap()
+                    .to_instruction_owned(),
+                sysvar
+                    .load_instruction_at(1)
+                    .unwrap()
+                    .to_instruction_owned(),
+            ]
+        };
+        // the owned instructions outlive the sysvar data
+        drop(data);
+
+        let [first, second] = owned.map(Result::unwrap);
+
+        assert_eq!(first.program_id, program_ids[0]);
+        assert_eq!(first.data, [1, 2, 3]);
+        assert_eq!(
+            first.accounts,
+            [
+                (keys[0], MetaFlags::new(true, true)),
+                (keys[1], MetaFlags::new(false, false))
+            ]
+        );
+        for (meta, expected) in first.account_metas().iter().zip(account_metas.iter()) {
+            assert_eq!(meta.pubkey, expected.pubkey);
+            assert_eq!(meta.flags(), expected.flags());
+        }
+
+        assert_eq!(second.program_id, program_ids[1]);
+        assert!(second.accounts.is_empty());
+        assert!(second.data.is_empty());
+    }
+
+    #[test]
+    fn test_contains_account() {
+        let keys = [[1; 32], [2; 32], [3; 32]];
+        let account_metas = [
+            AccountMeta::readonly_signer(&keys[0]),
+            AccountMeta::writable(&keys[1]),
+            AccountMeta::writable_signer(&keys[2]),
+        ];
+        let data = pack_instructions(&[Instruction {
+            program_id: &[7; 32],
+            accounts: &account_metas,
+            data: &[],
+        }]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = sysvar.load_instruction_at(0).unwrap();
+
+        assert_eq!(instruction.num_accounts(), Ok(3));
+        assert!(instruction.contains_account(&keys[0]));
+        assert!(instruction.contains_account(&keys[2]));
+        assert!(!instruction.contains_account(&[4; 32]));
+        // the program ID is not an account of the instruction
+        assert!(!instruction.contains_account(&[7; 32]));
+
+        let writable: Vec<_> = instruction
+            .writable_accounts()
+            .map(|meta| meta.key)
+            .collect();
+        assert_eq!(writable, [keys[1], keys[2]]);
+    }
+
+    #[test]
+    fn test_data_and_accounts_eq() {
+        let keys = [[1; 32], [2; 32]];
+        let account_metas = [
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+        ];
+        let data = pack_instructions(&[Instruction {
+            program_id: &[7; 32],
+            accounts: &account_metas,
+            data: &[1, 2, 3],
+        }]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = sysvar.load_instruction_at(0).unwrap();
+
+        assert!(instruction.data_eq(&[1, 2, 3]));
+        assert!(!instruction.data_eq(&[1, 2]));
+        assert!(!instruction.data_eq(&[1, 2, 4]));
+        assert!(!instruction.data_eq(&[]));
+
+        assert!(instruction.accounts_eq(&account_metas));
+        // different flags
+        assert!(!instruction.accounts_eq(&[
+            AccountMeta::writable(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+        ]));
+        // different order
+        assert!(!instruction.accounts_eq(&[
+            AccountMeta::readonly(&keys[1]),
+            AccountMeta::writable_signer(&keys[0]),
+        ]));
+        // different keys
+        assert!(!instruction.accounts_eq(&[
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&[3; 32]),
+        ]));
+        // missing and extra accounts
+        assert!(!instruction.accounts_eq(&account_metas[..1]));
+        assert!(!instruction.accounts_eq(&[
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+            AccountMeta::readonly(&keys[1]),
+        ]));
+
+        // an instruction truncated in the middle of its account metas
+        let truncated = IntrospectedInstruction {
+            raw: instruction.raw,
+            len: size_of::<u16>() + IntrospectedAccountMeta::LEN,
+            trusted: false,
+            marker: PhantomData,
+        };
+        assert!(!truncated.accounts_eq(&account_metas));
+        assert!(!truncated.data_eq(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_pubkey_index_map() {
+        let keys = [[1; 32], [2; 32], [3; 32]];
+        let first = [
+            AccountMeta::writable_signer(&keys[0]),
+            AccountMeta::readonly(&keys[1]),
+        ];
+        let second = [AccountMeta::writable(&keys[2])];
+        let data = pack_instructions(&[
+            Instruction {
+                program_id: &[7; 32],
+                accounts: &first,
+                data: &[],
+            },
+            Instruction {
+                program_id: &[8; 32],
+                accounts: &second,
+                data: &[],
+            },
+        ]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+
+        let mut map = PubkeyIndexMap::<3>::new();
+        assert!(map.is_empty());
+
+        for index in 0..2 {
+            let instruction = sysvar.load_instruction_at_checked(index).unwrap();
+            for meta in instruction.account_metas() {
+                assert_eq!(map.insert(&meta.key, index), Ok(None));
+            }
+        }
+
+        // lookup hits
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&keys[0]), Some(0));
+        assert_eq!(map.get(&keys[1]), Some(0));
+        assert_eq!(map.get(&keys[2]), Some(1));
+        assert!(map.contains_key(&keys[2]));
+
+        // lookup misses
+        assert_eq!(map.get(&[4; 32]), None);
+        assert!(!map.contains_key(&[7; 32]));
+
+        // inserting an existing key replaces its index in place
+        assert_eq!(map.insert(&keys[0], 5), Ok(Some(0)));
+        let entries: Vec<_> = map.iter().collect();
+        assert_eq!(entries, [(&keys[0], 5), (&keys[1], 0), (&keys[2], 1)]);
+
+        // the map is full
+        assert_eq!(map.insert(&[4; 32], 2), Err(ProgramError::InvalidArgument));
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&[4; 32]), None);
+        // existing keys can still be updated
+        assert_eq!(map.insert(&keys[1], 2), Ok(Some(0)));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&keys[0]), None);
+        assert_eq!(map.insert(&[4; 32], 2), Ok(None));
+    }
+
+    #[test]
+    fn test_signers() {
+        let keys = [[1; 32], [2; 32], [3; 32], [4; 32]];
+        let account_metas = [
+            AccountMeta::writable(&keys[0]),
+            AccountMeta::readonly_signer(&keys[1]),
+            AccountMeta::readonly(&keys[2]),
+            AccountMeta::writable_signer(&keys[3]),
+        ];
+        let data = pack_instructions(&[Instruction {
+            program_id: &[7; 32],
+            accounts: &account_metas,
+            data: &[],
+        }]);
+        let sysvar = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = sysvar.load_instruction_at(0).unwrap();
+
+        let metas: Vec<_> = instruction.account_metas().map(|meta| meta.key).collect();
+        assert_eq!(metas, keys);
+
+        let signers: Vec<_> = instruction.signers().collect();
+        assert_eq!(signers, [&keys[1], &keys[3]]);
+        assert!(instruction.signers().any(|key| key == &keys[3]));
+        assert!(!instruction.signers().any(|key| key == &keys[0]));
+
+        // an instruction without the room for its number of accounts
+        let truncated = IntrospectedInstruction {
+            raw: data.as_ptr(),
+            len: 1,
+            trusted: false,
+            marker: PhantomData,
+        };
+        assert_eq!(truncated.account_metas().count(), 0);
+        assert_eq!(truncated.signers().count(), 0);
+    }
+
+    #[test]
+    #[cfg(not(feature = "trust-input"))]
+    fn test_empty_instruction() {
+        // the offset of the instruction is the end of the sysvar data
+        let sysvar = unsafe { Instructions::new_unchecked(&[1, 0, 4, 0][..]) };
+        let instruction = sysvar.load_instruction_at_checked(0).unwrap();
+
+        assert_eq!(
+            instruction.num_accounts(),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
+        assert_eq!(instruction.account_metas().count(), 0);
+        assert!(!instruction.contains_account(&[0; 32]));
+    }
+
+    #[test]
+    #[cfg(not(feature = "trust-input"))]
+    fn test_oversized_num_accounts() {
+        let data = sysvar_data(u16::MAX, &[(0, [1; 32])], &[2; 32], &[3, 4, 5]);
+        let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = instructions.load_instruction_at(0).unwrap();
+
+        assert_eq!(
+            instruction.try_get_program_id(),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
+        assert_eq!(
+            instruction.try_get_instruction_data(),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "trust-input"))]
+    fn test_oversized_data_len() {
+        let mut data = sysvar_data(0, &[], &[2; 32], &[3, 4, 5]);
+        // instruction data length
+        let offset = 4 + 2 + 32;
+        data[offset..offset + 2].copy_from_slice(&100u16.to_le_bytes());
+
+        let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+        let instruction = instructions.load_instruction_at(0).unwrap();
+
+        assert_eq!(instruction.try_get_program_id(), Ok(&[2; 32]));
+        assert_eq!(
+            instruction.try_get_instruction_data(),
+            Err(SanitizeError::IndexOutOfBounds)
+        );
     }
 }
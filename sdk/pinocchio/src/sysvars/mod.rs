@@ -1,11 +1,24 @@
 //! Provides access to cluster system accounts.
 
-use crate::program_error::ProgramError;
+#[cfg(not(target_os = "solana"))]
+use crate::mock as syscalls;
+#[cfg(target_os = "solana")]
+use crate::syscalls;
+use crate::{program_error::ProgramError, pubkey::Pubkey};
 
 pub mod clock;
 pub mod fees;
+pub mod ids;
 pub mod instructions;
 pub mod rent;
+pub mod slot_history;
+
+/// Return value of `sol_get_sysvar` when the requested range exceeds the
+/// sysvar data.
+pub(crate) const OFFSET_LENGTH_EXCEEDS_SYSVAR: u64 = 1;
+
+/// Return value of `sol_get_sysvar` when the sysvar is not found.
+pub(crate) const SYSVAR_NOT_FOUND: u64 = 2;
 
 /// A type that holds sysvar data.
 pub trait Sysvar: Default + Sized {
@@ -34,7 +47,7 @@ macro_rules! impl_sysvar_get {
             let result = unsafe { $crate::syscalls::$syscall_name(var_addr) };
 
             #[cfg(not(target_os = "solana"))]
-            let result = core::hint::black_box(var_addr as *const _ as u64);
+            let result = unsafe { $crate::mock::$syscall_name(var_addr) };
 
             match result {
                 $crate::SUCCESS => Ok(var),
@@ -43,3 +56,79 @@ macro_rules! impl_sysvar_get {
         }
     };
 }
+
+/// Copy a range of the data of the sysvar `sysvar_id` into `buf`.
+///
+/// The range starts at `offset` and has the length of `buf`. This reads the
+/// sysvar directly from the runtime, so the sysvar account does not need to
+/// be passed to the program and any sysvar can be read, including those
+/// without a dedicated syscall.
+///
+/// On non-Solana targets the data is read from the sysvars set with
+/// `mock::set_sysvar` when either the `std` feature or `cfg(test)` is
+/// enabled.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the range exceeds the sysvar
+/// data, or [`ProgramError::UnsupportedSysvar`] if the sysvar is not found.
+#[inline]
+pub fn get_sysvar(sysvar_id: &Pubkey, offset: u64, buf: &mut [u8]) -> Result<(), ProgramError> {
+    let result = unsafe {
+        syscalls::sol_get_sysvar(
+            sysvar_id as *const _ as *const u8,
+            buf.as_mut_ptr(),
+            offset,
+            buf.len() as u64,
+        )
+    };
+
+    match result {
+        crate::SUCCESS => Ok(()),
+        OFFSET_LENGTH_EXCEEDS_SYSVAR => Err(ProgramError::InvalidArgument),
+        SYSVAR_NOT_FOUND => Err(ProgramError::UnsupportedSysvar),
+        error => Err(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_sysvar() {
+        let id = [9; 32];
+        crate::mock::set_sysvar(&id, &[0, 1, 2, 3, 4, 5, 6, 7]);
+
+        let mut buf = [0; 3];
+        assert_eq!(get_sysvar(&id, 2, &mut buf), Ok(()));
+        assert_eq!(buf, [2, 3, 4]);
+
+        let mut buf = [0; 8];
+        assert_eq!(get_sysvar(&id, 0, &mut buf), Ok(()));
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+        // Range exceeds the sysvar data.
+        let mut buf = [0; 3];
+        assert_eq!(
+            get_sysvar(&id, 6, &mut buf),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            get_sysvar(&id, u64::MAX, &mut buf),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        // Unknown sysvar.
+        assert_eq!(
+            get_sysvar(&[8; 32], 0, &mut buf),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+
+        crate::mock::reset();
+        assert_eq!(
+            get_sysvar(&id, 0, &mut buf),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+    }
+}
@@ -7,14 +7,9 @@ use crate::{
     account_info::{AccountInfo, Ref},
     impl_sysvar_get,
     program_error::ProgramError,
-    pubkey::Pubkey,
 };
 
-/// The ID of the rent sysvar.
-pub const RENT_ID: Pubkey = [
-    6, 167, 213, 23, 25, 44, 92, 81, 33, 140, 201, 76, 61, 74, 241, 127, 88, 218, 238, 8, 155, 161,
-    253, 68, 227, 219, 217, 138, 0, 0, 0, 0,
-];
+pub use super::ids::RENT_ID;
 
 /// Default rental rate in lamports/byte-year.
 ///
@@ -73,7 +68,7 @@ impl Rent {
     /// This method performs a check on the account info key.
     #[inline]
     pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<Rent>, ProgramError> {
-        if account_info.key() != &RENT_ID {
+        if !account_info.key_eq(&RENT_ID) {
             return Err(ProgramError::InvalidArgument);
         }
         Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
@@ -94,7 +89,7 @@ impl Rent {
     pub unsafe fn from_account_info_unchecked(
         account_info: &AccountInfo,
     ) -> Result<&Self, ProgramError> {
-        if account_info.key() != &RENT_ID {
+        if !account_info.key_eq(&RENT_ID) {
             return Err(ProgramError::InvalidArgument);
         }
         Ok(Self::from_bytes_unchecked(
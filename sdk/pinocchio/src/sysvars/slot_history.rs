@@ -0,0 +1,274 @@
+//! Bit-vector of the slots present over the most recent epoch.
+//!
+//! The sysvar is serialized as a bit-vector of [`MAX_ENTRIES`] bits, followed by
+//! the number of bits and the next slot to be recorded. Slot `s` is stored at
+//! bit `s % MAX_ENTRIES`, so the bit-vector works as a ring buffer covering the
+//! slots in the range `[next_slot - MAX_ENTRIES, next_slot)`.
+
+use core::ops::Deref;
+
+use crate::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    sysvars::{clock::Slot, get_sysvar},
+};
+
+pub use super::ids::SLOT_HISTORY_ID;
+
+/// Number of slots tracked by the slot history.
+pub const MAX_ENTRIES: u64 = 1024 * 1024;
+
+/// Number of bits in each block of the bit-vector.
+const BITS_PER_BLOCK: u64 = u64::BITS as u64;
+
+/// Number of blocks in the bit-vector.
+const BLOCKS_LEN: usize = (MAX_ENTRIES / BITS_PER_BLOCK) as usize;
+
+/// Offset of the first block of the bit-vector.
+///
+/// The blocks are preceded by the `Option` tag and the number of blocks.
+const BLOCKS_OFFSET: usize = 1 + 8;
+
+/// Offset of the number of bits in the bit-vector.
+const BITS_LEN_OFFSET: usize = BLOCKS_OFFSET + BLOCKS_LEN * 8;
+
+/// Offset of the next slot.
+const NEXT_SLOT_OFFSET: usize = BITS_LEN_OFFSET + 8;
+
+/// Result of checking whether a slot is present in the slot history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    /// The slot is newer than the most recent slot recorded.
+    Future,
+
+    /// The slot is older than the oldest slot tracked.
+    TooOld,
+
+    /// The slot is in the history.
+    Found,
+
+    /// The slot is within the tracked range but was not recorded (e.g., it
+    /// was skipped).
+    NotFound,
+}
+
+/// View over the slot history sysvar account data.
+///
+/// The data is read in place, without copying or allocating the bit-vector.
+pub struct SlotHistory<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    data: T,
+}
+
+impl<'a> SlotHistory<Ref<'a, [u8]>> {
+    /// Return a `SlotHistory` from the given account info.
+    ///
+    /// This method performs a check on the account info key.
+    #[inline]
+    pub fn from_account_info(account_info: &'a AccountInfo) -> Result<Self, ProgramError> {
+        if !account_info.key_eq(&SLOT_HISTORY_ID) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Self::new(account_info.try_borrow_data()?)
+    }
+}
+
+impl<T> SlotHistory<T>
+where
+    T: Deref<Target = [u8]>,
+{
+    /// The length of the `SlotHistory` sysvar account data.
+    pub const LEN: usize = NEXT_SLOT_OFFSET + 8;
+
+    /// Creates a new `SlotHistory` from the sysvar account data.
+    ///
+    /// This method validates the length of the data and the size of the
+    /// bit-vector.
+    #[inline]
+    pub fn new(data: T) -> Result<Self, ProgramError> {
+        if data.len() != Self::LEN
+            || data[0] != 1
+            || read_u64(&data, 1) != BLOCKS_LEN as u64
+            || read_u64(&data, BITS_LEN_OFFSET) != MAX_ENTRIES
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(Self { data })
+    }
+
+    /// Return the next slot to be recorded.
+    #[inline(always)]
+    pub fn next_slot(&self) -> Slot {
+        read_u64(&self.data, NEXT_SLOT_OFFSET)
+    }
+
+    /// Return the most recent slot recorded, if any.
+    #[inline(always)]
+    pub fn newest(&self) -> Option<Slot> {
+        self.next_slot().checked_sub(1)
+    }
+
+    /// Return the oldest slot tracked by the history.
+    #[inline(always)]
+    pub fn oldest(&self) -> Slot {
+        self.next_slot().saturating_sub(MAX_ENTRIES)
+    }
+
+    /// Check whether `slot` is present in the history.
+    #[inline]
+    pub fn check(&self, slot: Slot) -> Check {
+        // Reading from the (validated) account data cannot fail.
+        check(self.next_slot(), slot, |offset| {
+            Ok(read_u64(&self.data, offset))
+        })
+        .unwrap_or(Check::NotFound)
+    }
+}
+
+/// Check whether `slot` is present in the slot history, reading the sysvar
+/// directly from the runtime.
+///
+/// Only the next slot and the block of the bit-vector holding `slot` are read,
+/// so the sysvar account does not need to be passed to the program.
+#[inline]
+pub fn check_slot(slot: Slot) -> Result<Check, ProgramError> {
+    let read_u64 = |offset: usize| {
+        let mut bytes = [0u8; 8];
+        get_sysvar(&SLOT_HISTORY_ID, offset as u64, &mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    };
+
+    check(read_u64(NEXT_SLOT_OFFSET)?, slot, read_u64)
+}
+
+/// Check whether `slot` is present in a slot history with the given
+/// `next_slot`, using `read_u64` to read the block of the bit-vector at an
+/// offset of the sysvar data.
+#[inline(always)]
+fn check(
+    next_slot: Slot,
+    slot: Slot,
+    read_u64: impl FnOnce(usize) -> Result<u64, ProgramError>,
+) -> Result<Check, ProgramError> {
+    match next_slot.checked_sub(1) {
+        Some(newest) if slot <= newest => {}
+        _ => return Ok(Check::Future),
+    }
+
+    if slot < next_slot.saturating_sub(MAX_ENTRIES) {
+        return Ok(Check::TooOld);
+    }
+
+    let index = slot % MAX_ENTRIES;
+    let block = read_u64(BLOCKS_OFFSET + (index / BITS_PER_BLOCK) as usize * 8)?;
+
+    if block & (1 << (index % BITS_PER_BLOCK)) != 0 {
+        Ok(Check::Found)
+    } else {
+        Ok(Check::NotFound)
+    }
+}
+
+/// Read a little-endian `u64` at `offset`.
+///
+/// The caller must ensure that `data` has at least `offset + 8` bytes.
+#[inline(always)]
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use super::*;
+
+    /// Build the sysvar data with the given `slots` recorded.
+    fn slot_history(slots: &[Slot], next_slot: Slot) -> Vec<u8> {
+        let mut data = vec![0u8; SlotHistory::<&[u8]>::LEN];
+        data[0] = 1;
+        data[1..BLOCKS_OFFSET].copy_from_slice(&(BLOCKS_LEN as u64).to_le_bytes());
+        data[BITS_LEN_OFFSET..NEXT_SLOT_OFFSET].copy_from_slice(&MAX_ENTRIES.to_le_bytes());
+        data[NEXT_SLOT_OFFSET..].copy_from_slice(&next_slot.to_le_bytes());
+
+        for slot in slots {
+            let index = slot % MAX_ENTRIES;
+            data[BLOCKS_OFFSET + (index / 8) as usize] |= 1 << (index % 8);
+        }
+
+        data
+    }
+
+    #[test]
+    fn test_check() {
+        let data = slot_history(&[0, 63, 64, 1000], 1001);
+        let history = SlotHistory::new(data.as_slice()).unwrap();
+
+        assert_eq!(history.newest(), Some(1000));
+        assert_eq!(history.oldest(), 0);
+
+        assert_eq!(history.check(0), Check::Found);
+        assert_eq!(history.check(63), Check::Found);
+        assert_eq!(history.check(64), Check::Found);
+        assert_eq!(history.check(1000), Check::Found);
+        assert_eq!(history.check(1), Check::NotFound);
+        assert_eq!(history.check(999), Check::NotFound);
+        assert_eq!(history.check(1001), Check::Future);
+    }
+
+    #[test]
+    fn test_check_wraparound() {
+        // the window covers `[MAX_ENTRIES + 10, 2 * MAX_ENTRIES + 10)`, so the
+        // newest slots wrap around to the start of the bit-vector
+        let next_slot = 2 * MAX_ENTRIES + 10;
+        let data = slot_history(&[MAX_ENTRIES + 10, 2 * MAX_ENTRIES + 5], next_slot);
+        let history = SlotHistory::new(data.as_slice()).unwrap();
+
+        assert_eq!(history.oldest(), MAX_ENTRIES + 10);
+        assert_eq!(history.check(MAX_ENTRIES + 9), Check::TooOld);
+        assert_eq!(history.check(MAX_ENTRIES + 10), Check::Found);
+        assert_eq!(history.check(2 * MAX_ENTRIES + 5), Check::Found);
+        assert_eq!(history.check(2 * MAX_ENTRIES + 6), Check::NotFound);
+        assert_eq!(history.check(next_slot), Check::Future);
+        // slot 5 shares its bit with the recorded slot `2 * MAX_ENTRIES + 5`
+        assert_eq!(history.check(5), Check::TooOld);
+    }
+
+    #[test]
+    fn test_check_slot() {
+        let data = slot_history(&[3, 10], 11);
+        crate::mock::set_sysvar(&SLOT_HISTORY_ID, &data);
+
+        assert_eq!(check_slot(3), Ok(Check::Found));
+        assert_eq!(check_slot(4), Ok(Check::NotFound));
+        assert_eq!(check_slot(10), Ok(Check::Found));
+        assert_eq!(check_slot(11), Ok(Check::Future));
+
+        crate::mock::reset();
+        assert_eq!(check_slot(3), Err(ProgramError::UnsupportedSysvar));
+    }
+
+    #[test]
+    fn test_empty() {
+        let data = slot_history(&[], 0);
+        let history = SlotHistory::new(data.as_slice()).unwrap();
+
+        assert_eq!(history.newest(), None);
+        assert_eq!(history.check(0), Check::Future);
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let mut data = slot_history(&[], 1);
+        assert!(SlotHistory::new(&data[..data.len() - 1]).is_err());
+
+        data[0] = 0;
+        assert!(SlotHistory::new(data.as_slice()).is_err());
+    }
+}
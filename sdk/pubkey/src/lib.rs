@@ -40,3 +40,89 @@ macro_rules! declare_id {
 pub const fn from_str(value: &str) -> pinocchio::pubkey::Pubkey {
     decode_32_const(value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::from_str;
+    use pinocchio::sysvars::{clock::CLOCK_ID, instructions::INSTRUCTIONS_ID};
+
+    #[test]
+    fn test_pubkey() {
+        const INSTRUCTIONS: pinocchio::pubkey::Pubkey =
+            pubkey!("Sysvar1nstructions1111111111111111111111111");
+        const CLOCK: pinocchio::pubkey::Pubkey =
+            pubkey!("SysvarC1ock11111111111111111111111111111111");
+
+        assert_eq!(INSTRUCTIONS, INSTRUCTIONS_ID);
+        assert_eq!(CLOCK, CLOCK_ID);
+        assert_eq!(pubkey!("11111111111111111111111111111111"), [0; 32]);
+    }
+
+    #[test]
+    fn test_sysvar_ids() {
+        use pinocchio::sysvars::ids::*;
+
+        for (id, address) in [
+            (
+                CLOCK_ID,
+                pubkey!("SysvarC1ock11111111111111111111111111111111"),
+            ),
+            (
+                EPOCH_REWARDS_ID,
+                pubkey!("SysvarEpochRewards1111111111111111111111111"),
+            ),
+            (
+                EPOCH_SCHEDULE_ID,
+                pubkey!("SysvarEpochSchedu1e111111111111111111111111"),
+            ),
+            (
+                FEES_ID,
+                pubkey!("SysvarFees111111111111111111111111111111111"),
+            ),
+            (
+                INSTRUCTIONS_ID,
+                pubkey!("Sysvar1nstructions1111111111111111111111111"),
+            ),
+            (
+                LAST_RESTART_SLOT_ID,
+                pubkey!("SysvarLastRestartS1ot1111111111111111111111"),
+            ),
+            (
+                RECENT_BLOCKHASHES_ID,
+                pubkey!("SysvarRecentB1ockHashes11111111111111111111"),
+            ),
+            (
+                RENT_ID,
+                pubkey!("SysvarRent111111111111111111111111111111111"),
+            ),
+            (
+                SLOT_HASHES_ID,
+                pubkey!("SysvarS1otHashes111111111111111111111111111"),
+            ),
+            (
+                SLOT_HISTORY_ID,
+                pubkey!("SysvarS1otHistory11111111111111111111111111"),
+            ),
+            (
+                STAKE_HISTORY_ID,
+                pubkey!("SysvarStakeHistory1111111111111111111111111"),
+            ),
+        ] {
+            assert_eq!(id, address);
+        }
+    }
+
+    mod program {
+        declare_id!("Ping111111111111111111111111111111111111111");
+    }
+
+    #[test]
+    fn test_declare_id() {
+        assert!(program::check_id(&program::id()));
+        assert_eq!(
+            program::ID,
+            from_str("Ping111111111111111111111111111111111111111")
+        );
+        assert!(!program::check_id(&[0; 32]));
+    }
+}